@@ -0,0 +1,196 @@
+use crate::schemas::{AggregationType, AggregationResult};
+use crate::decimal::{Decimal, MAX_DECIMAL_SCALE};
+use crate::timestamp::Timestamp;
+
+
+/// The amount of fractional digits an average of decimals has in addition to the biggest scale of the values.
+const DECIMAL_AVG_EXTRA_SCALE: u32 = 4;
+
+
+/// Aggregates integer values. Sums are computed with 128 bit integers so that they can't overflow.
+/// 
+/// # Arguments:
+/// * `aggregation`: The aggregation to compute.
+/// * `values`: The values to aggregate.
+/// 
+/// # Returns:
+/// The aggregation result, `AggregationResult::None` if the aggregation isn't defined on zero values (AVG, MIN, MAX).
+pub fn aggregate_ints(aggregation: AggregationType, values: impl Iterator<Item = i64>) -> AggregationResult {
+    let mut count: usize = 0;
+    let mut sum: i128 = 0;
+    let mut min: Option<i64> = None;
+    let mut max: Option<i64> = None;
+
+    for value in values {
+        count += 1;
+        sum += value as i128;
+        min = Some(min.map_or(value, |min| min.min(value)));
+        max = Some(max.map_or(value, |max| max.max(value)));
+    }
+
+    match aggregation {
+        AggregationType::Count => AggregationResult::Int(count as i128),
+        AggregationType::Sum => AggregationResult::Int(sum),
+        AggregationType::Avg if count > 0 => AggregationResult::Float(sum as f64 / count as f64),
+        AggregationType::Min if count > 0 => AggregationResult::Int(min.unwrap() as i128),
+        AggregationType::Max if count > 0 => AggregationResult::Int(max.unwrap() as i128),
+        _ => AggregationResult::None,
+    }
+}
+
+/// Aggregates float values. Sums are computed with 64 bit floats.
+/// 
+/// # Arguments:
+/// * `aggregation`: The aggregation to compute.
+/// * `values`: The values to aggregate.
+/// 
+/// # Returns:
+/// The aggregation result, `AggregationResult::None` if the aggregation isn't defined on zero values (AVG, MIN, MAX).
+pub fn aggregate_floats(aggregation: AggregationType, values: impl Iterator<Item = f64>) -> AggregationResult {
+    let mut count: usize = 0;
+    let mut sum: f64 = 0.0;
+    let mut min: Option<f64> = None;
+    let mut max: Option<f64> = None;
+
+    for value in values {
+        count += 1;
+        sum += value;
+        min = Some(min.map_or(value, |min| min.min(value)));
+        max = Some(max.map_or(value, |max| max.max(value)));
+    }
+
+    match aggregation {
+        AggregationType::Count => AggregationResult::Int(count as i128),
+        AggregationType::Sum => AggregationResult::Float(sum),
+        AggregationType::Avg if count > 0 => AggregationResult::Float(sum / count as f64),
+        AggregationType::Min if count > 0 => AggregationResult::Float(min.unwrap()),
+        AggregationType::Max if count > 0 => AggregationResult::Float(max.unwrap()),
+        _ => AggregationResult::None,
+    }
+}
+
+/// Aggregates decimal values exactly. Sums keep the biggest scale of the values, averages are rounded half away from zero.
+/// 
+/// # Arguments:
+/// * `aggregation`: The aggregation to compute.
+/// * `values`: The values to aggregate.
+/// 
+/// # Returns:
+/// The aggregation result, `AggregationResult::None` if the aggregation isn't defined on zero values (AVG, MIN, MAX) or the sum overflows.
+pub fn aggregate_decimals(aggregation: AggregationType, values: impl Iterator<Item = Decimal>) -> AggregationResult {
+    let mut count: usize = 0;
+    let mut sum: Option<Decimal> = Some(Decimal::new(0, 0));
+    let mut min: Option<Decimal> = None;
+    let mut max: Option<Decimal> = None;
+
+    for value in values {
+        count += 1;
+        sum = sum.and_then(|sum| sum.checked_add(&value));
+        min = Some(min.map_or(value, |min| min.min(value)));
+        max = Some(max.map_or(value, |max| max.max(value)));
+    }
+
+    let average = |sum: Decimal| {
+        let scale: u32 = (sum.scale() + DECIMAL_AVG_EXTRA_SCALE).min(MAX_DECIMAL_SCALE).max(sum.scale());
+        sum.checked_div(count as i128, scale)
+    };
+
+    match (aggregation, sum) {
+        (AggregationType::Count, _) => AggregationResult::Int(count as i128),
+        (AggregationType::Sum, Some(sum)) => AggregationResult::Decimal(sum),
+        (AggregationType::Avg, Some(sum)) if count > 0 => average(sum).map_or(AggregationResult::None, AggregationResult::Decimal),
+        (AggregationType::Min, _) if count > 0 => AggregationResult::Decimal(min.unwrap()),
+        (AggregationType::Max, _) if count > 0 => AggregationResult::Decimal(max.unwrap()),
+        _ => AggregationResult::None,
+    }
+}
+
+/// Aggregates timestamps. Only MIN, MAX and COUNT are defined on instants, SUM and AVG return `AggregationResult::None`.
+/// 
+/// # Arguments:
+/// * `aggregation`: The aggregation to compute.
+/// * `values`: The values to aggregate.
+/// 
+/// # Returns:
+/// The aggregation result, `AggregationResult::None` if the aggregation isn't defined on timestamps or on zero values (MIN, MAX).
+pub fn aggregate_timestamps(aggregation: AggregationType, values: impl Iterator<Item = Timestamp>) -> AggregationResult {
+    let mut count: usize = 0;
+    let mut min: Option<Timestamp> = None;
+    let mut max: Option<Timestamp> = None;
+
+    for value in values {
+        count += 1;
+        min = Some(min.map_or(value, |min| min.min(value)));
+        max = Some(max.map_or(value, |max| max.max(value)));
+    }
+
+    match (aggregation, min, max) {
+        (AggregationType::Count, _, _) => AggregationResult::Int(count as i128),
+        (AggregationType::Min, Some(min), _) => AggregationResult::Timestamp(min),
+        (AggregationType::Max, _, Some(max)) => AggregationResult::Timestamp(max),
+        _ => AggregationResult::None,
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aggregate_ints() {
+        let values: Vec<i64> = vec![3, -1, 10];
+
+        assert_eq!(aggregate_ints(AggregationType::Sum, values.clone().into_iter()), AggregationResult::Int(12));
+        assert_eq!(aggregate_ints(AggregationType::Avg, values.clone().into_iter()), AggregationResult::Float(4.0));
+        assert_eq!(aggregate_ints(AggregationType::Min, values.clone().into_iter()), AggregationResult::Int(-1));
+        assert_eq!(aggregate_ints(AggregationType::Max, values.clone().into_iter()), AggregationResult::Int(10));
+        assert_eq!(aggregate_ints(AggregationType::Count, values.into_iter()), AggregationResult::Int(3));
+
+        // sums don't overflow
+        let values: Vec<i64> = vec![i32::MAX as i64, i32::MAX as i64];
+        assert_eq!(aggregate_ints(AggregationType::Sum, values.into_iter()), AggregationResult::Int(2 * i32::MAX as i128));
+
+        // aggregations on zero values
+        assert_eq!(aggregate_ints(AggregationType::Sum, vec![].into_iter()), AggregationResult::Int(0));
+        assert_eq!(aggregate_ints(AggregationType::Count, vec![].into_iter()), AggregationResult::Int(0));
+        assert_eq!(aggregate_ints(AggregationType::Avg, vec![].into_iter()), AggregationResult::None);
+    }
+
+    #[test]
+    fn test_aggregate_floats() {
+        let values: Vec<f64> = vec![0.5, -1.5, 4.0];
+
+        assert_eq!(aggregate_floats(AggregationType::Sum, values.clone().into_iter()), AggregationResult::Float(3.0));
+        assert_eq!(aggregate_floats(AggregationType::Avg, values.clone().into_iter()), AggregationResult::Float(1.0));
+        assert_eq!(aggregate_floats(AggregationType::Min, values.clone().into_iter()), AggregationResult::Float(-1.5));
+        assert_eq!(aggregate_floats(AggregationType::Max, values.clone().into_iter()), AggregationResult::Float(4.0));
+        assert_eq!(aggregate_floats(AggregationType::Count, values.into_iter()), AggregationResult::Int(3));
+
+        assert_eq!(aggregate_floats(AggregationType::Max, vec![].into_iter()), AggregationResult::None);
+    }
+
+    #[test]
+    fn test_aggregate_decimals() {
+        let values: Vec<Decimal> = vec![Decimal::parse("0.10").unwrap(), Decimal::parse("0.2").unwrap(), Decimal::parse("-1").unwrap()];
+
+        assert_eq!(aggregate_decimals(AggregationType::Sum, values.clone().into_iter()), AggregationResult::Decimal(Decimal::parse("-0.70").unwrap()));
+        assert_eq!(aggregate_decimals(AggregationType::Avg, values.clone().into_iter()), AggregationResult::Decimal(Decimal::parse("-0.233333").unwrap()));
+        assert_eq!(aggregate_decimals(AggregationType::Min, values.clone().into_iter()), AggregationResult::Decimal(Decimal::parse("-1").unwrap()));
+        assert_eq!(aggregate_decimals(AggregationType::Max, values.clone().into_iter()), AggregationResult::Decimal(Decimal::parse("0.2").unwrap()));
+        assert_eq!(aggregate_decimals(AggregationType::Count, values.into_iter()), AggregationResult::Int(3));
+        assert_eq!(aggregate_decimals(AggregationType::Avg, vec![].into_iter()), AggregationResult::None);
+    }
+
+    #[test]
+    fn test_aggregate_timestamps() {
+        let values: Vec<Timestamp> = vec![Timestamp::from_millis(1697000800000).unwrap(), Timestamp::from_millis(-5).unwrap()];
+
+        assert_eq!(aggregate_timestamps(AggregationType::Min, values.clone().into_iter()), AggregationResult::Timestamp(Timestamp::from_millis(-5).unwrap()));
+        assert_eq!(aggregate_timestamps(AggregationType::Max, values.clone().into_iter()), AggregationResult::Timestamp(Timestamp::from_millis(1697000800000).unwrap()));
+        assert_eq!(aggregate_timestamps(AggregationType::Count, values.clone().into_iter()), AggregationResult::Int(2));
+        assert_eq!(aggregate_timestamps(AggregationType::Sum, values.into_iter()), AggregationResult::None);
+        assert_eq!(aggregate_timestamps(AggregationType::Max, vec![].into_iter()), AggregationResult::None);
+    }
+}
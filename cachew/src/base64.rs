@@ -0,0 +1,98 @@
+/// The standard base64 alphabet (RFC 4648).
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const PADDING: u8 = b'=';
+
+
+/// Encodes bytes as padded base64.
+///
+/// # Arguments:
+/// * `bytes`: The bytes to encode.
+///
+/// # Returns:
+/// The base64 string.
+pub fn encode(bytes: &[u8]) -> String {
+    let mut encoded: Vec<u8> = Vec::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let group: u32 = chunk.iter().enumerate().fold(0, |group, (index, byte)| group | ((*byte as u32) << (16 - 8 * index)));
+
+        for index in 0..4 {
+            if index <= chunk.len() {
+                encoded.push(ALPHABET[((group >> (18 - 6 * index)) & 0x3f) as usize]);
+            }
+            else {
+                encoded.push(PADDING);
+            }
+        }
+    }
+
+    String::from_utf8(encoded).unwrap()
+}
+
+fn decode_symbol(symbol: u8) -> Option<u32> {
+    ALPHABET.iter().position(|character| *character == symbol).map(|position| position as u32)
+}
+
+/// Decodes padded base64.
+///
+/// # Arguments:
+/// * `encoded`: The base64 string.
+///
+/// # Returns:
+/// The decoded bytes or `None` if the string isn't valid padded base64.
+pub fn decode(encoded: &str) -> Option<Vec<u8>> {
+    let encoded: &[u8] = encoded.as_bytes();
+    if !encoded.len().is_multiple_of(4) {
+        return None;
+    }
+
+    let mut decoded: Vec<u8> = Vec::with_capacity(encoded.len() / 4 * 3);
+    for (chunk_index, chunk) in encoded.chunks(4).enumerate() {
+        let is_last_chunk: bool = chunk_index == encoded.len() / 4 - 1;
+        let padding: usize = chunk.iter().rev().take_while(|symbol| **symbol == PADDING).count();
+        if padding > 2 || (padding > 0 && !is_last_chunk) {
+            return None;
+        }
+
+        let mut group: u32 = 0;
+        for (index, symbol) in chunk[..4 - padding].iter().enumerate() {
+            group |= decode_symbol(*symbol)? << (18 - 6 * index);
+        }
+
+        let bytes: [u8; 3] = [(group >> 16) as u8, (group >> 8) as u8, group as u8];
+        decoded.extend_from_slice(&bytes[..3 - padding]);
+    }
+
+    Some(decoded)
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(encode(b""), "");
+        assert_eq!(encode(b"f"), "Zg==");
+        assert_eq!(encode(b"fo"), "Zm8=");
+        assert_eq!(encode(b"foo"), "Zm9v");
+        assert_eq!(encode(b"foobar"), "Zm9vYmFy");
+        assert_eq!(encode(&[0, 255, 254, 62, 63]), "AP/+Pj8=");
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(decode(""), Some(vec![]));
+        assert_eq!(decode("Zg=="), Some(b"f".to_vec()));
+        assert_eq!(decode("Zm8="), Some(b"fo".to_vec()));
+        assert_eq!(decode("Zm9vYmFy"), Some(b"foobar".to_vec()));
+        assert_eq!(decode("AP/+Pj8="), Some(vec![0, 255, 254, 62, 63]));
+
+        assert_eq!(decode("Zg="), None);
+        assert_eq!(decode("Zg==Zg=="), None);
+        assert_eq!(decode("Z==="), None);
+        assert_eq!(decode("Zm9*"), None);
+    }
+}
@@ -3,7 +3,10 @@ use crate::schemas::{DatabaseType};
 use std::env::{self, VarError};
 use log::{info, warn, error};
 
-use crate::cli::validators::{validate_database_type, validate_password};
+use crate::cli::validators::{validate_database_type, validate_password, validate_json_schema, validate_flag, validate_size, validate_key_order};
+use crate::database::DEFAULT_MAX_BYTES_SIZE;
+use crate::json_schema::JsonSchema;
+use crate::key::KeyOrder;
 
 /// Stores the CLI arguments for starting the CachewDB server.
 /// 
@@ -12,6 +15,10 @@ use crate::cli::validators::{validate_database_type, validate_password};
 /// * Optional: `password`: The password needed to communitcate with the CachewDB server.
 /// * Optional: `host`: The address which the CachewDB server is hostet on.
 /// * Optional: `port`: The port on which the CachewDB server is accessible.
+/// * Optional: `json_schema`: The path to a JSON schema file every document of a JSON database has to conform to.
+/// * Optional: `value_index`: Enables the value index for reverse lookups (FIND VALUE) on scalar databases.
+/// * Optional: `max_bytes_size`: The maximum size (in bytes) of a value in a BYTES database.
+/// * Optional: `key_order`: The collation of the keys (bytewise, natural or case-insensitive).
 #[derive(Parser, Debug)] 
 #[command(author, version, about, long_about = None)]
 pub struct CliArgs {
@@ -26,6 +33,18 @@ pub struct CliArgs {
 
     #[arg(long = "port")]
     pub port: Option<String>,
+
+    #[arg(long = "json-schema")]
+    pub json_schema: Option<String>,
+
+    #[arg(long = "value-index")]
+    pub value_index: bool,
+
+    #[arg(long = "max-bytes-size")]
+    pub max_bytes_size: Option<String>,
+
+    #[arg(long = "key-order")]
+    pub key_order: Option<String>,
 }
 
 /// Stores initial arguments needed for starting a CachewDB instance.
@@ -35,11 +54,19 @@ pub struct CliArgs {
 /// * `password`: The password needed to communitcate with the CachewDB server.
 /// * `host`: The address which the CachewDB server is hostet on.
 /// * `port`: The port on which the CachewDB server is accessible.
+/// * `json_schema`: The JSON schema every document of a JSON database has to conform to.
+/// * `value_index`: Whether the value index for reverse lookups is enabled.
+/// * `max_bytes_size`: The maximum size (in bytes) of a value in a BYTES database.
+/// * `key_order`: The collation the keys are ordered by.
 pub struct CachewDbArgs {
     pub database_type: DatabaseType,
     pub password: String,
     pub host: String,
-    pub port: String
+    pub port: String,
+    pub json_schema: Option<JsonSchema>,
+    pub value_index: bool,
+    pub max_bytes_size: usize,
+    pub key_order: KeyOrder
 }
 
 /// Gets the initial arguments needed for starting a CachewDB instance from CLI flags or ENV variables.
@@ -49,12 +76,30 @@ pub struct CachewDbArgs {
 pub fn get_cachew_db_args() -> CachewDbArgs {
     let cli_args = CliArgs::parse();
 
-    CachewDbArgs {
+    let cachew_db_args = CachewDbArgs {
         database_type: get_argument::<DatabaseType>(cli_args.database_type, "CACHEW_DB_TYPE", validate_database_type, None),
         password: get_argument::<String>(cli_args.password, "CACHEW_DB_PASSWORD", validate_password, None),
         host: get_argument::<String>(cli_args.host, "CACHEW_DB_HOST", |x| x, Some("127.0.0.1".to_string())),
         port: get_argument::<String>(cli_args.port, "CACHEW_DB_PORT", |x| x, Some("8080".to_string())),
+        json_schema: get_argument::<Option<JsonSchema>>(cli_args.json_schema, "CACHEW_DB_JSON_SCHEMA", validate_json_schema, Some(None)),
+        value_index: get_argument::<bool>(cli_args.value_index.then(|| "true".to_string()), "CACHEW_DB_VALUE_INDEX", validate_flag, Some(false)),
+        max_bytes_size: get_argument::<usize>(cli_args.max_bytes_size, "CACHEW_DB_MAX_BYTES_SIZE", validate_size, Some(DEFAULT_MAX_BYTES_SIZE)),
+        key_order: get_argument::<KeyOrder>(cli_args.key_order, "CACHEW_DB_KEY_ORDER", validate_key_order, Some(KeyOrder::Bytewise)),
+    };
+
+    if cachew_db_args.json_schema.is_some() && cachew_db_args.database_type != DatabaseType::Json {
+        let error_message: String = format!("A JSON schema can only be used with the database type 'JSON', not '{}'.", cachew_db_args.database_type);
+        error!("{}", error_message);
+        panic!("{}", error_message);
     }
+
+    if cachew_db_args.value_index && cachew_db_args.database_type == DatabaseType::Json {
+        let error_message: String = "The value index can't be used with the database type 'JSON', use 'INDEX CREATE' instead.".to_string();
+        error!("{}", error_message);
+        panic!("{}", error_message);
+    }
+
+    cachew_db_args
 }
 
 /// Gets an argument provided by the user using the CLI flag or ENV variable.
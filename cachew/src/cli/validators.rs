@@ -2,6 +2,8 @@ use log::{info, warn, error};
 use regex::Regex;
 
 use crate::schemas::{DatabaseType};
+use crate::json_schema::JsonSchema;
+use crate::key::KeyOrder;
 
 
 /// Validates the database-type provided by the user.
@@ -19,8 +21,13 @@ pub fn validate_database_type(database_type_arg: String) -> DatabaseType {
         "FLOAT" => DatabaseType::Float,
         "BOOL" => DatabaseType::Bool,
         "JSON" => DatabaseType::Json,
+        "INT64" => DatabaseType::Int64,
+        "FLOAT64" => DatabaseType::Float64,
+        "DECIMAL" => DatabaseType::Decimal,
+        "BYTES" => DatabaseType::Bytes,
+        "TIMESTAMP" => DatabaseType::Timestamp,
         _ => {
-            let error_message: String = format!("Invalid database type '{}'. Choose one of: STR, INT, FLOAT, BOOL or JSON.", database_type_arg);
+            let error_message: String = format!("Invalid database type '{}'. Choose one of: STR, INT, FLOAT, BOOL, JSON, INT64, FLOAT64, DECIMAL, BYTES or TIMESTAMP.", database_type_arg);
             error!("{}", error_message);
             panic!("{}", error_message);
         }
@@ -74,6 +81,87 @@ pub fn validate_password(password: String) -> String {
 }
 
 
+/// Loads and validates the JSON schema file provided by the user.
+/// 
+/// # Arguments:
+/// * `json_schema_path`: The path to the JSON schema file.
+/// 
+/// # Returns:
+/// Returns the parsed JSON schema. If the file can't be read or doesn't contain a valid schema, the program panics.
+pub fn validate_json_schema(json_schema_path: String) -> Option<JsonSchema> {
+    let parsed_schema: Result<JsonSchema, String> = std::fs::read_to_string(&json_schema_path)
+        .map_err(|error| error.to_string())
+        .and_then(|content| serde_json::from_str(&content).map_err(|error| error.to_string()))
+        .and_then(JsonSchema::new);
+
+    match parsed_schema {
+        Ok(json_schema) => Some(json_schema),
+        Err(error) => {
+            let error_message: String = format!("Invalid JSON schema '{}'. Error: {}", json_schema_path, error);
+            error!("{}", error_message);
+            panic!("{}", error_message);
+        }
+    }
+}
+
+/// Validates a boolean switch provided by the user (e.g the ``CACHEW_DB_VALUE_INDEX`` ENV variable).
+/// 
+/// # Arguments:
+/// * `flag`: The switch value provided by the user.
+/// 
+/// # Returns:
+/// Returns the parsed bool. If the value is neither "true" nor "false", the program panics.
+pub fn validate_flag(flag: String) -> bool {
+    match flag.to_lowercase().as_str() {
+        "true" => true,
+        "false" => false,
+        _ => {
+            let error_message: String = format!("Invalid flag value '{}'. Choose one of: true or false.", flag);
+            error!("{}", error_message);
+            panic!("{}", error_message);
+        }
+    }
+}
+
+/// Validates a size in bytes provided by the user.
+/// 
+/// # Arguments:
+/// * `size`: The size provided by the user.
+/// 
+/// # Returns:
+/// Returns the parsed size. If it isn't a positive integer, the program panics.
+pub fn validate_size(size: String) -> usize {
+    match size.parse::<usize>() {
+        Ok(parsed_size) if parsed_size > 0 => parsed_size,
+        _ => {
+            let error_message: String = format!("Invalid size '{}'. Expected a positive amount of bytes.", size);
+            error!("{}", error_message);
+            panic!("{}", error_message);
+        }
+    }
+}
+
+/// Validates the key order provided by the user.
+/// 
+/// # Arguments:
+/// * `key_order`: The key order provided by the user.
+/// 
+/// # Returns:
+/// Returns the parsed KeyOrder. If the key order is invalid, the program panics.
+pub fn validate_key_order(key_order: String) -> KeyOrder {
+    match key_order.as_str() {
+        "bytewise" => KeyOrder::Bytewise,
+        "natural" => KeyOrder::Natural,
+        "case-insensitive" => KeyOrder::CaseInsensitive,
+        _ => {
+            let error_message: String = format!("Invalid key order '{}'. Choose one of: bytewise, natural or case-insensitive.", key_order);
+            error!("{}", error_message);
+            panic!("{}", error_message);
+        }
+    }
+}
+
+
 
 #[cfg(test)]
 mod tests {
@@ -81,8 +169,11 @@ mod tests {
 
     #[test]
     fn test_validate_database_type() {
-        let db_types: &[&str] = &["STR", "INT", "FLOAT", "BOOL", "JSON"];
-        let expected_db_types: &[DatabaseType] = &[DatabaseType::Str, DatabaseType::Int, DatabaseType::Float, DatabaseType::Bool, DatabaseType::Json];
+        let db_types: &[&str] = &["STR", "INT", "FLOAT", "BOOL", "JSON", "INT64", "FLOAT64", "DECIMAL", "BYTES", "TIMESTAMP"];
+        let expected_db_types: &[DatabaseType] = &[
+            DatabaseType::Str, DatabaseType::Int, DatabaseType::Float, DatabaseType::Bool, DatabaseType::Json,
+            DatabaseType::Int64, DatabaseType::Float64, DatabaseType::Decimal, DatabaseType::Bytes, DatabaseType::Timestamp
+        ];
 
         for (idx, db_type) in db_types.iter().enumerate() {
             assert_eq!(validate_database_type(db_type.to_string()), expected_db_types[idx]);
@@ -90,7 +181,7 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Invalid database type 'WOOL'. Choose one of: STR, INT, FLOAT, BOOL or JSON.")]
+    #[should_panic(expected = "Invalid database type 'WOOL'. Choose one of: STR, INT, FLOAT, BOOL, JSON, INT64, FLOAT64, DECIMAL, BYTES or TIMESTAMP.")]
     fn test_validate_wrong_database_type() {
         validate_database_type("WOOL".to_string());
     }
@@ -106,8 +197,59 @@ mod tests {
         validate_password("p".to_string());
     }
 
+    #[test]
+    fn test_validate_json_schema() {
+        let json_schema_path = std::env::temp_dir().join("cachew_test_schema.json");
+        std::fs::write(&json_schema_path, "{\"type\": \"object\"}").unwrap();
+
+        let json_schema = validate_json_schema(json_schema_path.to_str().unwrap().to_string());
+        assert_eq!(json_schema, Some(JsonSchema::new(serde_json::json!({"type": "object"})).unwrap()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid JSON schema 'missing.json'.")]
+    fn test_validate_missing_json_schema() {
+        validate_json_schema("missing.json".to_string());
+    }
+
+    #[test]
+    fn test_validate_flag() {
+        assert!(validate_flag("true".to_string()));
+        assert!(!validate_flag("FALSE".to_string()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid flag value 'yes'. Choose one of: true or false.")]
+    fn test_validate_wrong_flag() {
+        validate_flag("yes".to_string());
+    }
+
+    #[test]
+    fn test_validate_size() {
+        assert_eq!(validate_size("1024".to_string()), 1024);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid size '0'. Expected a positive amount of bytes.")]
+    fn test_validate_wrong_size() {
+        validate_size("0".to_string());
+    }
+
+    #[test]
+    fn test_validate_key_order() {
+        assert_eq!(validate_key_order("bytewise".to_string()), KeyOrder::Bytewise);
+        assert_eq!(validate_key_order("natural".to_string()), KeyOrder::Natural);
+        assert_eq!(validate_key_order("case-insensitive".to_string()), KeyOrder::CaseInsensitive);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid key order 'numeric'. Choose one of: bytewise, natural or case-insensitive.")]
+    fn test_validate_wrong_key_order() {
+        validate_key_order("numeric".to_string());
+    }
+
     /*#[test]
-    #[should_panic(expected = "Invalid database type 'WOOL'. Choose one of: STR, INT, FLOAT, BOOL or JSON.")]
+    #[should_panic(expected = "Invalid database type 'WOOL'. Choose one of: STR, INT, FLOAT, BOOL, JSON, INT64, FLOAT64, DECIMAL, BYTES or TIMESTAMP.")]
     fn test_failed_database_type_env() {
         std::env::set_var("CACHEW_DB_TYPE", "WOOL");
         get_database_type(None);
@@ -0,0 +1,156 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt;
+
+use crate::sorted_set::SortedSet;
+use crate::time_series::TimeSeries;
+use crate::probabilistic::{HyperLogLog, BloomFilter, DEFAULT_PRECISION, DEFAULT_BLOOM_ERROR_RATE, DEFAULT_BLOOM_CAPACITY};
+use crate::token_bucket::TokenBucket;
+use crate::geo::GeoSet;
+
+
+/// The kinds of collections a key can hold instead of a single value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollectionKind {
+    List,
+    Set,
+    Hash,
+    SortedSet,
+    TimeSeries,
+    HyperLogLog,
+    BloomFilter,
+    TokenBucket,
+    GeoSet
+}
+
+impl CollectionKind {
+
+    /// Creates an empty collection of this kind.
+    pub fn new_collection(&self) -> Collection {
+        match self {
+            CollectionKind::List => Collection::List(VecDeque::new()),
+            CollectionKind::Set => Collection::Set(BTreeSet::new()),
+            CollectionKind::Hash => Collection::Hash(BTreeMap::new()),
+            CollectionKind::SortedSet => Collection::SortedSet(SortedSet::new()),
+            CollectionKind::TimeSeries => Collection::TimeSeries(TimeSeries::new()),
+            CollectionKind::HyperLogLog => Collection::HyperLogLog(HyperLogLog::new(DEFAULT_PRECISION)),
+            CollectionKind::BloomFilter => Collection::BloomFilter(BloomFilter::new(DEFAULT_BLOOM_ERROR_RATE, DEFAULT_BLOOM_CAPACITY)),
+            CollectionKind::TokenBucket => Collection::TokenBucket(TokenBucket::new()),
+            CollectionKind::GeoSet => Collection::GeoSet(GeoSet::new()),
+        }
+    }
+}
+
+impl fmt::Display for CollectionKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CollectionKind::List => write!(f, "LIST"),
+            CollectionKind::Set => write!(f, "SET"),
+            CollectionKind::Hash => write!(f, "HASH"),
+            CollectionKind::SortedSet => write!(f, "ZSET"),
+            CollectionKind::TimeSeries => write!(f, "TS"),
+            CollectionKind::HyperLogLog => write!(f, "HLL"),
+            CollectionKind::BloomFilter => write!(f, "BLOOM"),
+            CollectionKind::TokenBucket => write!(f, "THROTTLE"),
+            CollectionKind::GeoSet => write!(f, "GEO"),
+        }
+    }
+}
+
+
+/// A collection stored under a single key. Its elements are serialized values of the database type,
+/// like the values of the storage, so they are validated and (de)serialized the same way.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Collection {
+    /// A list that can be pushed to and popped from both ends.
+    List(VecDeque<Vec<u8>>),
+    /// A set of distinct values (ordered by their serialized form).
+    Set(BTreeSet<Vec<u8>>),
+    /// A map of fields to values, ordered by field.
+    Hash(BTreeMap<String, Vec<u8>>),
+    /// A set of distinct values ordered by a score.
+    SortedSet(SortedSet),
+    /// Timestamped values, ordered by their timestamp.
+    TimeSeries(TimeSeries),
+    /// An estimate of the amount of distinct values (the values aren't stored).
+    HyperLogLog(HyperLogLog),
+    /// A set membership test with false positives (the values aren't stored).
+    BloomFilter(BloomFilter),
+    /// The state of a rate limiter (the limits are passed with every THROTTLE request).
+    TokenBucket(TokenBucket),
+    /// A set of distinct values and their positions, ordered by geohash.
+    GeoSet(GeoSet)
+}
+
+impl Collection {
+
+    pub fn kind(&self) -> CollectionKind {
+        match self {
+            Collection::List(_) => CollectionKind::List,
+            Collection::Set(_) => CollectionKind::Set,
+            Collection::Hash(_) => CollectionKind::Hash,
+            Collection::SortedSet(_) => CollectionKind::SortedSet,
+            Collection::TimeSeries(_) => CollectionKind::TimeSeries,
+            Collection::HyperLogLog(_) => CollectionKind::HyperLogLog,
+            Collection::BloomFilter(_) => CollectionKind::BloomFilter,
+            Collection::TokenBucket(_) => CollectionKind::TokenBucket,
+            Collection::GeoSet(_) => CollectionKind::GeoSet,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Collection::List(list) => list.is_empty(),
+            Collection::Set(set) => set.is_empty(),
+            Collection::Hash(hash) => hash.is_empty(),
+            Collection::SortedSet(sorted_set) => sorted_set.is_empty(),
+            Collection::TimeSeries(time_series) => time_series.is_empty(),
+            Collection::GeoSet(geo_set) => geo_set.is_empty(),
+            // nothing can be removed from them
+            Collection::HyperLogLog(_) | Collection::BloomFilter(_) | Collection::TokenBucket(_) => false,
+        }
+    }
+}
+
+
+/// Resolves the inclusive indexes of an LRANGE query, negative indexes count from the end of the list (-1 is the last element).
+///
+/// # Arguments:
+/// * `length`: The length of the list.
+/// * `start`: The index of the first element.
+/// * `stop`: The index of the last element.
+///
+/// # Returns:
+/// The range of positions inside the list, or `None` if no element is inside the range.
+pub fn list_range(length: usize, start: i64, stop: i64) -> Option<std::ops::RangeInclusive<usize>> {
+    let length: i64 = length as i64;
+    let resolve = |index: i64| if index < 0 { length + index } else { index };
+
+    let start: i64 = resolve(start).max(0);
+    let stop: i64 = resolve(stop).min(length - 1);
+    (start <= stop).then_some(start as usize..=stop as usize)
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kind() {
+        assert_eq!(CollectionKind::Set.new_collection().kind(), CollectionKind::Set);
+        assert!(CollectionKind::Hash.new_collection().is_empty());
+        assert_eq!(CollectionKind::List.to_string(), "LIST");
+    }
+
+    #[test]
+    fn test_list_range() {
+        assert_eq!(list_range(5, 0, -1), Some(0..=4));
+        assert_eq!(list_range(5, 1, 2), Some(1..=2));
+        assert_eq!(list_range(5, -2, 100), Some(3..=4));
+        assert_eq!(list_range(5, -100, 0), Some(0..=0));
+        assert_eq!(list_range(5, 3, 1), None);
+        assert_eq!(list_range(5, 5, 10), None);
+        assert_eq!(list_range(0, 0, -1), None);
+    }
+}
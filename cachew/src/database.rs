@@ -1,10 +1,25 @@
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::str::FromStr;
 use bincode::{serialize, deserialize};
-use std::ops::Bound::Included;
+use std::ops::Bound::{Included, Excluded, Unbounded};
+use regex::{Regex, RegexBuilder};
 use std::sync::{Arc, Mutex};
-
-use crate::schemas::{KeyValuePair, ValueType, QueryResponseType, QueryRequest, DatabaseType};
+use tokio::sync::broadcast;
+
+use crate::schemas::{KeyValuePair, ScoredMember, Sample, Downsampling, ThrottleLimit, GeoPoint, GeoUnit, ValueType, QueryResponseType, QueryRequest, DatabaseType, AggregationType, AggregationResult};
+use crate::aggregation::{aggregate_ints, aggregate_floats, aggregate_decimals, aggregate_timestamps};
+use crate::json::{JsonPath, merge_patch};
+use crate::json_schema::{JsonSchema, SchemaViolation};
+use crate::index::{JsonIndex, ValueIndex, IndexValue};
+use crate::key::{Key, KeyOrder};
+use crate::tuple::Tuple;
+use crate::collection::{Collection, CollectionKind, list_range};
+use crate::time_series::bucket_start;
+use crate::probabilistic::{HyperLogLog, BloomFilter, DEFAULT_PRECISION};
+use crate::lock::Locks;
+use crate::geo;
+use crate::timestamp::Timestamp;
+use crate::predicate::{Predicate, ComparisonOperator};
 use crate::{database_error};
 use crate::errors::database_errors::{DatabaseErrorType};
 
@@ -12,23 +27,247 @@ use std::marker::{Send, Copy};
 use std::fmt::{Debug, Display};
 
 
+/// The default maximum size of a value in a BYTES database (16 MiB).
+pub const DEFAULT_MAX_BYTES_SIZE: usize = 16 * 1024 * 1024;
+
+/// The name of the kind of a key holding a single value (used in 'wrongKind' errors).
+const VALUE_KIND: &str = "VALUE";
+
+/// The amount of key changes buffered for blocked requests, a blocked request that falls behind re-checks its key.
+const KEY_CHANGES_CAPACITY: usize = 1024;
+
+/// What a key holds, used to move and copy keys regardless of what they hold.
+enum Entry {
+    Value(Vec<u8>),
+    Collection(Collection)
+}
+
 pub struct Database {
     pub database_type: DatabaseType,
-    storage: BTreeMap<String, Vec<u8>>,
+    storage: BTreeMap<Key, Vec<u8>>,
+    collections: BTreeMap<Key, Collection>,
+    key_order: KeyOrder,
+    json_schema: Option<JsonSchema>,
+    indexes: BTreeMap<String, JsonIndex>,
+    value_index: Option<ValueIndex>,
+    max_bytes_size: usize,
+    locks: Locks,
+    /// The last ID handed out by each sequence, independent of the keys and the database type.
+    sequences: HashMap<String, u64>,
+    /// The keys set with EPHEMERAL and the address of the connection each one is bound to.
+    ephemeral_keys: BTreeMap<Key, String>,
+    key_changes: broadcast::Sender<Key>,
 }
 
 
 impl Database {
     
     pub fn new(database_type: DatabaseType) -> Self {
-        let storage: BTreeMap<String, Vec<u8>> = BTreeMap::new();
+        let storage: BTreeMap<Key, Vec<u8>> = BTreeMap::new();
 
         Self {
             database_type,
-            storage
+            storage,
+            collections: BTreeMap::new(),
+            key_order: KeyOrder::Bytewise,
+            json_schema: None,
+            indexes: BTreeMap::new(),
+            value_index: None,
+            max_bytes_size: DEFAULT_MAX_BYTES_SIZE,
+            locks: Locks::new(),
+            sequences: HashMap::new(),
+            ephemeral_keys: BTreeMap::new(),
+            key_changes: broadcast::channel(KEY_CHANGES_CAPACITY).0
+        }
+    }
+
+    /// Sets the maximum size (in bytes) a value of a BYTES database can have.
+    /// 
+    /// # Arguments:
+    /// * `max_bytes_size`: The size limit.
+    pub fn set_max_bytes_size(&mut self, max_bytes_size: usize) {
+        self.max_bytes_size = max_bytes_size;
+    }
+
+    /// Sets the collation the keys are ordered by. Existing entries are re-sorted, keys that become
+    /// the same key under the new collation (e.g `Key` and `key` for `CaseInsensitive`) are merged.
+    /// 
+    /// # Arguments:
+    /// * `key_order`: The collation.
+    pub fn set_key_order(&mut self, key_order: KeyOrder) {
+        self.key_order = key_order;
+        self.storage = std::mem::take(&mut self.storage)
+            .into_iter()
+            .map(|(key, value)| (Key::new(key.into_name(), key_order), value))
+            .collect();
+        self.collections = std::mem::take(&mut self.collections)
+            .into_iter()
+            .map(|(key, collection)| (Key::new(key.into_name(), key_order), collection))
+            .collect();
+        self.ephemeral_keys = std::mem::take(&mut self.ephemeral_keys)
+            .into_iter()
+            .map(|(key, connection)| (Key::new(key.into_name(), key_order), connection))
+            .collect();
+    }
+
+    /// Wraps a key name into a storage key ordered by the collation of the database.
+    pub fn key(&self, name: &str) -> Key {
+        Key::new(name.to_owned(), self.key_order)
+    }
+
+    /// Iterates over the entries of the storage or the collections whose key starts with a prefix (using the collation), in key order.
+    fn prefix_entries<'a, V>(&self, entries: &'a BTreeMap<Key, V>, prefix: &'a str) -> Box<dyn Iterator<Item = (&'a Key, &'a V)> + 'a> {
+        // encoded tuples are compared bytewise with every collation, so their prefixes are always contiguous
+        if Tuple::is_encoded(prefix) {
+            Box::new(entries
+                .range((Included(self.key(prefix)), Unbounded))
+                .take_while(move |(key, _)| key.name().starts_with(prefix)))
+        }
+        else if self.key_order.has_contiguous_prefixes() {
+            Box::new(entries
+                .range((Included(self.key(prefix)), Unbounded))
+                .take_while(move |(key, _)| key.starts_with(prefix)))
+        }
+        else {
+            Box::new(entries.iter().filter(move |(key, _)| key.starts_with(prefix)))
+        }
+    }
+
+    /// Iterates over the keys (of values and collections) starting with a prefix, in key order.
+    fn prefix_keys<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = &'a Key> + 'a {
+        Self::merge_keys(
+            self.prefix_entries(&self.storage, prefix).map(|(key, _)| key),
+            self.prefix_entries(&self.collections, prefix).map(|(key, _)| key)
+        )
+    }
+
+    /// Merges two iterators over keys in key order (e.g the keys of values and of collections) into one.
+    fn merge_keys<'a>(keys: impl Iterator<Item = &'a Key>, other_keys: impl Iterator<Item = &'a Key>) -> impl Iterator<Item = &'a Key> {
+        let (mut keys, mut other_keys) = (keys.peekable(), other_keys.peekable());
+        std::iter::from_fn(move || match (keys.peek(), other_keys.peek()) {
+            (Some(key), Some(other_key)) if other_key < key => other_keys.next(),
+            (Some(_), _) => keys.next(),
+            (None, _) => other_keys.next()
+        })
+    }
+
+    /// Gets a copy of what a key holds.
+    fn get_entry(&self, key: &str) -> Option<Entry> {
+        let key: Key = self.key(key);
+        match self.storage.get(&key) {
+            Some(serialized_value) => Some(Entry::Value(serialized_value.clone())),
+            None => self.collections.get(&key).cloned().map(Entry::Collection)
+        }
+    }
+
+    /// Takes what a key holds out of the database, like DEL does.
+    fn take_entry(&mut self, key: &str) -> Option<Entry> {
+        match self.remove_entry(key) {
+            Some(serialized_value) => Some(Entry::Value(serialized_value)),
+            None => self.remove_collection(key).map(Entry::Collection)
+        }
+    }
+
+    /// Puts a value or a collection under a key, after `check_entry_kind`.
+    fn put_entry(&mut self, key: String, entry: Entry) {
+        match entry {
+            Entry::Value(serialized_value) => self.insert_entry(key, serialized_value),
+            Entry::Collection(collection) => {
+                // removed first, so a different spelling of the key (case-insensitive collation) is replaced too
+                self.remove_collection(&key);
+                let key: Key = Key::new(key, self.key_order);
+                self.notify_key_change(&key);
+                self.collections.insert(key, collection);
+            }
+        }
+    }
+
+    /// Checks that what a key holds can be put under another key, ie. the other key holds nothing or the same kind of thing.
+    fn check_entry_kind(&self, key: &str, new_key: &str) -> Result<(), String> {
+        match self.collections.get(&self.key(key)) {
+            Some(collection) => self.check_kind(new_key, collection.kind()),
+            None => self.check_value_kind(new_key)
+        }
+    }
+
+    /// Checks if a key holds a value or a collection.
+    fn contains_key(&self, key: &str) -> bool {
+        let key: Key = self.key(key);
+        self.storage.contains_key(&key) || self.collections.contains_key(&key)
+    }
+
+    /// Subscribes to the keys written to (values set or pushed to a list) or deleted, used to wake up blocked requests.
+    pub fn subscribe_key_changes(&self) -> broadcast::Receiver<Key> {
+        self.key_changes.subscribe()
+    }
+
+    /// Announces a write to a key or its deletion to the blocked requests (if there are any).
+    fn notify_key_change(&self, key: &Key) {
+        if self.key_changes.receiver_count() > 0 {
+            let _ = self.key_changes.send(key.clone());
         }
     }
 
+    /// Enables the value index used for reverse lookups (value to keys) on scalar databases.
+    pub fn enable_value_index(&mut self) {
+        self.value_index = Some(ValueIndex::new());
+    }
+
+    /// Inserts a serialized value into the storage and keeps the secondary indexes up to date.
+    /// Every insertion into the storage has to go through this function, after `check_value_kind`.
+    fn insert_entry(&mut self, key: String, serialized_value: Vec<u8>) {
+        // with a case-insensitive collation the same key can be spelled differently, the latest spelling is kept
+        let key: Key = Key::new(key, self.key_order);
+        let stored_name: Option<String> = self.storage
+            .get_key_value(&key)
+            .filter(|(stored_key, _)| stored_key.name() != key.name())
+            .map(|(stored_key, _)| stored_key.name().to_owned());
+        if let Some(stored_name) = stored_name {
+            self.remove_entry(&stored_name);
+        }
+
+        if let Some(value_index) = self.value_index.as_mut() {
+            if let Some(value) = IndexValue::from_value(&deserialize(&serialized_value).unwrap()) {
+                value_index.insert(key.name(), value);
+            }
+        }
+        if !self.indexes.is_empty() {
+            if let ValueType::Json(document) = deserialize(&serialized_value).unwrap() {
+                // like documents without a scalar at the path of an index, documents that aren't valid JSON aren't indexed
+                if let Ok(document) = Self::parse_json(&document) {
+                    for index in self.indexes.values_mut() {
+                        index.insert(key.name(), &document);
+                    }
+                }
+            }
+        }
+        self.notify_key_change(&key);
+        self.storage.insert(key, serialized_value);
+    }
+
+    /// Removes a value from the storage and the secondary indexes.
+    /// Every removal from the storage has to go through this function, like `remove_collection` for collections.
+    fn remove_entry(&mut self, key: &str) -> Option<Vec<u8>> {
+        let (key, serialized_value) = self.storage.remove_entry(&self.key(key))?;
+        self.ephemeral_keys.remove(&key);
+        self.notify_key_change(&key);
+        if let Some(value_index) = self.value_index.as_mut() {
+            value_index.remove(key.name());
+        }
+        for index in self.indexes.values_mut() {
+            index.remove(key.name());
+        }
+        Some(serialized_value)
+    }
+
+    /// Sets the JSON schema every document of a JSON database has to conform to.
+    /// 
+    /// # Arguments:
+    /// * `json_schema`: The schema.
+    pub fn set_json_schema(&mut self, json_schema: JsonSchema) {
+        self.json_schema = Some(json_schema);
+    }
+
     pub fn check_value_type(&self, value: &ValueType) -> bool {
         match self.database_type {
             DatabaseType::Str => {
@@ -45,8 +284,56 @@ impl Database {
             },
             DatabaseType::Json => {
                 matches!(value, ValueType::Json(_))
+            },
+            DatabaseType::Int64 => {
+                matches!(value, ValueType::Int64(_))
+            },
+            DatabaseType::Float64 => {
+                matches!(value, ValueType::Float64(_))
+            },
+            DatabaseType::Decimal => {
+                matches!(value, ValueType::Decimal(_))
+            },
+            DatabaseType::Bytes => {
+                matches!(value, ValueType::Bytes(_))
+            },
+            DatabaseType::Timestamp => {
+                matches!(value, ValueType::Timestamp(_))
+            }
+        }
+    }
+
+    /// Checks a JSON document against the JSON schema (if there is one).
+    fn check_json_schema(&self, document: &serde_json::Value) -> Result<(), String> {
+        match self.json_schema.as_ref().map(|json_schema| json_schema.validate(document)) {
+            Some(Err(SchemaViolation { path, reason })) => database_error!(DatabaseErrorType::SchemaViolation(path, reason)),
+            _ => Ok(())
+        }
+    }
+
+    /// Parses a JSON document or value, sent by a client or stored (`validate_value` checks documents before they're stored).
+    fn parse_json(document: &str) -> Result<serde_json::Value, String> {
+        match serde_json::from_str(document) {
+            Ok(document) => Ok(document),
+            Err(_) => database_error!(DatabaseErrorType::InvalidJson)
+        }
+    }
+
+    /// Checks if a value can be inserted, ie. it matches the database type and, for JSON, that it's valid JSON conforming to the JSON schema.
+    fn validate_value(&self, value: &ValueType) -> Result<(), String> {
+        if !self.check_value_type(value) {
+            return database_error!(DatabaseErrorType::WrongValueType);
+        }
+        if let ValueType::Json(document) = value {
+            let document: serde_json::Value = Self::parse_json(document)?;
+            return self.check_json_schema(&document);
+        }
+        if let ValueType::Bytes(bytes) = value {
+            if bytes.len() > self.max_bytes_size {
+                return database_error!(DatabaseErrorType::ValueTooLarge(bytes.len(), self.max_bytes_size));
             }
         }
+        Ok(())
     }
 
     /// Gets a value by its key.
@@ -57,10 +344,11 @@ impl Database {
     /// # Returns:
     /// Either the queried value in a GET_OK enum or an error.
     pub fn get(&self, key: &str) -> Result<QueryResponseType, String> {
-        if let Some(serialized_value) = self.storage.get(key) {
+        if let Some(serialized_value) = self.storage.get(&self.key(key)) {
             let deserialized_value: ValueType = deserialize(serialized_value).unwrap();
             return Ok(QueryResponseType::GET_OK(deserialized_value));
         }
+        self.check_value_kind(key)?;
     
         database_error!(DatabaseErrorType::KeyNotFound(key.to_string()))
     }
@@ -70,10 +358,12 @@ impl Database {
     /// # Arguments:
     /// * `key_lower`: The lower query key.
     /// * `key_upper`: The upper query key.
+    /// * `predicates`: The WHERE predicates every returned value has to satisfy.
     /// 
     /// # Returns:
     /// Either the queried values in a GET_RANGE_OK enum or an error.
-    pub fn get_range(&self, key_lower: String, key_upper: String) -> Result<QueryResponseType, String> {
+    pub fn get_range(&self, key_lower: String, key_upper: String, predicates: &[Predicate]) -> Result<QueryResponseType, String> {
+        let (key_lower, key_upper) = (Key::new(key_lower, self.key_order), Key::new_upper_bound(key_upper, self.key_order));
         if key_lower > key_upper {
             return database_error!(DatabaseErrorType::InvalidRangeOrder);
         }
@@ -81,7 +371,10 @@ impl Database {
         let mut values: Vec<ValueType> = Vec::new();
         let range = self.storage.range((Included(key_lower), Included(key_upper)));
         for (_, value) in range {
-            values.push(deserialize(value).unwrap());
+            let value: ValueType = deserialize(value).unwrap();
+            if predicates.iter().all(|predicate| predicate.matches(&value)) {
+                values.push(value);
+            }
         }
     
         Ok(QueryResponseType::GET_RANGE_OK(values))
@@ -97,7 +390,7 @@ impl Database {
     pub fn get_many(&self, keys: Vec<&str>) -> Result<QueryResponseType, String> {
         let mut values: Vec<ValueType> = Vec::new();
         for key in keys {
-            if let Some(serialized_value) = self.storage.get(key) {
+            if let Some(serialized_value) = self.storage.get(&self.key(key)) {
                 let deserialized_value: ValueType = deserialize(serialized_value).unwrap();
                 values.push(deserialized_value);
             }
@@ -109,7 +402,23 @@ impl Database {
         Ok(QueryResponseType::GET_MANY_OK(values))
     }
 
-    /// Deletes a value by its key.
+    /// Gets values from a list of keys without failing on missing keys.
+    /// 
+    /// # Arguments:
+    /// * `keys`: The a vector of multiple keys.
+    /// 
+    /// # Returns:
+    /// The queried values (None for missing keys) in a GET_MANY_NULLS_OK enum.
+    pub fn get_many_nulls(&self, keys: Vec<&str>) -> Result<QueryResponseType, String> {
+        let values: Vec<Option<ValueType>> = keys
+            .iter()
+            .map(|key| self.storage.get(&self.key(key)).map(|serialized_value| deserialize(serialized_value).unwrap()))
+            .collect();
+
+        Ok(QueryResponseType::GET_MANY_NULLS_OK(values))
+    }
+
+    /// Deletes a value (or collection) by its key.
     /// 
     /// # Arguments:
     /// * `key`: The query key.
@@ -117,49 +426,56 @@ impl Database {
     /// # Returns:
     /// Either a GET_DEL enum on deletion or an error.
     pub fn del(&mut self, key: &str) -> Result<QueryResponseType, String> {
-        let _ = self.storage.remove(key);
+        let _ = self.remove_entry(key);
+        self.remove_collection(key);
         Ok(QueryResponseType::DEL_OK)
     }
 
-    /// Deletes values by a range of keys.
+    /// Deletes values (and collections) by a range of keys.
     /// 
     /// # Arguments:
     /// * `key_lower`: The lower query key.
     /// * `key_upper`: The upper query key.
     /// 
     /// # Returns:
-    /// Either a DEL_RANGE_OK enum on deletion or an error.
+    /// Either the amount of deleted keys in a DEL_RANGE_OK enum or an error.
     pub fn del_range(&mut self, key_lower: String, key_upper: String) -> Result<QueryResponseType, String> {
+        let (key_lower, key_upper) = (Key::new(key_lower, self.key_order), Key::new_upper_bound(key_upper, self.key_order));
         if key_lower > key_upper {
             return database_error!(DatabaseErrorType::InvalidRangeOrder);
         }
     
-        let keys_to_remove: Vec<String> = self.storage
-            .range((Included(key_lower), Included(key_upper)))
-            .map(|(key, _)| key.clone())
+        let keys_to_remove: Vec<String> = Self::merge_keys(
+                self.storage.range((Included(&key_lower), Included(&key_upper))).map(|(key, _)| key),
+                self.collections.range((Included(&key_lower), Included(&key_upper))).map(|(key, _)| key)
+            )
+            .map(|key| key.name().to_owned())
             .collect();
     
-        for key in keys_to_remove {
-            self.storage.remove(&key);
+        for key in &keys_to_remove {
+            self.take_entry(key);
         }
     
-        Ok(QueryResponseType::DEL_RANGE_OK)
+        Ok(QueryResponseType::DEL_RANGE_OK(keys_to_remove.len()))
     }
 
-    /// Deletes values by a list of keys.
+    /// Deletes values (or collections) by a list of keys.
     /// 
     /// # Arguments:
     /// * `store`: The BTreeMap storing the ordered key-value pairs.
     /// * `keys`: The a vector of multiple keys.
     /// 
     /// # Returns:
-    /// A DEL_MANY_OK enum.
+    /// The amount of actually deleted keys in a DEL_MANY_OK enum.
     pub fn del_many(&mut self, keys: Vec<&str>) -> Result<QueryResponseType, String> {
+        let mut deleted: usize = 0;
         for key in keys {
-            let _ = self.storage.remove(key);
+            if self.remove_entry(key).is_some() || self.remove_collection(key).is_some() {
+                deleted += 1;
+            }
         }
 
-        Ok(QueryResponseType::DEL_MANY_OK)
+        Ok(QueryResponseType::DEL_MANY_OK(deleted))
     }
 
     /// Inserts a new key value pair.
@@ -171,15 +487,62 @@ impl Database {
     /// # Returns:
     /// A SET_OK enum.
     pub fn set(&mut self, key: &str, value: ValueType) -> Result<QueryResponseType, String> {
-        if !self.check_value_type(&value) {
-            return database_error!(DatabaseErrorType::WrongValueType);
-        }
+        self.validate_value(&value)?;
+        self.check_value_kind(key)?;
+
+        self.insert_entry(key.to_owned(), serialize(&value).unwrap());
+        self.bind_ephemeral(key, None);
+        Ok(QueryResponseType::SET_OK)
+    }
 
-        self.storage.insert(key.to_owned(), serialize(&value).unwrap());
+    /// Inserts a value that is deleted once the connection setting it closes (unless it's deleted or set without EPHEMERAL before).
+    /// 
+    /// # Arguments:
+    /// * `key`: The key.
+    /// * `value`: The value.
+    /// * `connection`: The address of the connection the key is bound to, setting the key again binds it to the latest connection.
+    /// 
+    /// # Returns:
+    /// Either a SET_OK enum or an error.
+    pub fn set_ephemeral(&mut self, key: &str, value: ValueType, connection: &str) -> Result<QueryResponseType, String> {
+        self.set(key, value)?;
+        self.bind_ephemeral(key, Some(connection.to_owned()));
         Ok(QueryResponseType::SET_OK)
     }
 
-    /// Inserts multiple key value pairs.
+    /// Binds a key to the connection whose closing deletes it, or makes it permanent.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key.
+    /// * `connection`: The address of the connection, or `None` to make the key permanent.
+    fn bind_ephemeral(&mut self, key: &str, connection: Option<String>) {
+        match connection {
+            Some(connection) => self.ephemeral_keys.insert(self.key(key), connection),
+            None => self.ephemeral_keys.remove(&self.key(key))
+        };
+    }
+
+    /// Deletes the ephemeral keys of a connection, like DEL does.
+    /// 
+    /// # Arguments:
+    /// * `connection`: The address of the closed connection.
+    /// 
+    /// # Returns:
+    /// The amount of deleted keys.
+    pub fn del_ephemeral(&mut self, connection: &str) -> usize {
+        let keys_to_remove: Vec<String> = self.ephemeral_keys
+            .iter()
+            .filter(|(_, owner)| *owner == connection)
+            .map(|(key, _)| key.name().to_owned())
+            .collect();
+
+        for key in &keys_to_remove {
+            self.remove_entry(key);
+        }
+        keys_to_remove.len()
+    }
+
+    /// Inserts multiple key value pairs. The whole batch is validated first, so either all pairs are inserted or none.
     /// 
     /// # Arguments:
     /// * `key_value_pairs`: A vector of `key_value_pairs`.
@@ -187,112 +550,1499 @@ impl Database {
     /// # Returns:
     /// A SET_OK enum.
     pub fn set_many(&mut self, key_value_pairs: Vec<KeyValuePair>) -> Result<QueryResponseType, String> {
-        for pair in key_value_pairs {
-
-            if !self.check_value_type(&pair.value) {
-                return database_error!(DatabaseErrorType::WrongValueType);
-            }
+        for pair in &key_value_pairs {
+            self.validate_value(&pair.value)?;
+            self.check_value_kind(&pair.key)?;
+        }
 
-            self.storage.insert(pair.key.to_owned(), serialize(&pair.value).unwrap());
+        for pair in key_value_pairs {
+            self.bind_ephemeral(&pair.key, None);
+            self.insert_entry(pair.key, serialize(&pair.value).unwrap());
         }
         Ok(QueryResponseType::SET_MANY_OK)
     }
 
-    pub fn clear(&mut self) -> Result<QueryResponseType, String> {
-        self.storage = BTreeMap::new();
-        Ok(QueryResponseType::CLEAR_OK)
-    }
+    /// Renames a key holding a value or a collection, an ephemeral key stays bound to its connection.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key to rename.
+    /// * `new_key`: The new name of the key, an existing value (or collection of the same kind) under it gets overwritten.
+    /// * `nx`: If true, fail instead of overwriting an existing `new_key`.
+    /// 
+    /// # Returns:
+    /// Either a RENAME_OK enum or an error.
+    pub fn rename(&mut self, key: &str, new_key: &str, nx: bool) -> Result<QueryResponseType, String> {
+        if !self.contains_key(key) {
+            return database_error!(DatabaseErrorType::KeyNotFound(key.to_string()));
+        }
+        if key == new_key {
+            return Ok(QueryResponseType::RENAME_OK);
+        }
+        // renaming a key to another spelling of itself (case-insensitive collation) only changes the spelling
+        let same_key: bool = self.key(key) == self.key(new_key);
+        if nx && !same_key && self.contains_key(new_key) {
+            return database_error!(DatabaseErrorType::KeyAlreadyExists(new_key.to_string()));
+        }
+        if !same_key {
+            self.check_entry_kind(key, new_key)?;
+        }
 
-    pub fn len(&mut self) -> Result<QueryResponseType, String> {
-        Ok(QueryResponseType::LEN_OK(self.storage.len()))
+        let connection: Option<String> = self.ephemeral_keys.get(&self.key(key)).cloned();
+        let entry: Entry = self.take_entry(key).unwrap();
+        self.put_entry(new_key.to_owned(), entry);
+        self.bind_ephemeral(new_key, connection);
+        Ok(QueryResponseType::RENAME_OK)
     }
 
-    pub fn exists(&self, key: &str) -> Result<QueryResponseType, String> {
-        Ok(QueryResponseType::EXISTS_OK(self.storage.get(key).is_some()))
+    /// Moves every key (holding a value or a collection) starting with a prefix to a new prefix, ie. "user:1" becomes "customer:1".
+    /// Existing values (or collections of the same kind) under the new keys get overwritten, ephemeral keys stay bound to their connection.
+    /// 
+    /// # Arguments:
+    /// * `prefix`: The prefix of the keys to rename.
+    /// * `new_prefix`: The prefix replacing the old one.
+    /// 
+    /// # Returns:
+    /// Either the amount of renamed keys in a RENAME_PREFIX_OK enum or an error.
+    pub fn rename_prefix(&mut self, prefix: &str, new_prefix: &str) -> Result<QueryResponseType, String> {
+        let keys_to_rename: Vec<(String, String)> = self
+            .prefix_keys(prefix)
+            .map(|key| (key.name().to_owned(), format!("{}{}", new_prefix, key.strip_prefix(prefix).unwrap())))
+            .collect();
+
+        if keys_to_rename.is_empty() {
+            return database_error!(DatabaseErrorType::PrefixNotFound(prefix.to_string()));
+        }
+        // a new key only conflicts if it isn't renamed itself
+        let renamed_keys: BTreeSet<Key> = keys_to_rename.iter().map(|(key, _)| self.key(key)).collect();
+        for (key, new_key) in &keys_to_rename {
+            if !renamed_keys.contains(&self.key(new_key)) {
+                self.check_entry_kind(key, new_key)?;
+            }
+        }
+
+        // remove all entries before inserting, since the new keys can overlap with the old ones
+        let entries: Vec<(String, Entry, Option<String>)> = keys_to_rename
+            .into_iter()
+            .map(|(key, new_key)| {
+                let connection: Option<String> = self.ephemeral_keys.get(&self.key(&key)).cloned();
+                let entry: Entry = self.take_entry(&key).unwrap();
+                (new_key, entry, connection)
+            })
+            .collect();
+
+        let renamed: usize = entries.len();
+        for (key, entry, connection) in entries {
+            self.put_entry(key.clone(), entry);
+            self.bind_ephemeral(&key, connection);
+        }
+        Ok(QueryResponseType::RENAME_PREFIX_OK(renamed))
     }
-}
 
+    /// Copies the value (or collection) of a key to another key, the copy is never ephemeral.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key to copy.
+    /// * `new_key`: The key to copy to.
+    /// * `replace`: If true, an existing value (or collection of the same kind) under `new_key` gets overwritten, else the copy fails.
+    /// 
+    /// # Returns:
+    /// Either a COPY_OK enum or an error.
+    pub fn copy(&mut self, key: &str, new_key: &str, replace: bool) -> Result<QueryResponseType, String> {
+        let entry: Entry = match self.get_entry(key) {
+            Some(entry) => entry,
+            None => return database_error!(DatabaseErrorType::KeyNotFound(key.to_string()))
+        };
+        if !replace && self.contains_key(new_key) {
+            return database_error!(DatabaseErrorType::KeyAlreadyExists(new_key.to_string()));
+        }
+        self.check_entry_kind(key, new_key)?;
 
+        self.put_entry(new_key.to_owned(), entry);
+        self.bind_ephemeral(new_key, None);
+        Ok(QueryResponseType::COPY_OK)
+    }
 
+    /// Gets the parsed JSON document stored under a key. Only supported on JSON databases.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the document.
+    /// * `command`: The command requesting the document (used for the error message).
+    /// 
+    /// # Returns:
+    /// Either the document (None if the key doesn't exist) or an error.
+    fn get_json_document(&self, key: &str, command: &str) -> Result<Option<serde_json::Value>, String> {
+        if self.database_type != DatabaseType::Json {
+            return database_error!(DatabaseErrorType::UnsupportedCommand(command.to_string(), self.database_type.to_string()));
+        }
 
+        match self.storage.get(&self.key(key)).map(|serialized_value| deserialize::<ValueType>(serialized_value).unwrap()) {
+            Some(ValueType::Json(document)) => Self::parse_json(&document).map(Some),
+            _ => Ok(None)
+        }
+    }
 
+    fn set_json_document(&mut self, key: &str, document: serde_json::Value) -> Result<(), String> {
+        self.check_json_schema(&document)?;
+        self.check_value_kind(key)?;
+        self.insert_entry(key.to_owned(), serialize(&ValueType::Json(document.to_string())).unwrap());
+        Ok(())
+    }
 
-#[cfg(test)]
-mod tests {
-    use crate::database;
-    use super::*;
+    /// Gets the value at a path inside a JSON document.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the document.
+    /// * `path`: The path inside the document.
+    /// 
+    /// # Returns:
+    /// Either the JSON value in a JSON_GET_OK enum or an error.
+    pub fn json_get(&self, key: &str, path: &JsonPath) -> Result<QueryResponseType, String> {
+        let document: serde_json::Value = match self.get_json_document(key, "JSON.GET")? {
+            Some(document) => document,
+            None => return database_error!(DatabaseErrorType::KeyNotFound(key.to_string()))
+        };
+
+        match path.get(&document) {
+            Some(value) => Ok(QueryResponseType::JSON_GET_OK(value.to_string())),
+            None => database_error!(DatabaseErrorType::PathNotFound(path.to_string()))
+        }
+    }
 
-    #[test]
-    fn test_get() {
-        // get string
-        let mut database: database::Database = database::Database::new(DatabaseType::Str);
-        let _ = database.set("key1", ValueType::Str("val1".to_string()));
-        let response = database.get("key1");
-        assert_eq!(response, Ok(database::QueryResponseType::GET_OK(ValueType::Str("val1".to_string()))));
+    /// Sets the value at a path inside a JSON document. Setting the root path of a missing key creates the document.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the document.
+    /// * `path`: The path inside the document, its parent has to exist.
+    /// * `value`: The new JSON value.
+    /// 
+    /// # Returns:
+    /// Either a JSON_SET_OK enum or an error.
+    pub fn json_set(&mut self, key: &str, path: &JsonPath, value: &str) -> Result<QueryResponseType, String> {
+        let mut document: serde_json::Value = match self.get_json_document(key, "JSON.SET")? {
+            Some(document) => document,
+            None if path.is_root() => serde_json::Value::Null,
+            None => return database_error!(DatabaseErrorType::KeyNotFound(key.to_string()))
+        };
+
+        let value: serde_json::Value = Self::parse_json(value)?;
+        if !path.set(&mut document, value) {
+            return database_error!(DatabaseErrorType::PathNotFound(path.to_string()));
+        }
 
-        // get int
-        let mut database: database::Database = database::Database::new(DatabaseType::Int);
-        let _ = database.set("key", ValueType::Int(23));
-        let response = database.get("key");
-        assert_eq!(response, Ok(database::QueryResponseType::GET_OK(ValueType::Int(23))));
+        self.set_json_document(key, document)?;
+        Ok(QueryResponseType::JSON_SET_OK)
+    }
 
-        // get float
-        let mut database: database::Database = database::Database::new(DatabaseType::Float);
-        let _ = database.set("key", ValueType::Float(-0.1));
-        let response = database.get("key");
-        assert_eq!(response, Ok(database::QueryResponseType::GET_OK(ValueType::Float(-0.1))));
+    /// Deletes the value at a path inside a JSON document. Deleting the root path deletes the whole document.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the document.
+    /// * `path`: The path inside the document.
+    /// 
+    /// # Returns:
+    /// Either a JSON_DEL_OK enum or an error.
+    pub fn json_del(&mut self, key: &str, path: &JsonPath) -> Result<QueryResponseType, String> {
+        let mut document: serde_json::Value = match self.get_json_document(key, "JSON.DEL")? {
+            Some(document) => document,
+            None => return database_error!(DatabaseErrorType::KeyNotFound(key.to_string()))
+        };
+
+        if path.is_root() {
+            self.remove_entry(key);
+            return Ok(QueryResponseType::JSON_DEL_OK);
+        }
+        if !path.delete(&mut document) {
+            return database_error!(DatabaseErrorType::PathNotFound(path.to_string()));
+        }
 
-        // get bool
-        let mut database: database::Database = database::Database::new(DatabaseType::Bool);
-        let _ = database.set("key", ValueType::Bool(true));
-        let response = database.get("key");
-        assert_eq!(response, Ok(database::QueryResponseType::GET_OK(ValueType::Bool(true))));
+        self.set_json_document(key, document)?;
+        Ok(QueryResponseType::JSON_DEL_OK)
+    }
 
-        // get json
-        let mut database: database::Database = database::Database::new(DatabaseType::Json);
-        let _ = database.set("key", ValueType::Json("{key: \"value\"}".to_string()));
-        let response = database.get("key");
-        assert_eq!(response, Ok(database::QueryResponseType::GET_OK(ValueType::Json("{key: \"value\"}".to_string()))));
+    /// Applies a JSON merge patch (RFC 7396) to a document. A missing key is treated as a `null` document.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the document.
+    /// * `patch`: The JSON merge patch.
+    /// 
+    /// # Returns:
+    /// Either a JSON_MERGE_OK enum or an error.
+    pub fn json_merge(&mut self, key: &str, patch: &str) -> Result<QueryResponseType, String> {
+        let mut document: serde_json::Value = self.get_json_document(key, "JSON.MERGE")?.unwrap_or(serde_json::Value::Null);
 
-        // fail to get key
-        let mut database: database::Database = database::Database::new(DatabaseType::Int);
-        let _ = database.set("key0", ValueType::Int(23));
-        let response = database.get("key2");
-        assert_eq!(response, database_error!(DatabaseErrorType::KeyNotFound("key2".to_string())));
+        let patch: serde_json::Value = Self::parse_json(patch)?;
+        merge_patch(&mut document, &patch);
+
+        self.set_json_document(key, document)?;
+        Ok(QueryResponseType::JSON_MERGE_OK)
     }
 
-    #[test]
-    fn test_get_many() {
-        let mut database: database::Database = database::Database::new(DatabaseType::Str);
+    /// Gets the active JSON schema.
+    /// 
+    /// # Returns:
+    /// The schema (None if there is no schema) in a SCHEMA_GET_OK enum.
+    pub fn schema_get(&self) -> Result<QueryResponseType, String> {
+        Ok(QueryResponseType::SCHEMA_GET_OK(self.json_schema.as_ref().map(|json_schema| json_schema.as_value().to_string())))
+    }
 
-        // get many
-        for i in 0..5 {
-            let _ = database.set(&format!("key{}", i), ValueType::Str(format!("val{}", i)));
+    /// Creates a secondary index over the values at a path of all JSON documents, existing documents get indexed right away.
+    /// 
+    /// # Arguments:
+    /// * `name`: The name of the index.
+    /// * `path`: The path inside the documents to index.
+    /// 
+    /// # Returns:
+    /// Either an INDEX_CREATE_OK enum or an error.
+    pub fn index_create(&mut self, name: &str, path: JsonPath) -> Result<QueryResponseType, String> {
+        if self.database_type != DatabaseType::Json {
+            return database_error!(DatabaseErrorType::UnsupportedCommand("INDEX CREATE".to_string(), self.database_type.to_string()));
         }
-        let response = database.get_many(vec!["key1", "key3", "key4"]);
-        assert_eq!(response, Ok(QueryResponseType::GET_MANY_OK(vec![
-            ValueType::Str("val1".to_string()), 
-            ValueType::Str("val3".to_string()),
-            ValueType::Str("val4".to_string())
-        ])));
-        
-        // fail to get key
-        for i in 0..5 {
-            let _ = database.set(&format!("key{}", i), ValueType::Str(format!("val{}", i)));
+        if self.indexes.contains_key(name) {
+            return database_error!(DatabaseErrorType::IndexAlreadyExists(name.to_string()));
         }
-        let response = database.get_many(vec!["key4", "key5"]);
-        assert_eq!(response, database_error!(DatabaseErrorType::KeyNotFound("key5".to_string())));
-    }
 
-    #[test]
-    fn test_get_range() {
-        let mut database: database::Database = database::Database::new(DatabaseType::Float);
+        let mut index: JsonIndex = JsonIndex::new(path);
+        for (key, serialized_value) in &self.storage {
+            if let ValueType::Json(document) = deserialize(serialized_value).unwrap() {
+                if let Ok(document) = Self::parse_json(&document) {
+                    index.insert(key.name(), &document);
+                }
+            }
+        }
 
-        // get range
+        self.indexes.insert(name.to_owned(), index);
+        Ok(QueryResponseType::INDEX_CREATE_OK)
+    }
+
+    /// Drops a secondary index.
+    /// 
+    /// # Arguments:
+    /// * `name`: The name of the index.
+    /// 
+    /// # Returns:
+    /// Either an INDEX_DROP_OK enum or an error.
+    pub fn index_drop(&mut self, name: &str) -> Result<QueryResponseType, String> {
+        match self.indexes.remove(name) {
+            Some(_) => Ok(QueryResponseType::INDEX_DROP_OK),
+            None => database_error!(DatabaseErrorType::IndexNotFound(name.to_string()))
+        }
+    }
+
+    /// Finds all documents whose indexed value lies in a range (or equals a value if both bounds are the same).
+    /// 
+    /// # Arguments:
+    /// * `name`: The name of the index.
+    /// * `lower`: The lower bound (inclusive).
+    /// * `upper`: The upper bound (inclusive).
+    /// 
+    /// # Returns:
+    /// Either the matching key value pairs, ordered by the indexed value, in a FIND_OK enum or an error.
+    pub fn find(&self, name: &str, lower: &IndexValue, upper: &IndexValue) -> Result<QueryResponseType, String> {
+        let index: &JsonIndex = match self.indexes.get(name) {
+            Some(index) => index,
+            None => return database_error!(DatabaseErrorType::IndexNotFound(name.to_string()))
+        };
+
+        let key_value_pairs: Vec<KeyValuePair> = index
+            .find(lower, upper)
+            .into_iter()
+            .filter_map(|key| Self::to_key_value_pair(self.storage.get_key_value(&self.key(&key))))
+            .collect();
+
+        Ok(QueryResponseType::FIND_OK(key_value_pairs))
+    }
+
+    /// Finds all keys holding a value (or a value in a range) using the value index.
+    /// 
+    /// # Arguments:
+    /// * `lower`: The value to look up or, if `upper` is given, the lower bound (inclusive).
+    /// * `upper`: The upper bound (inclusive), only supported on numeric and TIMESTAMP databases.
+    /// 
+    /// # Returns:
+    /// Either the matching key value pairs, ordered by value, in a FIND_OK enum or an error.
+    pub fn find_value(&self, lower: &ValueType, upper: Option<&ValueType>) -> Result<QueryResponseType, String> {
+        if matches!(self.database_type, DatabaseType::Json | DatabaseType::Bytes) {
+            return database_error!(DatabaseErrorType::UnsupportedCommand("FIND VALUE".to_string(), self.database_type.to_string()));
+        }
+        if upper.is_some() && !self.database_type.is_numeric() && self.database_type != DatabaseType::Timestamp {
+            return database_error!(DatabaseErrorType::UnsupportedCommand("FIND VALUE BETWEEN".to_string(), self.database_type.to_string()));
+        }
+        let upper: &ValueType = upper.unwrap_or(lower);
+        if !self.check_value_type(lower) || !self.check_value_type(upper) {
+            return database_error!(DatabaseErrorType::WrongValueType);
+        }
+        let value_index: &ValueIndex = match self.value_index.as_ref() {
+            Some(value_index) => value_index,
+            None => return database_error!(DatabaseErrorType::ValueIndexDisabled)
+        };
+
+        let key_value_pairs: Vec<KeyValuePair> = match (IndexValue::from_value(lower), IndexValue::from_value(upper)) {
+            (Some(lower), Some(upper)) => value_index
+                .find(&lower, &upper)
+                .into_iter()
+                .filter_map(|key| Self::to_key_value_pair(self.storage.get_key_value(&self.key(&key))))
+                .collect(),
+            _ => vec![]
+        };
+
+        Ok(QueryResponseType::FIND_OK(key_value_pairs))
+    }
+
+    /// Gets the length of a value, ie. the amount of bytes of a BYTES value or of the UTF-8 encoded STR value.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the value.
+    /// 
+    /// # Returns:
+    /// Either the length in a STRLEN_OK enum or an error.
+    pub fn strlen(&self, key: &str) -> Result<QueryResponseType, String> {
+        if !matches!(self.database_type, DatabaseType::Str | DatabaseType::Bytes) {
+            return database_error!(DatabaseErrorType::UnsupportedCommand("STRLEN".to_string(), self.database_type.to_string()));
+        }
+
+        match self.storage.get(&self.key(key)).map(|serialized_value| deserialize::<ValueType>(serialized_value).unwrap()) {
+            Some(ValueType::Str(value)) => Ok(QueryResponseType::STRLEN_OK(value.len())),
+            Some(ValueType::Bytes(value)) => Ok(QueryResponseType::STRLEN_OK(value.len())),
+            _ => database_error!(DatabaseErrorType::KeyNotFound(key.to_string()))
+        }
+    }
+
+    pub fn clear(&mut self) -> Result<QueryResponseType, String> {
+        if self.key_changes.receiver_count() > 0 {
+            for key in self.storage.keys().chain(self.collections.keys()) {
+                self.notify_key_change(key);
+            }
+        }
+        self.storage = BTreeMap::new();
+        self.collections = BTreeMap::new();
+        self.ephemeral_keys = BTreeMap::new();
+        for index in self.indexes.values_mut() {
+            index.clear();
+        }
+        if let Some(value_index) = self.value_index.as_mut() {
+            value_index.clear();
+        }
+        Ok(QueryResponseType::CLEAR_OK)
+    }
+
+    pub fn len(&mut self) -> Result<QueryResponseType, String> {
+        Ok(QueryResponseType::LEN_OK(self.storage.len() + self.collections.len()))
+    }
+
+    pub fn exists(&self, key: &str) -> Result<QueryResponseType, String> {
+        let key: Key = self.key(key);
+        Ok(QueryResponseType::EXISTS_OK(self.storage.contains_key(&key) || self.collections.contains_key(&key)))
+    }
+
+    /// Turns a storage entry into a deserialized key value pair.
+    fn to_key_value_pair(entry: Option<(&Key, &Vec<u8>)>) -> Option<KeyValuePair> {
+        entry.map(|(key, value)| KeyValuePair { key: key.to_string(), value: deserialize(value).unwrap() })
+    }
+
+    /// Gets the entry with the smallest key.
+    /// 
+    /// # Returns:
+    /// The key value pair (or None if the database is empty) in a FIRST_OK enum.
+    pub fn first(&self) -> Result<QueryResponseType, String> {
+        Ok(QueryResponseType::FIRST_OK(Self::to_key_value_pair(self.storage.iter().next())))
+    }
+
+    /// Gets the entry with the biggest key.
+    /// 
+    /// # Returns:
+    /// The key value pair (or None if the database is empty) in a LAST_OK enum.
+    pub fn last(&self) -> Result<QueryResponseType, String> {
+        Ok(QueryResponseType::LAST_OK(Self::to_key_value_pair(self.storage.iter().next_back())))
+    }
+
+    /// Gets the entry with the smallest key strictly bigger than the given key.
+    /// 
+    /// # Arguments:
+    /// * `key`: The query key (doesn't have to exist).
+    /// 
+    /// # Returns:
+    /// The key value pair (or None if there is no bigger key) in a NEXT_OK enum.
+    pub fn next(&self, key: &str) -> Result<QueryResponseType, String> {
+        let entry = self.storage.range((Excluded(self.key(key)), Unbounded)).next();
+        Ok(QueryResponseType::NEXT_OK(Self::to_key_value_pair(entry)))
+    }
+
+    /// Gets the entry with the biggest key strictly smaller than the given key.
+    /// 
+    /// # Arguments:
+    /// * `key`: The query key (doesn't have to exist).
+    /// 
+    /// # Returns:
+    /// The key value pair (or None if there is no smaller key) in a PREV_OK enum.
+    pub fn prev(&self, key: &str) -> Result<QueryResponseType, String> {
+        let entry = self.storage.range((Unbounded, Excluded(self.key(key)))).next_back();
+        Ok(QueryResponseType::PREV_OK(Self::to_key_value_pair(entry)))
+    }
+
+    /// Gets the entry with the biggest key smaller than or equal to the given key.
+    /// 
+    /// # Arguments:
+    /// * `key`: The query key (doesn't have to exist).
+    /// 
+    /// # Returns:
+    /// The key value pair (or None if there is no such key) in a FLOOR_OK enum.
+    pub fn floor(&self, key: &str) -> Result<QueryResponseType, String> {
+        let entry = self.storage.range((Unbounded, Included(self.key(key)))).next_back();
+        Ok(QueryResponseType::FLOOR_OK(Self::to_key_value_pair(entry)))
+    }
+
+    /// Gets the entry with the smallest key bigger than or equal to the given key.
+    /// 
+    /// # Arguments:
+    /// * `key`: The query key (doesn't have to exist).
+    /// 
+    /// # Returns:
+    /// The key value pair (or None if there is no such key) in a CEIL_OK enum.
+    pub fn ceil(&self, key: &str) -> Result<QueryResponseType, String> {
+        let entry = self.storage.range((Included(self.key(key)), Unbounded)).next();
+        Ok(QueryResponseType::CEIL_OK(Self::to_key_value_pair(entry)))
+    }
+
+    /// Aggregates the values in a range of keys. Only supported on numeric databases and (MIN, MAX and COUNT only) on TIMESTAMP databases.
+    /// 
+    /// # Arguments:
+    /// * `aggregation`: The aggregation to compute (SUM, AVG, MIN, MAX or COUNT).
+    /// * `key_lower`: The lower query key.
+    /// * `key_upper`: The upper query key.
+    /// 
+    /// # Returns:
+    /// Either the aggregation result in an AGG_OK enum or an error.
+    pub fn aggregate(&self, aggregation: AggregationType, key_lower: String, key_upper: String) -> Result<QueryResponseType, String> {
+        let (key_lower, key_upper) = (Key::new(key_lower, self.key_order), Key::new_upper_bound(key_upper, self.key_order));
+        if key_lower > key_upper {
+            return database_error!(DatabaseErrorType::InvalidRangeOrder);
+        }
+
+        let values = self.storage
+            .range((Included(key_lower), Included(key_upper)))
+            .map(|(_, value)| value.as_slice());
+        Ok(QueryResponseType::AGG_OK(aggregation, self.aggregate_values(aggregation, values)?))
+    }
+
+    /// Aggregates serialized values of the database type (see `aggregate`).
+    /// 
+    /// # Returns:
+    /// Either the aggregation result or an error if the database type doesn't support the aggregation.
+    fn aggregate_values<'a>(&self, aggregation: AggregationType, values: impl Iterator<Item = &'a [u8]>) -> Result<AggregationResult, String> {
+        let values = values.map(|value| deserialize::<ValueType>(value).unwrap());
+
+        let result: AggregationResult = match self.database_type {
+            DatabaseType::Int => aggregate_ints(aggregation, values.filter_map(|value| match value {
+                ValueType::Int(value) => Some(value as i64),
+                _ => None
+            })),
+            DatabaseType::Float => aggregate_floats(aggregation, values.filter_map(|value| match value {
+                ValueType::Float(value) => Some(value as f64),
+                _ => None
+            })),
+            DatabaseType::Int64 => aggregate_ints(aggregation, values.filter_map(|value| match value {
+                ValueType::Int64(value) => Some(value),
+                _ => None
+            })),
+            DatabaseType::Float64 => aggregate_floats(aggregation, values.filter_map(|value| match value {
+                ValueType::Float64(value) => Some(value),
+                _ => None
+            })),
+            DatabaseType::Decimal => aggregate_decimals(aggregation, values.filter_map(|value| match value {
+                ValueType::Decimal(value) => Some(value),
+                _ => None
+            })),
+            DatabaseType::Timestamp if matches!(aggregation, AggregationType::Sum | AggregationType::Avg) => {
+                return database_error!(DatabaseErrorType::UnsupportedAggregationType(aggregation.to_string(), self.database_type.to_string()));
+            }
+            DatabaseType::Timestamp => aggregate_timestamps(aggregation, values.filter_map(|value| match value {
+                ValueType::Timestamp(value) => Some(value),
+                _ => None
+            })),
+            _ => return database_error!(DatabaseErrorType::UnsupportedAggregation(self.database_type.to_string()))
+        };
+
+        Ok(result)
+    }
+
+    /// Gets the keys (of values and collections) matching a pattern, in key order.
+    /// 
+    /// # Arguments:
+    /// * `pattern`: The regex pattern the keys have to match.
+    /// * `prefix`: A literal prefix every matching key starts with, used to narrow the scanned range (may be empty).
+    /// * `count`: The maximum amount of keys to return.
+    /// 
+    /// # Returns:
+    /// Either the matching keys in a KEYS_OK enum or an error.
+    pub fn keys(&self, pattern: &str, prefix: &str, count: usize) -> Result<QueryResponseType, String> {
+        // patterns match case-insensitively if the keys are case-insensitive
+        let re: Regex = match RegexBuilder::new(pattern).case_insensitive(self.key_order == KeyOrder::CaseInsensitive).build() {
+            Ok(re) => re,
+            Err(_) => return database_error!(DatabaseErrorType::InvalidPattern(pattern.to_string()))
+        };
+
+        // tuple keys are stored encoded, so their prefix is encoded too (after undoing the escaping of their quotes),
+        // strings are left out if the keys are case-insensitive since tuple strings are compared bytewise
+        let tuple_prefix: Option<String> = match self.key_order {
+            KeyOrder::CaseInsensitive => prefix.split("\\\"").next().and_then(Tuple::encode_prefix),
+            _ => Tuple::encode_prefix(&prefix.replace("\\\"", "\""))
+        };
+        let keys: Vec<String> = self
+            .prefix_keys(tuple_prefix.as_deref().unwrap_or(prefix))
+            .map(|key| key.to_string())
+            .filter(|key| re.is_match(key))
+            .take(count)
+            .collect();
+
+        Ok(QueryResponseType::KEYS_OK(keys))
+    }
+
+    /// Checks that a key holds a collection of the expected kind (or nothing).
+    fn check_kind(&self, key: &str, kind: CollectionKind) -> Result<(), String> {
+        if self.storage.contains_key(&self.key(key)) {
+            return database_error!(DatabaseErrorType::WrongKind(key.to_string(), VALUE_KIND.to_string(), kind.to_string()));
+        }
+        match self.collections.get(&self.key(key)) {
+            Some(collection) if collection.kind() != kind => {
+                database_error!(DatabaseErrorType::WrongKind(key.to_string(), collection.kind().to_string(), kind.to_string()))
+            }
+            _ => Ok(())
+        }
+    }
+
+    /// Checks that a key doesn't hold a collection, single values never replace collections (like the other way around).
+    fn check_value_kind(&self, key: &str) -> Result<(), String> {
+        match self.collections.get(&self.key(key)) {
+            Some(collection) => database_error!(DatabaseErrorType::WrongKind(key.to_string(), collection.kind().to_string(), VALUE_KIND.to_string())),
+            None => Ok(())
+        }
+    }
+
+    /// Deletes the collection stored under a key, like `DEL` does.
+    fn remove_collection(&mut self, key: &str) -> Option<Collection> {
+        let key: Key = self.key(key);
+        let collection: Collection = self.collections.remove(&key)?;
+        self.notify_key_change(&key);
+        Some(collection)
+    }
+
+    /// Gets the collection stored under a key.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the collection.
+    /// * `kind`: The kind of collection the command works on.
+    /// 
+    /// # Returns:
+    /// Either the collection (None if the key doesn't exist) or an error if the key holds something else.
+    fn get_collection(&self, key: &str, kind: CollectionKind) -> Result<Option<&Collection>, String> {
+        self.check_kind(key, kind)?;
+        Ok(self.collections.get(&self.key(key)))
+    }
+
+    /// Gets the collection stored under a key to modify it, an empty collection is created if the key doesn't exist.
+    /// Collections that end up empty have to be removed with `remove_empty_collection`.
+    fn get_collection_mut(&mut self, key: &str, kind: CollectionKind) -> Result<&mut Collection, String> {
+        self.check_kind(key, kind)?;
+        let key: Key = self.key(key);
+        Ok(self.collections.entry(key).or_insert_with(|| kind.new_collection()))
+    }
+
+    /// Removes a collection without elements, ie. a key never holds an empty collection.
+    fn remove_empty_collection(&mut self, key: &str) {
+        let key: Key = self.key(key);
+        if self.collections.get(&key).is_some_and(Collection::is_empty) {
+            self.collections.remove(&key);
+        }
+    }
+
+    /// Pushes values to the front or back of a list, the list is created if the key doesn't exist.
+    fn push(&mut self, key: &str, values: Vec<ValueType>, front: bool) -> Result<usize, String> {
+        for value in &values {
+            self.validate_value(value)?;
+        }
+
+        let Collection::List(list) = self.get_collection_mut(key, CollectionKind::List)? else { unreachable!() };
+        for value in values {
+            match front {
+                true => list.push_front(serialize(&value).unwrap()),
+                false => list.push_back(serialize(&value).unwrap())
+            }
+        }
+        let length: usize = list.len();
+        self.notify_key_change(&self.key(key));
+        Ok(length)
+    }
+
+    /// Pushes values to the front of a list, one after another (ie. the last value ends up first).
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the list, it's created if it doesn't exist.
+    /// * `values`: The values to push.
+    /// 
+    /// # Returns:
+    /// Either the new length of the list in a LPUSH_OK enum or an error.
+    pub fn lpush(&mut self, key: &str, values: Vec<ValueType>) -> Result<QueryResponseType, String> {
+        Ok(QueryResponseType::LPUSH_OK(self.push(key, values, true)?))
+    }
+
+    /// Pushes values to the back of a list.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the list, it's created if it doesn't exist.
+    /// * `values`: The values to push.
+    /// 
+    /// # Returns:
+    /// Either the new length of the list in a RPUSH_OK enum or an error.
+    pub fn rpush(&mut self, key: &str, values: Vec<ValueType>) -> Result<QueryResponseType, String> {
+        Ok(QueryResponseType::RPUSH_OK(self.push(key, values, false)?))
+    }
+
+    /// Pops a value from the front or back of a list, the list is removed once it's empty.
+    fn pop(&mut self, key: &str, front: bool) -> Result<Option<ValueType>, String> {
+        self.check_kind(key, CollectionKind::List)?;
+        let Some(Collection::List(list)) = self.collections.get_mut(&self.key(key)) else {
+            return Ok(None);
+        };
+
+        let value: Option<Vec<u8>> = if front { list.pop_front() } else { list.pop_back() };
+        self.remove_empty_collection(key);
+        Ok(value.map(|value| deserialize(&value).unwrap()))
+    }
+
+    /// Pops the first value of a list.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the list.
+    /// 
+    /// # Returns:
+    /// Either the value (None if the list doesn't exist) in a LPOP_OK enum or an error.
+    pub fn lpop(&mut self, key: &str) -> Result<QueryResponseType, String> {
+        Ok(QueryResponseType::LPOP_OK(self.pop(key, true)?))
+    }
+
+    /// Pops the last value of a list.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the list.
+    /// 
+    /// # Returns:
+    /// Either the value (None if the list doesn't exist) in a RPOP_OK enum or an error.
+    pub fn rpop(&mut self, key: &str) -> Result<QueryResponseType, String> {
+        Ok(QueryResponseType::RPOP_OK(self.pop(key, false)?))
+    }
+
+    /// Gets the values of a list between two indexes (both inclusive), negative indexes count from the end (-1 is the last value).
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the list.
+    /// * `start`: The index of the first value.
+    /// * `stop`: The index of the last value.
+    /// 
+    /// # Returns:
+    /// Either the values (empty if the list doesn't exist) in a LRANGE_OK enum or an error.
+    pub fn lrange(&self, key: &str, start: i64, stop: i64) -> Result<QueryResponseType, String> {
+        let Some(Collection::List(list)) = self.get_collection(key, CollectionKind::List)? else {
+            return Ok(QueryResponseType::LRANGE_OK(vec![]));
+        };
+
+        let values: Vec<ValueType> = match list_range(list.len(), start, stop) {
+            Some(range) => list.range(range).map(|value| deserialize(value).unwrap()).collect(),
+            None => vec![]
+        };
+        Ok(QueryResponseType::LRANGE_OK(values))
+    }
+
+    /// Adds members to a set.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the set, it's created if it doesn't exist.
+    /// * `members`: The members to add.
+    /// 
+    /// # Returns:
+    /// Either the amount of members that weren't in the set yet in a SADD_OK enum or an error.
+    pub fn sadd(&mut self, key: &str, members: Vec<ValueType>) -> Result<QueryResponseType, String> {
+        for member in &members {
+            self.validate_value(member)?;
+        }
+
+        let Collection::Set(set) = self.get_collection_mut(key, CollectionKind::Set)? else { unreachable!() };
+        let added: usize = members.iter().filter(|member| set.insert(serialize(member).unwrap())).count();
+        Ok(QueryResponseType::SADD_OK(added))
+    }
+
+    /// Removes members from a set, the set is removed once it's empty.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the set.
+    /// * `members`: The members to remove.
+    /// 
+    /// # Returns:
+    /// Either the amount of removed members in a SREM_OK enum or an error.
+    pub fn srem(&mut self, key: &str, members: Vec<ValueType>) -> Result<QueryResponseType, String> {
+        self.check_kind(key, CollectionKind::Set)?;
+        let Some(Collection::Set(set)) = self.collections.get_mut(&self.key(key)) else {
+            return Ok(QueryResponseType::SREM_OK(0));
+        };
+
+        let removed: usize = members.iter().filter(|member| set.remove(&serialize(member).unwrap())).count();
+        self.remove_empty_collection(key);
+        Ok(QueryResponseType::SREM_OK(removed))
+    }
+
+    /// Gets the members of a set.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the set.
+    /// 
+    /// # Returns:
+    /// Either the members (empty if the set doesn't exist) in a SMEMBERS_OK enum or an error.
+    pub fn smembers(&self, key: &str) -> Result<QueryResponseType, String> {
+        let Some(Collection::Set(set)) = self.get_collection(key, CollectionKind::Set)? else {
+            return Ok(QueryResponseType::SMEMBERS_OK(vec![]));
+        };
+        Ok(QueryResponseType::SMEMBERS_OK(set.iter().map(|member| deserialize(member).unwrap()).collect()))
+    }
+
+    /// Checks if a value is a member of a set.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the set.
+    /// * `member`: The value to look for.
+    /// 
+    /// # Returns:
+    /// Either true if the value is a member (false if the set doesn't exist) in a SISMEMBER_OK enum or an error.
+    pub fn sismember(&self, key: &str, member: &ValueType) -> Result<QueryResponseType, String> {
+        let is_member: bool = match self.get_collection(key, CollectionKind::Set)? {
+            Some(Collection::Set(set)) => set.contains(&serialize(member).unwrap()),
+            _ => false
+        };
+        Ok(QueryResponseType::SISMEMBER_OK(is_member))
+    }
+
+    /// Sets fields of a hash, existing fields are overwritten.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the hash, it's created if it doesn't exist.
+    /// * `fields`: The fields and their values.
+    /// 
+    /// # Returns:
+    /// Either the amount of fields that didn't exist yet in a HSET_OK enum or an error.
+    pub fn hset(&mut self, key: &str, fields: Vec<KeyValuePair>) -> Result<QueryResponseType, String> {
+        for field in &fields {
+            self.validate_value(&field.value)?;
+        }
+
+        let Collection::Hash(hash) = self.get_collection_mut(key, CollectionKind::Hash)? else { unreachable!() };
+        let added: usize = fields
+            .into_iter()
+            .filter(|field| hash.insert(field.key.clone(), serialize(&field.value).unwrap()).is_none())
+            .count();
+        Ok(QueryResponseType::HSET_OK(added))
+    }
+
+    /// Gets the value of a field of a hash.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the hash.
+    /// * `field`: The field.
+    /// 
+    /// # Returns:
+    /// Either the value in a HGET_OK enum or an error.
+    pub fn hget(&self, key: &str, field: &str) -> Result<QueryResponseType, String> {
+        let Some(Collection::Hash(hash)) = self.get_collection(key, CollectionKind::Hash)? else {
+            return database_error!(DatabaseErrorType::KeyNotFound(key.to_string()));
+        };
+
+        match hash.get(field) {
+            Some(value) => Ok(QueryResponseType::HGET_OK(deserialize(value).unwrap())),
+            None => database_error!(DatabaseErrorType::FieldNotFound(key.to_string(), field.to_string()))
+        }
+    }
+
+    /// Deletes fields of a hash, the hash is removed once it's empty.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the hash.
+    /// * `fields`: The fields to delete.
+    /// 
+    /// # Returns:
+    /// Either the amount of deleted fields in a HDEL_OK enum or an error.
+    pub fn hdel(&mut self, key: &str, fields: Vec<String>) -> Result<QueryResponseType, String> {
+        self.check_kind(key, CollectionKind::Hash)?;
+        let Some(Collection::Hash(hash)) = self.collections.get_mut(&self.key(key)) else {
+            return Ok(QueryResponseType::HDEL_OK(0));
+        };
+
+        let deleted: usize = fields.iter().filter(|field| hash.remove(*field).is_some()).count();
+        self.remove_empty_collection(key);
+        Ok(QueryResponseType::HDEL_OK(deleted))
+    }
+
+    /// Gets all fields of a hash, ordered by field.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the hash.
+    /// 
+    /// # Returns:
+    /// Either the fields and their values (empty if the hash doesn't exist) in a HGETALL_OK enum or an error.
+    pub fn hgetall(&self, key: &str) -> Result<QueryResponseType, String> {
+        let Some(Collection::Hash(hash)) = self.get_collection(key, CollectionKind::Hash)? else {
+            return Ok(QueryResponseType::HGETALL_OK(vec![]));
+        };
+
+        let fields: Vec<KeyValuePair> = hash
+            .iter()
+            .map(|(field, value)| KeyValuePair { key: field.clone(), value: deserialize(value).unwrap() })
+            .collect();
+        Ok(QueryResponseType::HGETALL_OK(fields))
+    }
+
+    /// Adds members to a sorted set or updates their scores.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the sorted set, it's created if it doesn't exist.
+    /// * `members`: The members and their scores.
+    /// 
+    /// # Returns:
+    /// Either the amount of members that weren't in the sorted set yet in a ZADD_OK enum or an error.
+    pub fn zadd(&mut self, key: &str, members: Vec<ScoredMember>) -> Result<QueryResponseType, String> {
+        for member in &members {
+            self.validate_value(&member.member)?;
+        }
+
+        let Collection::SortedSet(sorted_set) = self.get_collection_mut(key, CollectionKind::SortedSet)? else { unreachable!() };
+        let added: usize = members
+            .into_iter()
+            .filter(|member| sorted_set.insert(serialize(&member.member).unwrap(), member.score))
+            .count();
+        Ok(QueryResponseType::ZADD_OK(added))
+    }
+
+    /// Increments the score of a member of a sorted set, a missing member is added with the increment as score.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the sorted set, it's created if it doesn't exist.
+    /// * `increment`: The amount to add to the score (may be negative).
+    /// * `member`: The member.
+    /// 
+    /// # Returns:
+    /// Either the new score in a ZINCRBY_OK enum or an error.
+    pub fn zincrby(&mut self, key: &str, increment: f64, member: ValueType) -> Result<QueryResponseType, String> {
+        self.validate_value(&member)?;
+        self.check_kind(key, CollectionKind::SortedSet)?;
+
+        let member: Vec<u8> = serialize(&member).unwrap();
+        let score: f64 = match self.collections.get(&self.key(key)) {
+            Some(Collection::SortedSet(sorted_set)) => sorted_set.score(&member).unwrap_or(0.0) + increment,
+            _ => increment
+        };
+        // e.g 'inf' plus '-inf'
+        if score.is_nan() {
+            return database_error!(DatabaseErrorType::ScoreNotANumber);
+        }
+
+        let Collection::SortedSet(sorted_set) = self.get_collection_mut(key, CollectionKind::SortedSet)? else { unreachable!() };
+        sorted_set.insert(member, score);
+        Ok(QueryResponseType::ZINCRBY_OK(score))
+    }
+
+    /// Gets the members of a sorted set between two ranks (both inclusive), negative ranks count from the end (-1 is the last member).
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the sorted set.
+    /// * `start`: The rank of the first member.
+    /// * `stop`: The rank of the last member.
+    /// * `reverse`: If true, ranks count from the highest score (ie. 0 is the member with the highest score).
+    /// 
+    /// # Returns:
+    /// Either the members and their scores (empty if the sorted set doesn't exist) in a ZRANGE_OK enum or an error.
+    pub fn zrange(&self, key: &str, start: i64, stop: i64, reverse: bool) -> Result<QueryResponseType, String> {
+        let Some(Collection::SortedSet(sorted_set)) = self.get_collection(key, CollectionKind::SortedSet)? else {
+            return Ok(QueryResponseType::ZRANGE_OK(vec![]));
+        };
+
+        let members: Vec<ScoredMember> = match list_range(sorted_set.len(), start, stop) {
+            Some(ranks) => sorted_set
+                .range_by_rank(ranks, reverse)
+                .into_iter()
+                .map(|(member, score)| ScoredMember { member: deserialize(member).unwrap(), score })
+                .collect(),
+            None => vec![]
+        };
+        Ok(QueryResponseType::ZRANGE_OK(members))
+    }
+
+    /// Gets the members of a sorted set with a score between a minimum and a maximum (both inclusive), in ascending score order.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the sorted set.
+    /// * `min`: The minimum score.
+    /// * `max`: The maximum score.
+    /// 
+    /// # Returns:
+    /// Either the members and their scores (empty if the sorted set doesn't exist) in a ZRANGE_OK enum or an error.
+    pub fn zrange_by_score(&self, key: &str, min: f64, max: f64) -> Result<QueryResponseType, String> {
+        let Some(Collection::SortedSet(sorted_set)) = self.get_collection(key, CollectionKind::SortedSet)? else {
+            return Ok(QueryResponseType::ZRANGE_OK(vec![]));
+        };
+
+        let members: Vec<ScoredMember> = sorted_set
+            .range_by_score(min, max)
+            .map(|(member, score)| ScoredMember { member: deserialize(member).unwrap(), score })
+            .collect();
+        Ok(QueryResponseType::ZRANGE_OK(members))
+    }
+
+    /// Gets the rank of a member of a sorted set.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the sorted set.
+    /// * `member`: The member.
+    /// * `reverse`: If true, ranks count from the highest score.
+    /// 
+    /// # Returns:
+    /// Either the rank (None if the member doesn't exist) in a ZRANK_OK enum or an error.
+    pub fn zrank(&self, key: &str, member: &ValueType, reverse: bool) -> Result<QueryResponseType, String> {
+        let Some(Collection::SortedSet(sorted_set)) = self.get_collection(key, CollectionKind::SortedSet)? else {
+            return Ok(QueryResponseType::ZRANK_OK(None));
+        };
+
+        let rank: Option<usize> = sorted_set
+            .rank(&serialize(member).unwrap())
+            .map(|rank| if reverse { sorted_set.len() - 1 - rank } else { rank });
+        Ok(QueryResponseType::ZRANK_OK(rank))
+    }
+
+    /// Removes members from a sorted set, the sorted set is removed once it's empty.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the sorted set.
+    /// * `members`: The members to remove.
+    /// 
+    /// # Returns:
+    /// Either the amount of removed members in a ZREM_OK enum or an error.
+    pub fn zrem(&mut self, key: &str, members: Vec<ValueType>) -> Result<QueryResponseType, String> {
+        self.check_kind(key, CollectionKind::SortedSet)?;
+        let Some(Collection::SortedSet(sorted_set)) = self.collections.get_mut(&self.key(key)) else {
+            return Ok(QueryResponseType::ZREM_OK(0));
+        };
+
+        let removed: usize = members.iter().filter(|member| sorted_set.remove(&serialize(member).unwrap())).count();
+        self.remove_empty_collection(key);
+        Ok(QueryResponseType::ZREM_OK(removed))
+    }
+
+    /// Counts the members of a sorted set with a score between a minimum and a maximum (both inclusive).
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the sorted set.
+    /// * `min`: The minimum score.
+    /// * `max`: The maximum score.
+    /// 
+    /// # Returns:
+    /// Either the amount of members (0 if the sorted set doesn't exist) in a ZCOUNT_OK enum or an error.
+    pub fn zcount(&self, key: &str, min: f64, max: f64) -> Result<QueryResponseType, String> {
+        let count: usize = match self.get_collection(key, CollectionKind::SortedSet)? {
+            Some(Collection::SortedSet(sorted_set)) => sorted_set.range_by_score(min, max).count(),
+            _ => 0
+        };
+        Ok(QueryResponseType::ZCOUNT_OK(count))
+    }
+
+    /// Appends a sample to a time series, samples that are older than the retention are dropped.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the time series, it's created if it doesn't exist.
+    /// * `timestamp`: The timestamp in epoch milliseconds (`None` for the current time), it has to be after the last sample.
+    /// * `value`: The value.
+    /// * `retention`: The new retention of the time series in milliseconds (0 keeps every sample), `None` keeps the current one.
+    /// 
+    /// # Returns:
+    /// Either the timestamp of the sample in a TS_ADD_OK enum or an error.
+    pub fn ts_add(&mut self, key: &str, timestamp: Option<i64>, value: ValueType, retention: Option<i64>) -> Result<QueryResponseType, String> {
+        self.validate_value(&value)?;
+        let timestamp: i64 = timestamp.unwrap_or_else(|| Timestamp::now().millis());
+
+        let Collection::TimeSeries(time_series) = self.get_collection_mut(key, CollectionKind::TimeSeries)? else { unreachable!() };
+        if let Err(last_timestamp) = time_series.append(timestamp, serialize(&value).unwrap()) {
+            return database_error!(DatabaseErrorType::SampleOutOfOrder(key.to_string(), last_timestamp));
+        }
+        if let Some(retention) = retention {
+            time_series.set_retention(retention);
+        }
+        Ok(QueryResponseType::TS_ADD_OK(timestamp))
+    }
+
+    /// Gets the samples of a time series between two timestamps (both inclusive), optionally aggregated per bucket.
+    /// Buckets are aligned to the epoch and buckets without samples are left out.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the time series.
+    /// * `from`: The first timestamp in epoch milliseconds.
+    /// * `to`: The last timestamp in epoch milliseconds.
+    /// * `downsampling`: The aggregation and the bucket size, it's supported on the same database types as AGG.
+    /// 
+    /// # Returns:
+    /// Either the samples (empty if the time series doesn't exist) in a TS_RANGE_OK enum, the start of the buckets and
+    /// their aggregation result in a TS_RANGE_AGG_OK enum or an error.
+    pub fn ts_range(&self, key: &str, from: i64, to: i64, downsampling: Option<Downsampling>) -> Result<QueryResponseType, String> {
+        let samples: Vec<(i64, &[u8])> = match self.get_collection(key, CollectionKind::TimeSeries)? {
+            Some(Collection::TimeSeries(time_series)) => time_series.range(from, to).collect(),
+            _ => vec![]
+        };
+
+        let Some(Downsampling { aggregation, bucket }) = downsampling else {
+            let samples: Vec<Sample> = samples
+                .into_iter()
+                .map(|(timestamp, value)| Sample { timestamp, value: deserialize(value).unwrap() })
+                .collect();
+            return Ok(QueryResponseType::TS_RANGE_OK(samples));
+        };
+
+        // fails on databases that don't support the aggregation, even if there are no samples
+        self.aggregate_values(aggregation, std::iter::empty())?;
+
+        let buckets: Vec<(i64, AggregationResult)> = samples
+            .chunk_by(|(timestamp, _), (next_timestamp, _)| bucket_start(*timestamp, bucket) == bucket_start(*next_timestamp, bucket))
+            .map(|samples| Ok((bucket_start(samples[0].0, bucket), self.aggregate_values(aggregation, samples.iter().map(|(_, value)| *value))?)))
+            .collect::<Result<Vec<(i64, AggregationResult)>, String>>()?;
+        Ok(QueryResponseType::TS_RANGE_AGG_OK(aggregation, buckets))
+    }
+
+    /// Combines HyperLogLogs into one that estimates the union of their values, missing keys are skipped.
+    /// 
+    /// # Arguments:
+    /// * `keys`: The keys of the HyperLogLogs.
+    /// 
+    /// # Returns:
+    /// Either the combined HyperLogLog (`None` if no key exists) or an error if the HyperLogLogs have different precisions.
+    fn merge_hyperloglogs(&self, keys: &[&str]) -> Result<Option<HyperLogLog>, String> {
+        let mut merged: Option<(&str, HyperLogLog)> = None;
+        for key in keys {
+            let Some(Collection::HyperLogLog(hyperloglog)) = self.get_collection(key, CollectionKind::HyperLogLog)? else { continue };
+            match &mut merged {
+                Some((first_key, merged)) => if !merged.merge(hyperloglog) {
+                    return database_error!(DatabaseErrorType::PrecisionMismatch(first_key.to_string(), key.to_string()));
+                },
+                None => merged = Some((key, hyperloglog.clone()))
+            }
+        }
+        Ok(merged.map(|(_, merged)| merged))
+    }
+
+    /// Adds values to a HyperLogLog.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the HyperLogLog, it's created if it doesn't exist.
+    /// * `error_rate`: The standard error of the HyperLogLog if it's created (`None` for 0.81%).
+    /// * `values`: The values.
+    /// 
+    /// # Returns:
+    /// Either true if the estimate might have changed in a PFADD_OK enum or an error.
+    pub fn pfadd(&mut self, key: &str, error_rate: Option<f64>, values: Vec<ValueType>) -> Result<QueryResponseType, String> {
+        for value in &values {
+            self.validate_value(value)?;
+        }
+        self.check_kind(key, CollectionKind::HyperLogLog)?;
+
+        let precision: u8 = error_rate.map_or(DEFAULT_PRECISION, HyperLogLog::precision_for);
+        let mut changed: bool = !self.collections.contains_key(&self.key(key));
+        let Collection::HyperLogLog(hyperloglog) = self.collections
+            .entry(self.key(key))
+            .or_insert_with(|| Collection::HyperLogLog(HyperLogLog::new(precision))) else { unreachable!() };
+
+        for value in &values {
+            changed |= hyperloglog.add(&serialize(value).unwrap());
+        }
+        Ok(QueryResponseType::PFADD_OK(changed))
+    }
+
+    /// Estimates the amount of distinct values added to HyperLogLogs.
+    /// 
+    /// # Arguments:
+    /// * `keys`: The keys of the HyperLogLogs, the values of all of them are counted once (missing keys are skipped).
+    /// 
+    /// # Returns:
+    /// Either the estimate in a PFCOUNT_OK enum or an error.
+    pub fn pfcount(&self, keys: Vec<&str>) -> Result<QueryResponseType, String> {
+        let count: u64 = self.merge_hyperloglogs(&keys)?.map_or(0, |hyperloglog| hyperloglog.count());
+        Ok(QueryResponseType::PFCOUNT_OK(count))
+    }
+
+    /// Merges HyperLogLogs into another one, afterwards it estimates the union of all of them.
+    /// 
+    /// # Arguments:
+    /// * `destination`: The key of the merged HyperLogLog, it's created (with the precision of the sources) if it doesn't exist.
+    /// * `sources`: The keys of the HyperLogLogs to merge (missing keys are skipped).
+    /// 
+    /// # Returns:
+    /// Either a PFMERGE_OK enum or an error.
+    pub fn pfmerge(&mut self, destination: &str, sources: Vec<&str>) -> Result<QueryResponseType, String> {
+        self.check_kind(destination, CollectionKind::HyperLogLog)?;
+        let keys: Vec<&str> = std::iter::once(destination).chain(sources).collect();
+        let merged: HyperLogLog = self.merge_hyperloglogs(&keys)?.unwrap_or_else(|| HyperLogLog::new(DEFAULT_PRECISION));
+
+        self.collections.insert(self.key(destination), Collection::HyperLogLog(merged));
+        Ok(QueryResponseType::PFMERGE_OK)
+    }
+
+    /// Creates a Bloom filter sized for a capacity and an error rate, its bits are bounded by the size limit of values.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the Bloom filter, it must not exist.
+    /// * `error_rate`: The rate of false positives while there are at most `capacity` values.
+    /// * `capacity`: The amount of values the Bloom filter is sized for.
+    /// 
+    /// # Returns:
+    /// Either a BF_RESERVE_OK enum or an error.
+    pub fn bf_reserve(&mut self, key: &str, error_rate: f64, capacity: u64) -> Result<QueryResponseType, String> {
+        if self.exists(key) == Ok(QueryResponseType::EXISTS_OK(true)) {
+            return database_error!(DatabaseErrorType::KeyAlreadyExists(key.to_string()));
+        }
+
+        let size: usize = BloomFilter::bit_count(error_rate, capacity).div_ceil(8) as usize;
+        if size > self.max_bytes_size {
+            return database_error!(DatabaseErrorType::ValueTooLarge(size, self.max_bytes_size));
+        }
+
+        self.collections.insert(self.key(key), Collection::BloomFilter(BloomFilter::new(error_rate, capacity)));
+        Ok(QueryResponseType::BF_RESERVE_OK)
+    }
+
+    /// Adds a value to a Bloom filter.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the Bloom filter, it's created (with an error rate of 1% for 100 values) if it doesn't exist.
+    /// * `value`: The value.
+    /// 
+    /// # Returns:
+    /// Either true if the value wasn't in the Bloom filter yet (false positives return false) in a BF_ADD_OK enum or an error.
+    pub fn bf_add(&mut self, key: &str, value: ValueType) -> Result<QueryResponseType, String> {
+        self.validate_value(&value)?;
+        let Collection::BloomFilter(bloom_filter) = self.get_collection_mut(key, CollectionKind::BloomFilter)? else { unreachable!() };
+        Ok(QueryResponseType::BF_ADD_OK(bloom_filter.add(&serialize(&value).unwrap())))
+    }
+
+    /// Checks if a value was added to a Bloom filter.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the Bloom filter.
+    /// * `value`: The value.
+    /// 
+    /// # Returns:
+    /// Either false if the value was never added (true may be a false positive) in a BF_EXISTS_OK enum or an error.
+    pub fn bf_exists(&self, key: &str, value: &ValueType) -> Result<QueryResponseType, String> {
+        let exists: bool = match self.get_collection(key, CollectionKind::BloomFilter)? {
+            Some(Collection::BloomFilter(bloom_filter)) => bloom_filter.contains(&serialize(value).unwrap()),
+            _ => false
+        };
+        Ok(QueryResponseType::BF_EXISTS_OK(exists))
+    }
+
+    /// Takes tokens from the token bucket of a rate limiter, the bucket is refilled continuously since the last request.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the token bucket, it's created full if it doesn't exist.
+    /// * `limit`: The max burst (capacity) and the refill rate of the bucket.
+    /// * `cost`: The amount of tokens the request costs.
+    /// 
+    /// # Returns:
+    /// Either if the request is allowed, the remaining tokens and the milliseconds until it's allowed in a THROTTLE_OK enum or an error.
+    pub fn throttle(&mut self, key: &str, limit: &ThrottleLimit, cost: u64) -> Result<QueryResponseType, String> {
+        let now: i64 = Timestamp::now().millis();
+        let Collection::TokenBucket(token_bucket) = self.get_collection_mut(key, CollectionKind::TokenBucket)? else { unreachable!() };
+        Ok(QueryResponseType::THROTTLE_OK(token_bucket.take(limit, cost, now)))
+    }
+
+    /// Acquires a lock, locks are independent of the keys and expire on the server.
+    /// 
+    /// # Arguments:
+    /// * `name`: The name of the lock.
+    /// * `owner`: The owner acquiring the lock, only it can unlock or renew the lock.
+    /// * `ttl`: The milliseconds after which the lock expires (unless it's renewed).
+    /// 
+    /// # Returns:
+    /// Either the fencing token (greater than every token before) or None if the lock is held in a LOCK_OK enum.
+    pub fn lock(&mut self, name: &str, owner: &str, ttl: i64) -> Result<QueryResponseType, String> {
+        Ok(QueryResponseType::LOCK_OK(self.locks.lock(name, owner, ttl, Timestamp::now().millis())))
+    }
+
+    /// Releases a lock.
+    /// 
+    /// # Arguments:
+    /// * `name`: The name of the lock.
+    /// * `owner`: The owner of the lock.
+    /// 
+    /// # Returns:
+    /// Either true if the lock was held by the owner and is released or false (e.g if it expired) in an UNLOCK_OK enum.
+    pub fn unlock(&mut self, name: &str, owner: &str) -> Result<QueryResponseType, String> {
+        Ok(QueryResponseType::UNLOCK_OK(self.locks.unlock(name, owner, Timestamp::now().millis())))
+    }
+
+    /// Extends a lock, it keeps its fencing token.
+    /// 
+    /// # Arguments:
+    /// * `name`: The name of the lock.
+    /// * `owner`: The owner of the lock.
+    /// * `ttl`: The milliseconds from now after which the lock expires.
+    /// 
+    /// # Returns:
+    /// Either true if the lock was held by the owner and is extended or false (e.g if it expired) in a RENEW_OK enum.
+    pub fn renew(&mut self, name: &str, owner: &str, ttl: i64) -> Result<QueryResponseType, String> {
+        Ok(QueryResponseType::RENEW_OK(self.locks.renew(name, owner, ttl, Timestamp::now().millis())))
+    }
+
+    /// Gets the owner, the fencing token and the remaining milliseconds of a lock.
+    /// 
+    /// # Arguments:
+    /// * `name`: The name of the lock.
+    /// 
+    /// # Returns:
+    /// Either the lock info or None if the lock isn't held in a LOCKINFO_OK enum.
+    pub fn lockinfo(&self, name: &str) -> Result<QueryResponseType, String> {
+        Ok(QueryResponseType::LOCKINFO_OK(self.locks.info(name, Timestamp::now().millis())))
+    }
+
+    /// Reserves a block of IDs from a sequence, IDs are never handed out twice and start at 1.
+    /// 
+    /// # Arguments:
+    /// * `name`: The name of the sequence, it's created if it doesn't exist.
+    /// * `count`: The amount of consecutive IDs to reserve, at least 1.
+    /// 
+    /// # Returns:
+    /// Either the first reserved ID (the block ends at the first ID + count - 1) in a SEQ_NEXT_OK enum or an error.
+    pub fn seq_next(&mut self, name: &str, count: u64) -> Result<QueryResponseType, String> {
+        if count == 0 {
+            return database_error!(DatabaseErrorType::InvalidSequenceCount(name.to_string()));
+        }
+        let last_id: u64 = self.sequences.get(name).copied().unwrap_or(0);
+        let Some(new_last_id) = last_id.checked_add(count) else {
+            return database_error!(DatabaseErrorType::SequenceExhausted(name.to_string()));
+        };
+
+        self.sequences.insert(name.to_owned(), new_last_id);
+        Ok(QueryResponseType::SEQ_NEXT_OK(new_last_id - count + 1))
+    }
+
+    /// Sets the last ID handed out by a sequence, e.g to continue after IDs issued elsewhere.
+    /// 
+    /// # Arguments:
+    /// * `name`: The name of the sequence.
+    /// * `last_id`: The last handed out ID, it can't be lower than the current one (so no ID is handed out twice).
+    /// 
+    /// # Returns:
+    /// Either a SEQ_SET_OK enum or an error.
+    pub fn seq_set(&mut self, name: &str, last_id: u64) -> Result<QueryResponseType, String> {
+        match self.sequences.get(name) {
+            Some(current_last_id) if *current_last_id > last_id => database_error!(DatabaseErrorType::SequenceDecrease(name.to_string(), *current_last_id)),
+            _ => {
+                self.sequences.insert(name.to_owned(), last_id);
+                Ok(QueryResponseType::SEQ_SET_OK)
+            }
+        }
+    }
+
+    /// Gets the last ID handed out by a sequence.
+    /// 
+    /// # Arguments:
+    /// * `name`: The name of the sequence.
+    /// 
+    /// # Returns:
+    /// Either the last ID (0 if none was handed out yet) in a SEQ_GET_OK enum or an error.
+    pub fn seq_get(&self, name: &str) -> Result<QueryResponseType, String> {
+        Ok(QueryResponseType::SEQ_GET_OK(self.sequences.get(name).copied().unwrap_or(0)))
+    }
+
+    /// Adds a member to a geospatial set or moves it.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the geospatial set, it's created if it doesn't exist.
+    /// * `member`: The member.
+    /// * `position`: The position of the member.
+    /// 
+    /// # Returns:
+    /// Either true if the member wasn't in the geospatial set yet in a GEO_ADD_OK enum or an error.
+    pub fn geo_add(&mut self, key: &str, member: ValueType, position: GeoPoint) -> Result<QueryResponseType, String> {
+        self.validate_value(&member)?;
+        let Collection::GeoSet(geo_set) = self.get_collection_mut(key, CollectionKind::GeoSet)? else { unreachable!() };
+        Ok(QueryResponseType::GEO_ADD_OK(geo_set.insert(serialize(&member).unwrap(), position)))
+    }
+
+    /// Gets the members of a geospatial set within a distance of a point, the nearest first.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the geospatial set.
+    /// * `center`: The point.
+    /// * `radius`: The distance in the unit.
+    /// * `unit`: The unit of the radius and the returned distances.
+    /// * `count`: The maximum amount of members (the nearest ones) to return.
+    /// 
+    /// # Returns:
+    /// Either the members and their distances (empty if the geospatial set doesn't exist) in a GEO_RADIUS_OK enum or an error.
+    pub fn geo_radius(&self, key: &str, center: &GeoPoint, radius: f64, unit: GeoUnit, count: Option<usize>) -> Result<QueryResponseType, String> {
+        let Some(Collection::GeoSet(geo_set)) = self.get_collection(key, CollectionKind::GeoSet)? else {
+            return Ok(QueryResponseType::GEO_RADIUS_OK(vec![]));
+        };
+
+        let members: Vec<(ValueType, f64)> = geo_set
+            .within_radius(center, radius * unit.meters())
+            .into_iter()
+            .take(count.unwrap_or(usize::MAX))
+            .map(|(member, distance)| (deserialize(member).unwrap(), distance / unit.meters()))
+            .collect();
+        Ok(QueryResponseType::GEO_RADIUS_OK(members))
+    }
+
+    /// Gets the members of a geospatial set inside a box, ordered by geohash.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the geospatial set.
+    /// * `min`: The south-west corner of the box.
+    /// * `max`: The north-east corner of the box, a longitude less than the one of `min` crosses the antimeridian.
+    /// * `count`: The maximum amount of members to return.
+    /// 
+    /// # Returns:
+    /// Either the members and their positions (empty if the geospatial set doesn't exist) in a GEO_BOX_OK enum or an error.
+    pub fn geo_box(&self, key: &str, min: &GeoPoint, max: &GeoPoint, count: Option<usize>) -> Result<QueryResponseType, String> {
+        let Some(Collection::GeoSet(geo_set)) = self.get_collection(key, CollectionKind::GeoSet)? else {
+            return Ok(QueryResponseType::GEO_BOX_OK(vec![]));
+        };
+
+        let members: Vec<(ValueType, GeoPoint)> = geo_set
+            .within_box(min, max)
+            .into_iter()
+            .take(count.unwrap_or(usize::MAX))
+            .map(|(member, position)| (deserialize(member).unwrap(), position))
+            .collect();
+        Ok(QueryResponseType::GEO_BOX_OK(members))
+    }
+
+    /// Gets the distance between two members of a geospatial set.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the geospatial set.
+    /// * `from`: The first member.
+    /// * `to`: The second member.
+    /// * `unit`: The unit of the distance.
+    /// 
+    /// # Returns:
+    /// Either the distance (None if a member doesn't exist) in a GEO_DIST_OK enum or an error.
+    pub fn geo_dist(&self, key: &str, from: &ValueType, to: &ValueType, unit: GeoUnit) -> Result<QueryResponseType, String> {
+        let Some(Collection::GeoSet(geo_set)) = self.get_collection(key, CollectionKind::GeoSet)? else {
+            return Ok(QueryResponseType::GEO_DIST_OK(None));
+        };
+
+        let distance: Option<f64> = geo_set.position(&serialize(from).unwrap())
+            .zip(geo_set.position(&serialize(to).unwrap()))
+            .map(|(from, to)| geo::distance(from, to) / unit.meters());
+        Ok(QueryResponseType::GEO_DIST_OK(distance))
+    }
+}
+
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+    use crate::database;
+    use crate::decimal::Decimal;
+    use crate::timestamp::Timestamp;
+    use crate::key::KeyOrder;
+    use crate::schemas::ThrottleResult;
+    use super::*;
+
+    #[test]
+    fn test_get() {
+        // get string
+        let mut database: database::Database = database::Database::new(DatabaseType::Str);
+        let _ = database.set("key1", ValueType::Str("val1".to_string()));
+        let response = database.get("key1");
+        assert_eq!(response, Ok(database::QueryResponseType::GET_OK(ValueType::Str("val1".to_string()))));
+
+        // get int
+        let mut database: database::Database = database::Database::new(DatabaseType::Int);
+        let _ = database.set("key", ValueType::Int(23));
+        let response = database.get("key");
+        assert_eq!(response, Ok(database::QueryResponseType::GET_OK(ValueType::Int(23))));
+
+        // get float
+        let mut database: database::Database = database::Database::new(DatabaseType::Float);
+        let _ = database.set("key", ValueType::Float(-0.1));
+        let response = database.get("key");
+        assert_eq!(response, Ok(database::QueryResponseType::GET_OK(ValueType::Float(-0.1))));
+
+        // get bool
+        let mut database: database::Database = database::Database::new(DatabaseType::Bool);
+        let _ = database.set("key", ValueType::Bool(true));
+        let response = database.get("key");
+        assert_eq!(response, Ok(database::QueryResponseType::GET_OK(ValueType::Bool(true))));
+
+        // get json
+        let mut database: database::Database = database::Database::new(DatabaseType::Json);
+        let _ = database.set("key", ValueType::Json("{\"key\": \"value\"}".to_string()));
+        let response = database.get("key");
+        assert_eq!(response, Ok(database::QueryResponseType::GET_OK(ValueType::Json("{\"key\": \"value\"}".to_string()))));
+
+        // invalid JSON is rejected, not stored as a string
+        let response = database.set("key", ValueType::Json("{key: \"value\"}".to_string()));
+        assert_eq!(response, database_error!(DatabaseErrorType::InvalidJson));
+
+        // fail to get key
+        let mut database: database::Database = database::Database::new(DatabaseType::Int);
+        let _ = database.set("key0", ValueType::Int(23));
+        let response = database.get("key2");
+        assert_eq!(response, database_error!(DatabaseErrorType::KeyNotFound("key2".to_string())));
+    }
+
+    #[test]
+    fn test_get_many() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Str);
+
+        // get many
+        for i in 0..5 {
+            let _ = database.set(&format!("key{}", i), ValueType::Str(format!("val{}", i)));
+        }
+        let response = database.get_many(vec!["key1", "key3", "key4"]);
+        assert_eq!(response, Ok(QueryResponseType::GET_MANY_OK(vec![
+            ValueType::Str("val1".to_string()), 
+            ValueType::Str("val3".to_string()),
+            ValueType::Str("val4".to_string())
+        ])));
+        
+        // fail to get key
+        for i in 0..5 {
+            let _ = database.set(&format!("key{}", i), ValueType::Str(format!("val{}", i)));
+        }
+        let response = database.get_many(vec!["key4", "key5"]);
+        assert_eq!(response, database_error!(DatabaseErrorType::KeyNotFound("key5".to_string())));
+    }
+
+    #[test]
+    fn test_get_many_nulls() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Bool);
+
+        let _ = database.set("key1", ValueType::Bool(true));
+        let _ = database.set("key3", ValueType::Bool(false));
+
+        let response = database.get_many_nulls(vec!["key1", "key2", "key3"]);
+        assert_eq!(response, Ok(QueryResponseType::GET_MANY_NULLS_OK(vec![
+            Some(ValueType::Bool(true)),
+            None,
+            Some(ValueType::Bool(false))
+        ])));
+    }
+
+    #[test]
+    fn test_get_range() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Float);
+
+        // get range
         for i in 0..5 {
             let _ = database.set(&format!("key{}", i), ValueType::Float(0.75f32));
         }
-        let response = database.get_range("key2".to_owned(), "key5".to_owned());
+        let response = database.get_range("key2".to_owned(), "key5".to_owned(), &[]);
         assert_eq!(response, Ok(QueryResponseType::GET_RANGE_OK(vec![
             ValueType::Float(0.75f32), 
             ValueType::Float(0.75f32),
@@ -303,98 +2053,673 @@ mod tests {
         for i in 0..5 {
             let _ = database.set(&format!("key{}", i), ValueType::Int(i));
         }
-        let response = database.get_range("key5".to_owned(), "key2".to_owned());
+        let response = database.get_range("key5".to_owned(), "key2".to_owned(), &[]);
+        assert_eq!(response, database_error!(DatabaseErrorType::InvalidRangeOrder));
+
+        // get empty range
+        let _ = database.set("key1", ValueType::Str("val1".to_owned()));
+        let response = database.get_range("a".to_owned(), "b".to_owned(), &[]);
+        assert_eq!(response, Ok(QueryResponseType::GET_RANGE_OK(vec![])));
+    }
+
+    #[test]
+    fn test_get_range_where() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Int);
+        for i in 0..10 {
+            let _ = database.set(&format!("key{}", i), ValueType::Int(i * 5));
+        }
+
+        let predicates = vec![
+            Predicate::Value { operator: ComparisonOperator::Gt, literal: ValueType::Int(10) },
+            Predicate::Value { operator: ComparisonOperator::Le, literal: ValueType::Int(30) },
+        ];
+        let response = database.get_range("key0".to_owned(), "key5".to_owned(), &predicates);
+        assert_eq!(response, Ok(QueryResponseType::GET_RANGE_OK(vec![ValueType::Int(15), ValueType::Int(20), ValueType::Int(25)])));
+
+        let mut database: database::Database = database::Database::new(DatabaseType::Json);
+        let _ = database.set("job1", ValueType::Json("{\"field\":3}".to_string()));
+        let _ = database.set("job2", ValueType::Json("{\"field\":4}".to_string()));
+
+        let predicates = vec![Predicate::Path { path: JsonPath::parse("$.field").unwrap(), operator: ComparisonOperator::Eq, literal: IndexValue::Number(3.0) }];
+        let response = database.get_range("a".to_owned(), "z".to_owned(), &predicates);
+        assert_eq!(response, Ok(QueryResponseType::GET_RANGE_OK(vec![ValueType::Json("{\"field\":3}".to_string())])));
+
+        let mut database: database::Database = database::Database::new(DatabaseType::Timestamp);
+        for i in 0..3 {
+            let _ = database.set(&format!("event{}", i), ValueType::Timestamp(Timestamp::from_millis(i * 1000).unwrap()));
+        }
+
+        let predicates = vec![Predicate::Value { operator: ComparisonOperator::After, literal: ValueType::Timestamp(Timestamp::from_millis(500).unwrap()) }];
+        let response = database.get_range("event0".to_owned(), "event9".to_owned(), &predicates);
+        assert_eq!(response, Ok(QueryResponseType::GET_RANGE_OK(vec![
+            ValueType::Timestamp(Timestamp::from_millis(1000).unwrap()),
+            ValueType::Timestamp(Timestamp::from_millis(2000).unwrap())
+        ])));
+    }
+
+    #[test]
+    fn test_del() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Float);
+        let _ = database.set("key", ValueType::Float(-9.99f32));
+        let _ = database.del("key");
+
+        let response = database.get("key");
+        assert_eq!(response, database_error!(DatabaseErrorType::KeyNotFound("key".to_string())));
+    }
+    
+    #[test]
+    fn test_del_range() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Str);
+
+        // delete range
+        for i in 0..5 {
+            let _ = database.set(&format!("key{}", i), ValueType::Str(format!("val{}", i)));
+        }
+        let response = database.del_range("key2".to_owned(), "key5".to_owned());
+        assert_eq!(response, Ok(QueryResponseType::DEL_RANGE_OK(3)));
+
+        let response = database.get("key3");
+        assert_eq!(response, database_error!(DatabaseErrorType::KeyNotFound("key3".to_string())));
+
+        // wrong order range
+        for i in 0..5 {
+            let _ = database.set(&format!("key{}", i), ValueType::Str(format!("val{}", i)));
+        }
+        let response = database.del_range("key5".to_owned(), "key2".to_owned());
         assert_eq!(response, database_error!(DatabaseErrorType::InvalidRangeOrder));
+    }
+
+    #[test]
+    fn test_del_many() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Int);
+
+        // delete many
+        for i in 0..5 {
+            let _ = database.set(&format!("key{}", i), ValueType::Int(i));
+        }
+        let response = database.del_many(vec!["key1", "key4"]);
+        assert_eq!(response, Ok(QueryResponseType::DEL_MANY_OK(2)));
+
+        let response = database.get("key1");
+        assert_eq!(response, database_error!(DatabaseErrorType::KeyNotFound("key1".to_string())));
+
+        // only existing keys are counted
+        let response = database.del_many(vec!["key1", "key2", "key9"]);
+        assert_eq!(response, Ok(QueryResponseType::DEL_MANY_OK(1)));
+    }
+
+    #[test]
+    fn test_set() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Str);
+
+        // set and get a key to check
+        let response = database.set("key", ValueType::Str("val".to_string()));
+        assert_eq!(response, Ok(database::QueryResponseType::SET_OK));
+
+        let response = database.get("key");
+        assert_eq!(response, Ok(database::QueryResponseType::GET_OK(ValueType::Str("val".to_string()))));
+
+        // set wrong value type
+        let response = database.set("key", ValueType::Int(1));
+        assert_eq!(response, database_error!(DatabaseErrorType::WrongValueType));
+    }
+
+    #[test]
+    fn test_set_many() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Int);
+
+        // set many and get a key to check
+        let response = database.set_many(vec![
+            KeyValuePair { key: "key0".to_owned(), value: ValueType::Int(1) },
+            KeyValuePair { key: "key1".to_owned(), value: ValueType::Int(2) },
+            KeyValuePair { key: "key2".to_owned(), value: ValueType::Int(3) },
+            KeyValuePair { key: "key3".to_owned(), value: ValueType::Int(4) },
+        ]);
+        assert_eq!(response, Ok(database::QueryResponseType::SET_MANY_OK));
+
+        let response = database.get("key2");
+        assert_eq!(response, Ok(database::QueryResponseType::GET_OK(ValueType::Int(3))));
+        
+        // set wrong value type
+        let response = database.set_many(vec![
+            KeyValuePair { key: "key4".to_owned(), value: ValueType::Int(4) },
+            KeyValuePair { key: "key5".to_owned(), value: ValueType::Int(5) },
+            KeyValuePair { key: "key6".to_owned(), value: ValueType::Float(6.1) },
+            KeyValuePair { key: "key7".to_owned(), value: ValueType::Int(7) },
+        ]);
+        assert_eq!(response, database_error!(DatabaseErrorType::WrongValueType));
+
+        // nothing of the failed batch was inserted
+        let response = database.get("key4");
+        assert_eq!(response, database_error!(DatabaseErrorType::KeyNotFound("key4".to_string())));
+    }
+
+    #[test]
+    fn test_rename() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Int);
+        let _ = database.set("key1", ValueType::Int(1));
+        let _ = database.set("key2", ValueType::Int(2));
+
+        let response = database.rename("key1", "key3", false);
+        assert_eq!(response, Ok(QueryResponseType::RENAME_OK));
+        assert_eq!(database.get("key3"), Ok(QueryResponseType::GET_OK(ValueType::Int(1))));
+        assert_eq!(database.exists("key1"), Ok(QueryResponseType::EXISTS_OK(false)));
+
+        // rename onto an existing key
+        let response = database.rename("key3", "key2", true);
+        assert_eq!(response, database_error!(DatabaseErrorType::KeyAlreadyExists("key2".to_string())));
+
+        let response = database.rename("key3", "key2", false);
+        assert_eq!(response, Ok(QueryResponseType::RENAME_OK));
+        assert_eq!(database.get("key2"), Ok(QueryResponseType::GET_OK(ValueType::Int(1))));
+
+        // rename a missing key
+        let response = database.rename("key3", "key4", false);
+        assert_eq!(response, database_error!(DatabaseErrorType::KeyNotFound("key3".to_string())));
+    }
+
+    #[test]
+    fn test_rename_prefix() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Int);
+        for i in 0..3 {
+            let _ = database.set(&format!("user:{}", i), ValueType::Int(i));
+        }
+        let _ = database.set("users", ValueType::Int(10));
+
+        let response = database.rename_prefix("user:", "customer:");
+        assert_eq!(response, Ok(QueryResponseType::RENAME_PREFIX_OK(3)));
+        assert_eq!(database.get("customer:2"), Ok(QueryResponseType::GET_OK(ValueType::Int(2))));
+        assert_eq!(database.exists("user:2"), Ok(QueryResponseType::EXISTS_OK(false)));
+        assert_eq!(database.exists("users"), Ok(QueryResponseType::EXISTS_OK(true)));
+
+        // the new prefix can extend the old one
+        let response = database.rename_prefix("customer:", "customer:v2:");
+        assert_eq!(response, Ok(QueryResponseType::RENAME_PREFIX_OK(3)));
+        assert_eq!(database.get("customer:v2:0"), Ok(QueryResponseType::GET_OK(ValueType::Int(0))));
+        assert_eq!(database.len(), Ok(QueryResponseType::LEN_OK(4)));
+
+        let response = database.rename_prefix("user:", "customer:");
+        assert_eq!(response, database_error!(DatabaseErrorType::PrefixNotFound("user:".to_string())));
+    }
+
+    #[test]
+    fn test_copy() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Str);
+        let _ = database.set("key1", ValueType::Str("val1".to_string()));
+        let _ = database.set("key2", ValueType::Str("val2".to_string()));
+
+        let response = database.copy("key1", "key3", false);
+        assert_eq!(response, Ok(QueryResponseType::COPY_OK));
+        assert_eq!(database.get("key1"), Ok(QueryResponseType::GET_OK(ValueType::Str("val1".to_string()))));
+        assert_eq!(database.get("key3"), Ok(QueryResponseType::GET_OK(ValueType::Str("val1".to_string()))));
+
+        let response = database.copy("key1", "key2", false);
+        assert_eq!(response, database_error!(DatabaseErrorType::KeyAlreadyExists("key2".to_string())));
+
+        let response = database.copy("key1", "key2", true);
+        assert_eq!(response, Ok(QueryResponseType::COPY_OK));
+        assert_eq!(database.get("key2"), Ok(QueryResponseType::GET_OK(ValueType::Str("val1".to_string()))));
+
+        let response = database.copy("key9", "key2", true);
+        assert_eq!(response, database_error!(DatabaseErrorType::KeyNotFound("key9".to_string())));
+    }
+
+    #[test]
+    fn test_lists() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Int);
+
+        let response = database.rpush("queue", vec![ValueType::Int(1), ValueType::Int(2)]);
+        assert_eq!(response, Ok(QueryResponseType::RPUSH_OK(2)));
+
+        // values are pushed one after another, so the last one ends up first
+        let response = database.lpush("queue", vec![ValueType::Int(3), ValueType::Int(4)]);
+        assert_eq!(response, Ok(QueryResponseType::LPUSH_OK(4)));
+
+        let response = database.lrange("queue", 0, -1);
+        assert_eq!(response, Ok(QueryResponseType::LRANGE_OK(vec![ValueType::Int(4), ValueType::Int(3), ValueType::Int(1), ValueType::Int(2)])));
+
+        let response = database.lrange("queue", -2, 10);
+        assert_eq!(response, Ok(QueryResponseType::LRANGE_OK(vec![ValueType::Int(1), ValueType::Int(2)])));
+
+        let response = database.lpush("queue", vec![ValueType::Int(5), ValueType::Str("x".to_string())]);
+        assert_eq!(response, database_error!(DatabaseErrorType::WrongValueType));
+
+        let response = database.lpop("queue");
+        assert_eq!(response, Ok(QueryResponseType::LPOP_OK(Some(ValueType::Int(4)))));
+
+        let response = database.rpop("queue");
+        assert_eq!(response, Ok(QueryResponseType::RPOP_OK(Some(ValueType::Int(2)))));
+
+        // the list is removed once it's empty
+        let _ = database.lpop("queue");
+        let _ = database.lpop("queue");
+        assert_eq!(database.exists("queue"), Ok(QueryResponseType::EXISTS_OK(false)));
+
+        let response = database.lpop("queue");
+        assert_eq!(response, Ok(QueryResponseType::LPOP_OK(None)));
+
+        let response = database.lrange("queue", 0, -1);
+        assert_eq!(response, Ok(QueryResponseType::LRANGE_OK(vec![])));
+    }
+
+    #[test]
+    fn test_sets() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Str);
+        let string = |value: &str| ValueType::Str(value.to_string());
+
+        let response = database.sadd("tags", vec![string("b"), string("a"), string("b")]);
+        assert_eq!(response, Ok(QueryResponseType::SADD_OK(2)));
+
+        let response = database.sadd("tags", vec![string("a"), string("c")]);
+        assert_eq!(response, Ok(QueryResponseType::SADD_OK(1)));
+
+        let response = database.smembers("tags");
+        assert_eq!(response, Ok(QueryResponseType::SMEMBERS_OK(vec![string("a"), string("b"), string("c")])));
+
+        let response = database.sismember("tags", &string("b"));
+        assert_eq!(response, Ok(QueryResponseType::SISMEMBER_OK(true)));
+
+        let response = database.srem("tags", vec![string("b"), string("x")]);
+        assert_eq!(response, Ok(QueryResponseType::SREM_OK(1)));
+
+        let response = database.sismember("tags", &string("b"));
+        assert_eq!(response, Ok(QueryResponseType::SISMEMBER_OK(false)));
+
+        let response = database.srem("tags", vec![string("a"), string("c")]);
+        assert_eq!(response, Ok(QueryResponseType::SREM_OK(2)));
+        assert_eq!(database.len(), Ok(QueryResponseType::LEN_OK(0)));
+
+        let response = database.smembers("tags");
+        assert_eq!(response, Ok(QueryResponseType::SMEMBERS_OK(vec![])));
+    }
+
+    #[test]
+    fn test_hashes() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Int);
+        let field = |field: &str, value: i32| KeyValuePair { key: field.to_string(), value: ValueType::Int(value) };
+
+        let response = database.hset("user:1", vec![field("height", 180), field("age", 30)]);
+        assert_eq!(response, Ok(QueryResponseType::HSET_OK(2)));
+
+        let response = database.hset("user:1", vec![field("age", 31), field("weight", 80)]);
+        assert_eq!(response, Ok(QueryResponseType::HSET_OK(1)));
+
+        let response = database.hget("user:1", "age");
+        assert_eq!(response, Ok(QueryResponseType::HGET_OK(ValueType::Int(31))));
+
+        let response = database.hget("user:1", "name");
+        assert_eq!(response, database_error!(DatabaseErrorType::FieldNotFound("user:1".to_string(), "name".to_string())));
+
+        let response = database.hget("user:2", "age");
+        assert_eq!(response, database_error!(DatabaseErrorType::KeyNotFound("user:2".to_string())));
+
+        let response = database.hgetall("user:1");
+        assert_eq!(response, Ok(QueryResponseType::HGETALL_OK(vec![field("age", 31), field("height", 180), field("weight", 80)])));
+
+        let response = database.hdel("user:1", vec!["age".to_string(), "name".to_string()]);
+        assert_eq!(response, Ok(QueryResponseType::HDEL_OK(1)));
+
+        let response = database.hdel("user:1", vec!["height".to_string(), "weight".to_string()]);
+        assert_eq!(response, Ok(QueryResponseType::HDEL_OK(2)));
+        assert_eq!(database.exists("user:1"), Ok(QueryResponseType::EXISTS_OK(false)));
+    }
+
+    #[test]
+    fn test_sorted_sets() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Str);
+        let member = |member: &str, score: f64| ScoredMember { member: ValueType::Str(member.to_string()), score };
+
+        let response = database.zadd("board", vec![member("alice", 10.0), member("bob", 30.0), member("carol", 20.0)]);
+        assert_eq!(response, Ok(QueryResponseType::ZADD_OK(3)));
+
+        // updating a score doesn't count as a new member
+        let response = database.zadd("board", vec![member("alice", 25.0), member("dave", 5.0)]);
+        assert_eq!(response, Ok(QueryResponseType::ZADD_OK(1)));
+
+        let response = database.zrange("board", 0, -1, false);
+        assert_eq!(response, Ok(QueryResponseType::ZRANGE_OK(vec![member("dave", 5.0), member("carol", 20.0), member("alice", 25.0), member("bob", 30.0)])));
+
+        // the top two of the leaderboard
+        let response = database.zrange("board", 0, 1, true);
+        assert_eq!(response, Ok(QueryResponseType::ZRANGE_OK(vec![member("bob", 30.0), member("alice", 25.0)])));
+
+        let response = database.zrange_by_score("board", 20.0, 25.0);
+        assert_eq!(response, Ok(QueryResponseType::ZRANGE_OK(vec![member("carol", 20.0), member("alice", 25.0)])));
+
+        let response = database.zincrby("board", 10.0, ValueType::Str("carol".to_string()));
+        assert_eq!(response, Ok(QueryResponseType::ZINCRBY_OK(30.0)));
+
+        // members with the same score are ordered by the member
+        let response = database.zrank("board", &ValueType::Str("carol".to_string()), false);
+        assert_eq!(response, Ok(QueryResponseType::ZRANK_OK(Some(3))));
+
+        let response = database.zrank("board", &ValueType::Str("carol".to_string()), true);
+        assert_eq!(response, Ok(QueryResponseType::ZRANK_OK(Some(0))));
+
+        let response = database.zrank("board", &ValueType::Str("erin".to_string()), false);
+        assert_eq!(response, Ok(QueryResponseType::ZRANK_OK(None)));
+
+        let response = database.zcount("board", f64::NEG_INFINITY, 25.0);
+        assert_eq!(response, Ok(QueryResponseType::ZCOUNT_OK(2)));
+
+        let response = database.zincrby("board", f64::INFINITY, ValueType::Str("erin".to_string()));
+        assert_eq!(response, Ok(QueryResponseType::ZINCRBY_OK(f64::INFINITY)));
+
+        let response = database.zincrby("board", f64::NEG_INFINITY, ValueType::Str("erin".to_string()));
+        assert_eq!(response, database_error!(DatabaseErrorType::ScoreNotANumber));
+
+        let response = database.zrem("board", vec![ValueType::Str("erin".to_string()), ValueType::Str("frank".to_string())]);
+        assert_eq!(response, Ok(QueryResponseType::ZREM_OK(1)));
+
+        let response = database.zadd("board", vec![ScoredMember { member: ValueType::Int(1), score: 1.0 }]);
+        assert_eq!(response, database_error!(DatabaseErrorType::WrongValueType));
 
-        // get empty range
-        let _ = database.set("key1", ValueType::Str("val1".to_owned()));
-        let response = database.get_range("a".to_owned(), "b".to_owned());
-        assert_eq!(response, Ok(QueryResponseType::GET_RANGE_OK(vec![])));
+        let response = database.smembers("board");
+        assert_eq!(response, database_error!(DatabaseErrorType::WrongKind("board".to_string(), "ZSET".to_string(), "SET".to_string())));
+
+        let response = database.zrange("missing", 0, -1, false);
+        assert_eq!(response, Ok(QueryResponseType::ZRANGE_OK(vec![])));
     }
 
     #[test]
-    fn test_del() {
-        let mut database: database::Database = database::Database::new(DatabaseType::Float);
-        let _ = database.set("key", ValueType::Float(-9.99f32));
-        let _ = database.del("key");
-
-        let response = database.get("key");
-        assert_eq!(response, database_error!(DatabaseErrorType::KeyNotFound("key".to_string())));
+    fn test_collection_kinds() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Int);
+        let _ = database.set("counter", ValueType::Int(1));
+        let _ = database.rpush("queue", vec![ValueType::Int(1)]);
+
+        // a key holds either a single value or one kind of collection
+        let response = database.sadd("queue", vec![ValueType::Int(1)]);
+        assert_eq!(response, database_error!(DatabaseErrorType::WrongKind("queue".to_string(), "LIST".to_string(), "SET".to_string())));
+
+        let response = database.lpush("counter", vec![ValueType::Int(1)]);
+        assert_eq!(response, database_error!(DatabaseErrorType::WrongKind("counter".to_string(), "VALUE".to_string(), "LIST".to_string())));
+
+        let response = database.get("queue");
+        assert_eq!(response, database_error!(DatabaseErrorType::WrongKind("queue".to_string(), "LIST".to_string(), "VALUE".to_string())));
+
+        assert_eq!(database.len(), Ok(QueryResponseType::LEN_OK(2)));
+
+        // writing a value doesn't replace a collection either
+        let _ = database.lpush("jobs", vec![ValueType::Int(7)]);
+        let response = database.set("jobs", ValueType::Int(2));
+        assert_eq!(response, database_error!(DatabaseErrorType::WrongKind("jobs".to_string(), "LIST".to_string(), "VALUE".to_string())));
+        let response = database.set_many(vec![KeyValuePair { key: "other".to_string(), value: ValueType::Int(1) }, KeyValuePair { key: "jobs".to_string(), value: ValueType::Int(2) }]);
+        assert_eq!(response, database_error!(DatabaseErrorType::WrongKind("jobs".to_string(), "LIST".to_string(), "VALUE".to_string())));
+        assert_eq!(database.exists("other"), Ok(QueryResponseType::EXISTS_OK(false)));
+        let response = database.rename("counter", "jobs", false);
+        assert_eq!(response, database_error!(DatabaseErrorType::WrongKind("jobs".to_string(), "LIST".to_string(), "VALUE".to_string())));
+        let response = database.copy("counter", "jobs", true);
+        assert_eq!(response, database_error!(DatabaseErrorType::WrongKind("jobs".to_string(), "LIST".to_string(), "VALUE".to_string())));
+        assert_eq!(database.lrange("jobs", 0, -1), Ok(QueryResponseType::LRANGE_OK(vec![ValueType::Int(7)])));
+        let _ = database.del("jobs");
+
+        let _ = database.hset("user", vec![KeyValuePair { key: "age".to_string(), value: ValueType::Int(30) }]);
+        let response = database.del_many(vec!["user", "queue", "missing"]);
+        assert_eq!(response, Ok(QueryResponseType::DEL_MANY_OK(2)));
+
+        let _ = database.sadd("tags", vec![ValueType::Int(1)]);
+        let _ = database.clear();
+        assert_eq!(database.exists("tags"), Ok(QueryResponseType::EXISTS_OK(false)));
     }
-    
-    #[test]
-    fn test_del_range() {
-        let mut database: database::Database = database::Database::new(DatabaseType::Str);
 
-        // delete range
-        for i in 0..5 {
-            let _ = database.set(&format!("key{}", i), ValueType::Str(format!("val{}", i)));
-        }
-        let _ = database.del_range("key2".to_owned(), "key5".to_owned());
-        let response = database.get("key3");
-        assert_eq!(response, database_error!(DatabaseErrorType::KeyNotFound("key3".to_string())));
+    #[test]
+    fn test_key_commands_on_collections() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Int);
+        let _ = database.set("user:1", ValueType::Int(1));
+        let _ = database.rpush("user:2", vec![ValueType::Int(2)]);
+        let _ = database.sadd("user:3", vec![ValueType::Int(3)]);
+        let _ = database.hset("user:4", vec![KeyValuePair { key: "age".to_string(), value: ValueType::Int(30) }]);
+
+        // KEYS lists collections in key order with the values
+        let response = database.keys("^user:.*$", "user:", 10);
+        assert_eq!(response, Ok(QueryResponseType::KEYS_OK(vec!["user:1".to_string(), "user:2".to_string(), "user:3".to_string(), "user:4".to_string()])));
+        let response = database.keys("^user:.*$", "user:", 2);
+        assert_eq!(response, Ok(QueryResponseType::KEYS_OK(vec!["user:1".to_string(), "user:2".to_string()])));
+
+        // RENAME and COPY move and clone collections
+        assert_eq!(database.rename("user:2", "queue", true), Ok(QueryResponseType::RENAME_OK));
+        assert_eq!(database.lrange("queue", 0, -1), Ok(QueryResponseType::LRANGE_OK(vec![ValueType::Int(2)])));
+        assert_eq!(database.exists("user:2"), Ok(QueryResponseType::EXISTS_OK(false)));
+        assert_eq!(database.copy("queue", "backup", false), Ok(QueryResponseType::COPY_OK));
+        let _ = database.rpush("queue", vec![ValueType::Int(5)]);
+        assert_eq!(database.lrange("backup", 0, -1), Ok(QueryResponseType::LRANGE_OK(vec![ValueType::Int(2)])));
+
+        // an existing key is only overwritten by the same kind of thing
+        assert_eq!(database.copy("queue", "backup", false), database_error!(DatabaseErrorType::KeyAlreadyExists("backup".to_string())));
+        assert_eq!(database.rename("queue", "user:3", false), database_error!(DatabaseErrorType::WrongKind("user:3".to_string(), "SET".to_string(), "LIST".to_string())));
+        assert_eq!(database.rename("queue", "user:1", false), database_error!(DatabaseErrorType::WrongKind("user:1".to_string(), "VALUE".to_string(), "LIST".to_string())));
+        assert_eq!(database.copy("queue", "backup", true), Ok(QueryResponseType::COPY_OK));
+        assert_eq!(database.lrange("backup", 0, -1), Ok(QueryResponseType::LRANGE_OK(vec![ValueType::Int(2), ValueType::Int(5)])));
+
+        // RENAME PREFIX moves values and collections
+        assert_eq!(database.rename_prefix("user:", "customer:"), Ok(QueryResponseType::RENAME_PREFIX_OK(3)));
+        assert_eq!(database.smembers("customer:3"), Ok(QueryResponseType::SMEMBERS_OK(vec![ValueType::Int(3)])));
+        assert_eq!(database.hget("customer:4", "age"), Ok(QueryResponseType::HGET_OK(ValueType::Int(30))));
+
+        // DEL RANGE deletes collections too
+        assert_eq!(database.del_range("customer:".to_string(), "customer:9".to_string()), Ok(QueryResponseType::DEL_RANGE_OK(3)));
+        assert_eq!(database.len(), Ok(QueryResponseType::LEN_OK(2)));
+    }
 
-        // wrong order range
-        for i in 0..5 {
-            let _ = database.set(&format!("key{}", i), ValueType::Str(format!("val{}", i)));
-        }
-        let response = database.del_range("key5".to_owned(), "key2".to_owned());
-        assert_eq!(response, database_error!(DatabaseErrorType::InvalidRangeOrder));
+    #[test]
+    fn test_json() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Json);
+        let path = |path: &str| JsonPath::parse(path).unwrap();
+
+        // create a document by setting the root
+        let response = database.json_set("user", &path("$"), "{\"name\":\"anon\",\"tags\":[\"a\"]}");
+        assert_eq!(response, Ok(QueryResponseType::JSON_SET_OK));
+
+        let response = database.json_get("user", &path("$.tags[0]"));
+        assert_eq!(response, Ok(QueryResponseType::JSON_GET_OK("\"a\"".to_string())));
+
+        // update single fields
+        let _ = database.json_set("user", &path("$.age"), "30");
+        let _ = database.json_set("user", &path("$.tags[1]"), "\"b\"");
+        let _ = database.json_del("user", &path("$.name"));
+        let response = database.get("user");
+        assert_eq!(response, Ok(QueryResponseType::GET_OK(ValueType::Json("{\"age\":30,\"tags\":[\"a\",\"b\"]}".to_string()))));
+
+        let response = database.json_merge("user", "{\"age\":null,\"status\":\"failed\"}");
+        assert_eq!(response, Ok(QueryResponseType::JSON_MERGE_OK));
+        let response = database.json_get("user", &path("$"));
+        assert_eq!(response, Ok(QueryResponseType::JSON_GET_OK("{\"status\":\"failed\",\"tags\":[\"a\",\"b\"]}".to_string())));
+
+        let response = database.json_merge("user", "{status: ok}");
+        assert_eq!(response, database_error!(DatabaseErrorType::InvalidJson));
+        let response = database.json_set("user", &path("$.status"), "ok");
+        assert_eq!(response, database_error!(DatabaseErrorType::InvalidJson));
+
+        // a stored document that isn't valid JSON fails the JSON commands instead of crashing the server, and isn't indexed
+        database.insert_entry("broken".to_owned(), serialize(&ValueType::Json("{".to_string())).unwrap());
+        let response = database.json_get("broken", &path("$"));
+        assert_eq!(response, database_error!(DatabaseErrorType::InvalidJson));
+        let response = database.index_create("by_status", path("$.status"));
+        assert_eq!(response, Ok(QueryResponseType::INDEX_CREATE_OK));
+        let _ = database.del("broken");
+
+        // missing paths and keys
+        let response = database.json_get("user", &path("$.name"));
+        assert_eq!(response, database_error!(DatabaseErrorType::PathNotFound("$.name".to_string())));
+
+        let response = database.json_set("user", &path("$.address.city"), "\"Berlin\"");
+        assert_eq!(response, database_error!(DatabaseErrorType::PathNotFound("$.address.city".to_string())));
+
+        let response = database.json_set("admin", &path("$.name"), "\"root\"");
+        assert_eq!(response, database_error!(DatabaseErrorType::KeyNotFound("admin".to_string())));
+
+        // deleting the root deletes the key
+        let _ = database.json_del("user", &path("$"));
+        assert_eq!(database.exists("user"), Ok(QueryResponseType::EXISTS_OK(false)));
+
+        // JSON commands on non-JSON databases
+        let database: database::Database = database::Database::new(DatabaseType::Str);
+        let response = database.json_get("user", &path("$"));
+        assert_eq!(response, database_error!(DatabaseErrorType::UnsupportedCommand("JSON.GET".to_string(), "STR".to_string())));
     }
 
     #[test]
-    fn test_del_many() {
-        let mut database: database::Database = database::Database::new(DatabaseType::Int);
+    fn test_json_schema() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Json);
+        assert_eq!(database.schema_get(), Ok(QueryResponseType::SCHEMA_GET_OK(None)));
 
-        // delete many
-        for i in 0..5 {
-            let _ = database.set(&format!("key{}", i), ValueType::Int(i));
-        }
-        let _ = database.del_many(vec!["key1", "key4"]);
-        let response = database.get("key1");
-        assert_eq!(response, database_error!(DatabaseErrorType::KeyNotFound("key1".to_string())));
+        let schema = serde_json::json!({"type": "object", "required": ["status"], "properties": {"status": {"enum": ["ok", "failed"]}}});
+        database.set_json_schema(JsonSchema::new(schema.clone()).unwrap());
+        assert_eq!(database.schema_get(), Ok(QueryResponseType::SCHEMA_GET_OK(Some(schema.to_string()))));
+
+        let response = database.set("job1", ValueType::Json("{\"status\":\"ok\"}".to_string()));
+        assert_eq!(response, Ok(QueryResponseType::SET_OK));
+
+        let response = database.set("job2", ValueType::Json("{\"status\":\"unknown\"}".to_string()));
+        assert_eq!(response, database_error!(DatabaseErrorType::SchemaViolation("$.status".to_string(), "expected one of [\"ok\",\"failed\"]".to_string())));
+
+        // a single violation rejects the whole batch
+        let response = database.set_many(vec![
+            KeyValuePair { key: "job3".to_owned(), value: ValueType::Json("{\"status\":\"failed\"}".to_string()) },
+            KeyValuePair { key: "job4".to_owned(), value: ValueType::Json("{}".to_string()) },
+        ]);
+        assert_eq!(response, database_error!(DatabaseErrorType::SchemaViolation("$".to_string(), "missing required property 'status'".to_string())));
+        assert_eq!(database.exists("job3"), Ok(QueryResponseType::EXISTS_OK(false)));
+
+        // partial updates are validated too
+        let response = database.json_del("job1", &JsonPath::parse("$.status").unwrap());
+        assert_eq!(response, database_error!(DatabaseErrorType::SchemaViolation("$".to_string(), "missing required property 'status'".to_string())));
+        assert_eq!(database.get("job1"), Ok(QueryResponseType::GET_OK(ValueType::Json("{\"status\":\"ok\"}".to_string()))));
     }
 
     #[test]
-    fn test_set() {
-        let mut database: database::Database = database::Database::new(DatabaseType::Str);
+    fn test_json_index() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Json);
+        let failed: IndexValue = IndexValue::Str("failed".to_string());
 
-        // set and get a key to check
-        let response = database.set("key", ValueType::Str("val".to_string()));
-        assert_eq!(response, Ok(database::QueryResponseType::SET_OK));
+        let _ = database.set_many(vec![
+            KeyValuePair { key: "job1".to_owned(), value: ValueType::Json("{\"status\":\"failed\",\"retries\":3}".to_string()) },
+            KeyValuePair { key: "job2".to_owned(), value: ValueType::Json("{\"status\":\"ok\",\"retries\":0}".to_string()) },
+        ]);
 
-        let response = database.get("key");
-        assert_eq!(response, Ok(database::QueryResponseType::GET_OK(ValueType::Str("val".to_string()))));
+        // existing documents get indexed on creation
+        let response = database.index_create("by_status", JsonPath::parse("$.status").unwrap());
+        assert_eq!(response, Ok(QueryResponseType::INDEX_CREATE_OK));
+        let _ = database.index_create("by_retries", JsonPath::parse("$.retries").unwrap());
 
-        // set wrong value type
-        let response = database.set("key", ValueType::Int(1));
-        assert_eq!(response, database_error!(DatabaseErrorType::WrongValueType));
+        let response = database.index_create("by_status", JsonPath::parse("$.state").unwrap());
+        assert_eq!(response, database_error!(DatabaseErrorType::IndexAlreadyExists("by_status".to_string())));
+
+        let response = database.find("by_status", &failed, &failed);
+        assert_eq!(response, Ok(QueryResponseType::FIND_OK(vec![
+            KeyValuePair { key: "job1".to_owned(), value: ValueType::Json("{\"status\":\"failed\",\"retries\":3}".to_string()) }
+        ])));
+
+        // every kind of write keeps the indexes up to date
+        let _ = database.json_set("job2", &JsonPath::parse("$.status").unwrap(), "\"failed\"");
+        let _ = database.copy("job2", "job3", false);
+        let _ = database.rename("job1", "job0", false);
+        let _ = database.set("job4", ValueType::Json("{\"status\":\"failed\",\"retries\":1}".to_string()));
+        let _ = database.json_merge("job4", "{\"status\":\"ok\"}");
+        let keys = |response: Result<QueryResponseType, String>| match response {
+            Ok(QueryResponseType::FIND_OK(pairs)) => pairs.into_iter().map(|pair| pair.key).collect::<Vec<String>>(),
+            _ => panic!("expected FIND_OK")
+        };
+        assert_eq!(keys(database.find("by_status", &failed, &failed)), vec!["job0", "job2", "job3"]);
+        assert_eq!(keys(database.find("by_retries", &IndexValue::Number(1.0), &IndexValue::Number(5.0))), vec!["job4", "job0"]);
+
+        let _ = database.del("job0");
+        let _ = database.del_range("job3".to_string(), "job9".to_string());
+        assert_eq!(keys(database.find("by_status", &failed, &failed)), vec!["job2"]);
+
+        let _ = database.clear();
+        assert_eq!(keys(database.find("by_status", &failed, &failed)), Vec::<String>::new());
+
+        assert_eq!(database.index_drop("by_status"), Ok(QueryResponseType::INDEX_DROP_OK));
+        assert_eq!(database.find("by_status", &failed, &failed), database_error!(DatabaseErrorType::IndexNotFound("by_status".to_string())));
+
+        let mut database: database::Database = database::Database::new(DatabaseType::Str);
+        let response = database.index_create("by_status", JsonPath::parse("$.status").unwrap());
+        assert_eq!(response, database_error!(DatabaseErrorType::UnsupportedCommand("INDEX CREATE".to_string(), "STR".to_string())));
     }
 
     #[test]
-    fn test_set_many() {
-        let mut database: database::Database = database::Database::new(DatabaseType::Int);
+    fn test_find_value() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Bool);
+        let response = database.find_value(&ValueType::Bool(true), None);
+        assert_eq!(response, database_error!(DatabaseErrorType::ValueIndexDisabled));
 
-        // set many and get a key to check
-        let response = database.set_many(vec![
-            KeyValuePair { key: "key0".to_owned(), value: ValueType::Int(1) },
-            KeyValuePair { key: "key1".to_owned(), value: ValueType::Int(2) },
-            KeyValuePair { key: "key2".to_owned(), value: ValueType::Int(3) },
-            KeyValuePair { key: "key3".to_owned(), value: ValueType::Int(4) },
+        database.enable_value_index();
+        let _ = database.set_many(vec![
+            KeyValuePair { key: "flag1".to_owned(), value: ValueType::Bool(true) },
+            KeyValuePair { key: "flag2".to_owned(), value: ValueType::Bool(false) },
+            KeyValuePair { key: "flag3".to_owned(), value: ValueType::Bool(true) },
         ]);
-        assert_eq!(response, Ok(database::QueryResponseType::SET_MANY_OK));
+        let _ = database.set("flag3", ValueType::Bool(false));
+        let response = database.find_value(&ValueType::Bool(true), None);
+        assert_eq!(response, Ok(QueryResponseType::FIND_OK(vec![KeyValuePair { key: "flag1".to_owned(), value: ValueType::Bool(true) }])));
 
-        let response = database.get("key2");
-        assert_eq!(response, Ok(database::QueryResponseType::GET_OK(ValueType::Int(3))));
-        
-        // set wrong value type
-        let response = database.set_many(vec![
-            KeyValuePair { key: "key4".to_owned(), value: ValueType::Int(4) },
-            KeyValuePair { key: "key5".to_owned(), value: ValueType::Int(5) },
-            KeyValuePair { key: "key6".to_owned(), value: ValueType::Float(6.1) },
-            KeyValuePair { key: "key7".to_owned(), value: ValueType::Int(7) },
+        let response = database.find_value(&ValueType::Bool(false), Some(&ValueType::Bool(true)));
+        assert_eq!(response, database_error!(DatabaseErrorType::UnsupportedCommand("FIND VALUE BETWEEN".to_string(), "BOOL".to_string())));
+
+        let mut database: database::Database = database::Database::new(DatabaseType::Int);
+        database.enable_value_index();
+        let _ = database.set_many(vec![
+            KeyValuePair { key: "a".to_owned(), value: ValueType::Int(30) },
+            KeyValuePair { key: "b".to_owned(), value: ValueType::Int(10) },
+            KeyValuePair { key: "c".to_owned(), value: ValueType::Int(20) },
+            KeyValuePair { key: "d".to_owned(), value: ValueType::Int(10) },
         ]);
+        let keys = |response: Result<QueryResponseType, String>| match response {
+            Ok(QueryResponseType::FIND_OK(pairs)) => pairs.into_iter().map(|pair| pair.key).collect::<Vec<String>>(),
+            _ => panic!("expected FIND_OK")
+        };
+        assert_eq!(keys(database.find_value(&ValueType::Int(10), Some(&ValueType::Int(25)))), vec!["b", "d", "c"]);
+
+        let _ = database.del("b");
+        let _ = database.del_range("c".to_string(), "z".to_string());
+        let _ = database.rename("a", "e", false);
+        assert_eq!(keys(database.find_value(&ValueType::Int(0), Some(&ValueType::Int(100)))), vec!["e"]);
+
+        let _ = database.clear();
+        assert_eq!(keys(database.find_value(&ValueType::Int(0), Some(&ValueType::Int(100)))), Vec::<String>::new());
+
+        let response = database.find_value(&ValueType::Int(0), Some(&ValueType::Float(1.0)));
         assert_eq!(response, database_error!(DatabaseErrorType::WrongValueType));
+
+        // neighbouring 64 bit ints above 2^53 (the same float) are told apart
+        let mut database: database::Database = database::Database::new(DatabaseType::Int64);
+        database.enable_value_index();
+        let _ = database.set("order:1", ValueType::Int64(9007199254740992));
+        let _ = database.set("order:2", ValueType::Int64(9007199254740993));
+        assert_eq!(keys(database.find_value(&ValueType::Int64(9007199254740993), None)), vec!["order:2"]);
+        assert_eq!(keys(database.find_value(&ValueType::Int64(9007199254740992), Some(&ValueType::Int64(9007199254740993)))), vec!["order:1", "order:2"]);
+
+        let mut database: database::Database = database::Database::new(DatabaseType::Decimal);
+        database.enable_value_index();
+        let _ = database.set("price:1", ValueType::Decimal(Decimal::parse("1000000000000000.01").unwrap()));
+        let _ = database.set("price:2", ValueType::Decimal(Decimal::parse("1000000000000000.02").unwrap()));
+        assert_eq!(keys(database.find_value(&ValueType::Decimal(Decimal::parse("1000000000000000.020").unwrap()), None)), vec!["price:2"]);
+    }
+
+    #[test]
+    fn test_bytes() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Bytes);
+        database.set_max_bytes_size(4);
+
+        let response = database.set("key", ValueType::Bytes(vec![0, 1, 2, 3]));
+        assert_eq!(response, Ok(QueryResponseType::SET_OK));
+        assert_eq!(database.get("key"), Ok(QueryResponseType::GET_OK(ValueType::Bytes(vec![0, 1, 2, 3]))));
+        assert_eq!(database.strlen("key"), Ok(QueryResponseType::STRLEN_OK(4)));
+        assert_eq!(database.strlen("missing"), database_error!(DatabaseErrorType::KeyNotFound("missing".to_string())));
+
+        let response = database.set("key", ValueType::Bytes(vec![0; 5]));
+        assert_eq!(response, database_error!(DatabaseErrorType::ValueTooLarge(5, 4)));
+
+        let response = database.set_many(vec![KeyValuePair { key: "key2".to_owned(), value: ValueType::Bytes(vec![0; 5]) }]);
+        assert_eq!(response, database_error!(DatabaseErrorType::ValueTooLarge(5, 4)));
+
+        let mut database: database::Database = database::Database::new(DatabaseType::Str);
+        let _ = database.set("key", ValueType::Str("größe".to_string()));
+        assert_eq!(database.strlen("key"), Ok(QueryResponseType::STRLEN_OK(7)));
+
+        let database: database::Database = database::Database::new(DatabaseType::Int);
+        assert_eq!(database.strlen("key"), database_error!(DatabaseErrorType::UnsupportedCommand("STRLEN".to_string(), "INT".to_string())));
     }
 
     #[test]
@@ -440,4 +2765,517 @@ mod tests {
         assert_eq!(response, Ok(database::QueryResponseType::EXISTS_OK(false)));
     }
 
+    #[test]
+    fn test_navigation() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Int);
+
+        // navigate on an empty database
+        assert_eq!(database.first(), Ok(QueryResponseType::FIRST_OK(None)));
+        assert_eq!(database.last(), Ok(QueryResponseType::LAST_OK(None)));
+
+        for i in [10, 20, 30] {
+            let _ = database.set(&format!("ts{}", i), ValueType::Int(i));
+        }
+        let pair = |key: &str, value: i32| Some(KeyValuePair { key: key.to_owned(), value: ValueType::Int(value) });
+
+        assert_eq!(database.first(), Ok(QueryResponseType::FIRST_OK(pair("ts10", 10))));
+        assert_eq!(database.last(), Ok(QueryResponseType::LAST_OK(pair("ts30", 30))));
+
+        // existing keys
+        assert_eq!(database.next("ts20"), Ok(QueryResponseType::NEXT_OK(pair("ts30", 30))));
+        assert_eq!(database.prev("ts20"), Ok(QueryResponseType::PREV_OK(pair("ts10", 10))));
+        assert_eq!(database.floor("ts20"), Ok(QueryResponseType::FLOOR_OK(pair("ts20", 20))));
+        assert_eq!(database.ceil("ts20"), Ok(QueryResponseType::CEIL_OK(pair("ts20", 20))));
+
+        // keys in between existing keys
+        assert_eq!(database.floor("ts25"), Ok(QueryResponseType::FLOOR_OK(pair("ts20", 20))));
+        assert_eq!(database.ceil("ts25"), Ok(QueryResponseType::CEIL_OK(pair("ts30", 30))));
+
+        // keys at the borders
+        assert_eq!(database.next("ts30"), Ok(QueryResponseType::NEXT_OK(None)));
+        assert_eq!(database.prev("ts10"), Ok(QueryResponseType::PREV_OK(None)));
+        assert_eq!(database.floor("a"), Ok(QueryResponseType::FLOOR_OK(None)));
+        assert_eq!(database.ceil("z"), Ok(QueryResponseType::CEIL_OK(None)));
+    }
+
+    #[test]
+    fn test_aggregate() {
+        // aggregate ints
+        let mut database: database::Database = database::Database::new(DatabaseType::Int);
+        for i in 0..5 {
+            let _ = database.set(&format!("key{}", i), ValueType::Int(i32::MAX - i));
+        }
+        let response = database.aggregate(AggregationType::Sum, "key0".to_owned(), "key1".to_owned());
+        assert_eq!(response, Ok(QueryResponseType::AGG_OK(AggregationType::Sum, AggregationResult::Int(2 * i32::MAX as i128 - 1))));
+
+        let response = database.aggregate(AggregationType::Min, "key1".to_owned(), "key9".to_owned());
+        assert_eq!(response, Ok(QueryResponseType::AGG_OK(AggregationType::Min, AggregationResult::Int(i32::MAX as i128 - 4))));
+
+        let response = database.aggregate(AggregationType::Count, "key3".to_owned(), "key9".to_owned());
+        assert_eq!(response, Ok(QueryResponseType::AGG_OK(AggregationType::Count, AggregationResult::Int(2))));
+
+        // aggregate floats
+        let mut database: database::Database = database::Database::new(DatabaseType::Float);
+        for i in 0..4 {
+            let _ = database.set(&format!("key{}", i), ValueType::Float(i as f32 * 0.5));
+        }
+        let response = database.aggregate(AggregationType::Avg, "key0".to_owned(), "key3".to_owned());
+        assert_eq!(response, Ok(QueryResponseType::AGG_OK(AggregationType::Avg, AggregationResult::Float(0.75))));
+
+        let response = database.aggregate(AggregationType::Max, "a".to_owned(), "b".to_owned());
+        assert_eq!(response, Ok(QueryResponseType::AGG_OK(AggregationType::Max, AggregationResult::None)));
+
+        // aggregate 64 bit ints and decimals
+        let mut database: database::Database = database::Database::new(DatabaseType::Int64);
+        let _ = database.set("key0", ValueType::Int64(i64::MAX));
+        let _ = database.set("key1", ValueType::Int64(i64::MAX));
+        let response = database.aggregate(AggregationType::Sum, "key0".to_owned(), "key1".to_owned());
+        assert_eq!(response, Ok(QueryResponseType::AGG_OK(AggregationType::Sum, AggregationResult::Int(2 * i64::MAX as i128))));
+
+        let mut database: database::Database = database::Database::new(DatabaseType::Decimal);
+        let _ = database.set("key0", ValueType::Decimal(Decimal::parse("0.1").unwrap()));
+        let _ = database.set("key1", ValueType::Decimal(Decimal::parse("0.2").unwrap()));
+        let response = database.aggregate(AggregationType::Sum, "key0".to_owned(), "key1".to_owned());
+        assert_eq!(response, Ok(QueryResponseType::AGG_OK(AggregationType::Sum, AggregationResult::Decimal(Decimal::parse("0.3").unwrap()))));
+
+        // aggregate timestamps, only MIN, MAX and COUNT
+        let mut database: database::Database = database::Database::new(DatabaseType::Timestamp);
+        let _ = database.set("key0", ValueType::Timestamp(Timestamp::from_millis(1697000800000).unwrap()));
+        let _ = database.set("key1", ValueType::Timestamp(Timestamp::from_millis(0).unwrap()));
+        let response = database.aggregate(AggregationType::Min, "key0".to_owned(), "key1".to_owned());
+        assert_eq!(response, Ok(QueryResponseType::AGG_OK(AggregationType::Min, AggregationResult::Timestamp(Timestamp::from_millis(0).unwrap()))));
+
+        let response = database.aggregate(AggregationType::Avg, "key0".to_owned(), "key1".to_owned());
+        assert_eq!(response, database_error!(DatabaseErrorType::UnsupportedAggregationType("AVG".to_string(), "TIMESTAMP".to_string())));
+
+        // wrong range order
+        let response = database.aggregate(AggregationType::Sum, "key3".to_owned(), "key0".to_owned());
+        assert_eq!(response, database_error!(DatabaseErrorType::InvalidRangeOrder));
+
+        // aggregate on non-numeric database
+        let database: database::Database = database::Database::new(DatabaseType::Str);
+        let response = database.aggregate(AggregationType::Sum, "key0".to_owned(), "key3".to_owned());
+        assert_eq!(response, database_error!(DatabaseErrorType::UnsupportedAggregation("STR".to_string())));
+    }
+
+    #[test]
+    fn test_natural_key_order() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Int);
+        database.set_key_order(KeyOrder::Natural);
+        for i in [1, 2, 9, 10, 11] {
+            let _ = database.set(&format!("key{}", i), ValueType::Int(i));
+        }
+
+        let response = database.get_range("key2".to_owned(), "key10".to_owned(), &[]);
+        assert_eq!(response, Ok(QueryResponseType::GET_RANGE_OK(vec![ValueType::Int(2), ValueType::Int(9), ValueType::Int(10)])));
+
+        let response = database.next("key9");
+        assert_eq!(response, Ok(QueryResponseType::NEXT_OK(Some(KeyValuePair { key: "key10".to_owned(), value: ValueType::Int(10) }))));
+
+        let response = database.last();
+        assert_eq!(response, Ok(QueryResponseType::LAST_OK(Some(KeyValuePair { key: "key11".to_owned(), value: ValueType::Int(11) }))));
+
+        // keys with the same prefix aren't next to each other in natural order
+        let response = database.keys("^key1.*$", "key1", 10);
+        assert_eq!(response, Ok(QueryResponseType::KEYS_OK(vec!["key1".to_owned(), "key10".to_owned(), "key11".to_owned()])));
+
+        let response = database.rename_prefix("key1", "old1");
+        assert_eq!(response, Ok(QueryResponseType::RENAME_PREFIX_OK(3)));
+
+        let response = database.get_range("key10".to_owned(), "key2".to_owned(), &[]);
+        assert_eq!(response, database_error!(DatabaseErrorType::InvalidRangeOrder));
+    }
+
+    #[test]
+    fn test_case_insensitive_key_order() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Int);
+        database.set_key_order(KeyOrder::CaseInsensitive);
+        database.enable_value_index();
+
+        let _ = database.set("User:1", ValueType::Int(1));
+        let _ = database.set("user:2", ValueType::Int(2));
+        let _ = database.set("b", ValueType::Int(3));
+
+        let response = database.get("USER:1");
+        assert_eq!(response, Ok(QueryResponseType::GET_OK(ValueType::Int(1))));
+
+        let response = database.get_range("a".to_owned(), "C".to_owned(), &[]);
+        assert_eq!(response, Ok(QueryResponseType::GET_RANGE_OK(vec![ValueType::Int(3)])));
+
+        // the latest spelling of a key is kept
+        let _ = database.set("user:1", ValueType::Int(10));
+        let response = database.keys("^USER:.*$", "USER:", 10);
+        assert_eq!(response, Ok(QueryResponseType::KEYS_OK(vec!["user:1".to_owned(), "user:2".to_owned()])));
+        assert_eq!(database.len(), Ok(QueryResponseType::LEN_OK(3)));
+
+        let response = database.find_value(&ValueType::Int(1), None);
+        assert_eq!(response, Ok(QueryResponseType::FIND_OK(vec![])));
+
+        let response = database.rename_prefix("USER:", "customer:");
+        assert_eq!(response, Ok(QueryResponseType::RENAME_PREFIX_OK(2)));
+        let response = database.find_value(&ValueType::Int(10), None);
+        assert_eq!(response, Ok(QueryResponseType::FIND_OK(vec![KeyValuePair { key: "customer:1".to_owned(), value: ValueType::Int(10) }])));
+
+        let response = database.rename("CUSTOMER:1", "Customer:1", true);
+        assert_eq!(response, Ok(QueryResponseType::RENAME_OK));
+        let response = database.ceil("customer:");
+        assert_eq!(response, Ok(QueryResponseType::CEIL_OK(Some(KeyValuePair { key: "Customer:1".to_owned(), value: ValueType::Int(10) }))));
+    }
+
+    #[test]
+    fn test_tuple_keys() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Int);
+        let _ = database.set_many(vec![
+            KeyValuePair { key: "(7, 1697000000, \"b\")".to_owned(), value: ValueType::Int(1) },
+            KeyValuePair { key: "(7, 1697000000, \"a\")".to_owned(), value: ValueType::Int(2) },
+            KeyValuePair { key: "(7, 1698000000, \"a\")".to_owned(), value: ValueType::Int(3) },
+            KeyValuePair { key: "(10, 0, \"a\")".to_owned(), value: ValueType::Int(4) },
+            KeyValuePair { key: "(8, 1697000000, \"a\")".to_owned(), value: ValueType::Int(5) },
+        ]);
+
+        let response = database.get("(7,1697000000,\"a\")");
+        assert_eq!(response, Ok(QueryResponseType::GET_OK(ValueType::Int(2))));
+
+        // segments are ordered numerically and partial tuples work as prefixes
+        let response = database.get_range("(7)".to_owned(), "(7)".to_owned(), &[]);
+        assert_eq!(response, Ok(QueryResponseType::GET_RANGE_OK(vec![ValueType::Int(2), ValueType::Int(1), ValueType::Int(3)])));
+
+        let response = database.get_range("(7, 1697000000)".to_owned(), "(8, 1697000000)".to_owned(), &[]);
+        assert_eq!(response, Ok(QueryResponseType::GET_RANGE_OK(vec![ValueType::Int(2), ValueType::Int(1), ValueType::Int(3), ValueType::Int(5)])));
+
+        let response = database.aggregate(AggregationType::Sum, "(7, 1697500000)".to_owned(), "(10)".to_owned());
+        assert_eq!(response, Ok(QueryResponseType::AGG_OK(AggregationType::Sum, AggregationResult::Int(12))));
+
+        let response = database.last();
+        assert_eq!(response, Ok(QueryResponseType::LAST_OK(Some(KeyValuePair { key: "(10, 0, \\\"a\\\")".to_owned(), value: ValueType::Int(4) }))));
+
+        let response = database.keys("^\\(8, .*$", "(8, ", 10);
+        assert_eq!(response, Ok(QueryResponseType::KEYS_OK(vec!["(8, 1697000000, \\\"a\\\")".to_owned()])));
+
+        // the prefix of tuple keys is encoded, partial segments included
+        let response = database.keys("^\\(7, 1697.*$", "(7, 1697", 10);
+        assert_eq!(response, Ok(QueryResponseType::KEYS_OK(vec!["(7, 1697000000, \\\"a\\\")".to_owned(), "(7, 1697000000, \\\"b\\\")".to_owned()])));
+        let response = database.keys("^.*$", "(7, 1697000000, \\\"b", 10);
+        assert_eq!(response, Ok(QueryResponseType::KEYS_OK(vec!["(7, 1697000000, \\\"b\\\")".to_owned()])));
+        let response = database.keys("^.*$", "(", 10);
+        assert_eq!(response, Ok(QueryResponseType::KEYS_OK(vec![
+            "(7, 1697000000, \\\"a\\\")".to_owned(), "(7, 1697000000, \\\"b\\\")".to_owned(), "(7, 1698000000, \\\"a\\\")".to_owned(),
+            "(8, 1697000000, \\\"a\\\")".to_owned(), "(10, 0, \\\"a\\\")".to_owned()
+        ])));
+
+        let response = database.del_range("(7, 1697000000)".to_owned(), "(7, 1697000000)".to_owned());
+        assert_eq!(response, Ok(QueryResponseType::DEL_RANGE_OK(2)));
+    }
+
+    #[test]
+    fn test_keys() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Int);
+
+        let _ = database.set_many(vec![
+            KeyValuePair { key: "session:1:2026-10-01".to_owned(), value: ValueType::Int(1) },
+            KeyValuePair { key: "session:2:2026-10-02".to_owned(), value: ValueType::Int(2) },
+            KeyValuePair { key: "session:3:2026-11-01".to_owned(), value: ValueType::Int(3) },
+            KeyValuePair { key: "user:1".to_owned(), value: ValueType::Int(4) },
+        ]);
+
+        let response = database.keys("^session:.*:2026-10-.*$", "session:", 10);
+        assert_eq!(response, Ok(QueryResponseType::KEYS_OK(vec!["session:1:2026-10-01".to_owned(), "session:2:2026-10-02".to_owned()])));
+
+        // the count bounds the amount of returned keys
+        let response = database.keys("^session:.*$", "session:", 1);
+        assert_eq!(response, Ok(QueryResponseType::KEYS_OK(vec!["session:1:2026-10-01".to_owned()])));
+
+        // keys outside of the prefix are never returned
+        let response = database.keys("1", "user:", 10);
+        assert_eq!(response, Ok(QueryResponseType::KEYS_OK(vec!["user:1".to_owned()])));
+
+        let response = database.keys("^order:.*$", "order:", 10);
+        assert_eq!(response, Ok(QueryResponseType::KEYS_OK(vec![])));
+
+        let response = database.keys("(", "", 10);
+        assert_eq!(response, database_error!(DatabaseErrorType::InvalidPattern("(".to_string())));
+    }
+
+    #[test]
+    fn test_time_series() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Int);
+
+        for (timestamp, value) in [(1000, 4), (30_000, 8), (61_000, 1), (62_000, 3), (185_000, 10)] {
+            let response = database.ts_add("temp", Some(timestamp), ValueType::Int(value), None);
+            assert_eq!(response, Ok(QueryResponseType::TS_ADD_OK(timestamp)));
+        }
+
+        let response = database.ts_add("temp", Some(62_000), ValueType::Int(5), None);
+        assert_eq!(response, database_error!(DatabaseErrorType::SampleOutOfOrder("temp".to_string(), 185_000)));
+
+        let response = database.ts_range("temp", 30_000, 62_000, None);
+        assert_eq!(response, Ok(QueryResponseType::TS_RANGE_OK(vec![
+            Sample { timestamp: 30_000, value: ValueType::Int(8) },
+            Sample { timestamp: 61_000, value: ValueType::Int(1) },
+            Sample { timestamp: 62_000, value: ValueType::Int(3) }
+        ])));
+
+        // buckets without samples (120000) are left out
+        let response = database.ts_range("temp", i64::MIN, i64::MAX, Some(Downsampling { aggregation: AggregationType::Sum, bucket: 60_000 }));
+        assert_eq!(response, Ok(QueryResponseType::TS_RANGE_AGG_OK(AggregationType::Sum, vec![
+            (0, AggregationResult::Int(12)),
+            (60_000, AggregationResult::Int(4)),
+            (180_000, AggregationResult::Int(10))
+        ])));
+
+        // the retention counts from the newest sample
+        let _ = database.ts_add("temp", Some(190_000), ValueType::Int(2), Some(10_000));
+        let response = database.ts_range("temp", i64::MIN, i64::MAX, Some(Downsampling { aggregation: AggregationType::Count, bucket: 1 }));
+        assert_eq!(response, Ok(QueryResponseType::TS_RANGE_AGG_OK(AggregationType::Count, vec![
+            (185_000, AggregationResult::Int(1)),
+            (190_000, AggregationResult::Int(1))
+        ])));
+
+        let response = database.ts_range("missing", i64::MIN, i64::MAX, None);
+        assert_eq!(response, Ok(QueryResponseType::TS_RANGE_OK(vec![])));
+
+        let _ = database.rpush("jobs", vec![ValueType::Int(1)]);
+        let response = database.ts_add("jobs", None, ValueType::Int(1), None);
+        assert_eq!(response, database_error!(DatabaseErrorType::WrongKind("jobs".to_string(), "LIST".to_string(), "TS".to_string())));
+
+        let database: database::Database = database::Database::new(DatabaseType::Str);
+        let response = database.ts_range("temp", i64::MIN, i64::MAX, Some(Downsampling { aggregation: AggregationType::Avg, bucket: 1000 }));
+        assert_eq!(response, database_error!(DatabaseErrorType::UnsupportedAggregation("STR".to_string())));
+    }
+
+    #[test]
+    fn test_hyperloglogs() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Int);
+
+        let response = database.pfadd("monday", None, (0..1000).map(ValueType::Int).collect());
+        assert_eq!(response, Ok(QueryResponseType::PFADD_OK(true)));
+        let response = database.pfadd("monday", None, vec![ValueType::Int(7)]);
+        assert_eq!(response, Ok(QueryResponseType::PFADD_OK(false)));
+        let _ = database.pfadd("tuesday", None, (500..1500).map(ValueType::Int).collect());
+
+        let Ok(QueryResponseType::PFCOUNT_OK(count)) = database.pfcount(vec!["monday", "tuesday", "missing"]) else { panic!() };
+        assert!((count as f64 - 1500.0).abs() < 1500.0 * 0.03, "estimate {} is off", count);
+
+        let response = database.pfmerge("week", vec!["monday", "tuesday"]);
+        assert_eq!(response, Ok(QueryResponseType::PFMERGE_OK));
+        assert_eq!(database.pfcount(vec!["week"]), Ok(QueryResponseType::PFCOUNT_OK(count)));
+
+        assert_eq!(database.pfcount(vec!["missing"]), Ok(QueryResponseType::PFCOUNT_OK(0)));
+
+        // HyperLogLogs with different error rates can't be combined
+        let _ = database.pfadd("coarse", Some(0.1), vec![ValueType::Int(1)]);
+        let response = database.pfcount(vec!["monday", "coarse"]);
+        assert_eq!(response, database_error!(DatabaseErrorType::PrecisionMismatch("monday".to_string(), "coarse".to_string())));
+
+        let response = database.pfadd("monday", None, vec![ValueType::Str("x".to_string())]);
+        assert_eq!(response, database_error!(DatabaseErrorType::WrongValueType));
+    }
+
+    #[test]
+    fn test_bloom_filters() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Int);
+
+        let response = database.bf_reserve("seen", 0.001, 10_000);
+        assert_eq!(response, Ok(QueryResponseType::BF_RESERVE_OK));
+        let response = database.bf_reserve("seen", 0.001, 10_000);
+        assert_eq!(response, database_error!(DatabaseErrorType::KeyAlreadyExists("seen".to_string())));
+
+        assert_eq!(database.bf_add("seen", ValueType::Int(42)), Ok(QueryResponseType::BF_ADD_OK(true)));
+        assert_eq!(database.bf_add("seen", ValueType::Int(42)), Ok(QueryResponseType::BF_ADD_OK(false)));
+        assert_eq!(database.bf_exists("seen", &ValueType::Int(42)), Ok(QueryResponseType::BF_EXISTS_OK(true)));
+        assert_eq!(database.bf_exists("seen", &ValueType::Int(43)), Ok(QueryResponseType::BF_EXISTS_OK(false)));
+        assert_eq!(database.bf_exists("missing", &ValueType::Int(42)), Ok(QueryResponseType::BF_EXISTS_OK(false)));
+
+        // BF.ADD creates a Bloom filter with the defaults
+        assert_eq!(database.bf_add("other", ValueType::Int(1)), Ok(QueryResponseType::BF_ADD_OK(true)));
+
+        database.set_max_bytes_size(1024);
+        let response = database.bf_reserve("huge", 0.01, 1_000_000);
+        assert_eq!(response, database_error!(DatabaseErrorType::ValueTooLarge(1198133, 1024)));
+
+        let response = database.pfadd("seen", None, vec![ValueType::Int(1)]);
+        assert_eq!(response, database_error!(DatabaseErrorType::WrongKind("seen".to_string(), "BLOOM".to_string(), "HLL".to_string())));
+    }
+
+    #[test]
+    fn test_throttle() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Int);
+        // 3 tokens, refilled with 1 token per hour (so nothing is refilled while testing)
+        let limit: ThrottleLimit = ThrottleLimit { max_burst: 3, rate: 1, period: 3_600_000 };
+
+        let response = database.throttle("api:alice", &limit, 2);
+        assert_eq!(response, Ok(QueryResponseType::THROTTLE_OK(ThrottleResult { allowed: true, remaining: 1, retry_after: Some(0) })));
+        let response = database.throttle("api:alice", &limit, 1);
+        assert_eq!(response, Ok(QueryResponseType::THROTTLE_OK(ThrottleResult { allowed: true, remaining: 0, retry_after: Some(0) })));
+
+        let Ok(QueryResponseType::THROTTLE_OK(result)) = database.throttle("api:alice", &limit, 1) else { panic!() };
+        assert!(!result.allowed);
+        assert!(result.retry_after.is_some_and(|retry_after| retry_after > 3_500_000 && retry_after <= 3_600_000));
+
+        // every key has its own bucket
+        let response = database.throttle("api:bob", &limit, 4);
+        assert_eq!(response, Ok(QueryResponseType::THROTTLE_OK(ThrottleResult { allowed: false, remaining: 3, retry_after: None })));
+
+        let response = database.rpush("api:alice", vec![ValueType::Int(1)]);
+        assert_eq!(response, database_error!(DatabaseErrorType::WrongKind("api:alice".to_string(), "THROTTLE".to_string(), "LIST".to_string())));
+
+        let _ = database.del("api:alice");
+        let response = database.throttle("api:alice", &limit, 3);
+        assert_eq!(response, Ok(QueryResponseType::THROTTLE_OK(ThrottleResult { allowed: true, remaining: 0, retry_after: Some(0) })));
+    }
+
+    #[test]
+    fn test_locks() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Int);
+
+        let Ok(QueryResponseType::LOCK_OK(Some(token))) = database.lock("leader", "worker-1", 60_000) else { panic!() };
+        assert_eq!(database.lock("leader", "worker-2", 60_000), Ok(QueryResponseType::LOCK_OK(None)));
+        assert_eq!(database.renew("leader", "worker-2", 60_000), Ok(QueryResponseType::RENEW_OK(false)));
+        assert_eq!(database.renew("leader", "worker-1", 120_000), Ok(QueryResponseType::RENEW_OK(true)));
+
+        let Ok(QueryResponseType::LOCKINFO_OK(Some(info))) = database.lockinfo("leader") else { panic!() };
+        assert_eq!((info.owner.as_str(), info.token), ("worker-1", token));
+        assert!(info.ttl > 60_000 && info.ttl <= 120_000);
+
+        // locks don't share the keys of the database
+        assert_eq!(database.exists("leader"), Ok(QueryResponseType::EXISTS_OK(false)));
+        let _ = database.set("leader", ValueType::Int(1));
+
+        assert_eq!(database.unlock("leader", "worker-2"), Ok(QueryResponseType::UNLOCK_OK(false)));
+        assert_eq!(database.unlock("leader", "worker-1"), Ok(QueryResponseType::UNLOCK_OK(true)));
+        assert_eq!(database.lockinfo("leader"), Ok(QueryResponseType::LOCKINFO_OK(None)));
+        assert_eq!(database.lock("leader", "worker-2", 60_000), Ok(QueryResponseType::LOCK_OK(Some(token + 1))));
+    }
+
+    #[test]
+    fn test_ephemeral_keys() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Int);
+        database.enable_value_index();
+
+        let _ = database.set_ephemeral("service:a", ValueType::Int(8080), "127.0.0.1:5001");
+        let _ = database.set_ephemeral("service:b", ValueType::Int(8081), "127.0.0.1:5001");
+        let _ = database.set_ephemeral("service:c", ValueType::Int(8082), "127.0.0.1:5002");
+        let _ = database.set("config", ValueType::Int(1));
+
+        // setting a key again without EPHEMERAL keeps it
+        let _ = database.set("service:b", ValueType::Int(9000));
+
+        assert_eq!(database.del_ephemeral("127.0.0.1:5001"), 1);
+        assert_eq!(database.exists("service:a"), Ok(QueryResponseType::EXISTS_OK(false)));
+        assert_eq!(database.exists("service:b"), Ok(QueryResponseType::EXISTS_OK(true)));
+        assert_eq!(database.find_value(&ValueType::Int(8080), None), Ok(QueryResponseType::FIND_OK(vec![])));
+
+        // a deleted key isn't deleted again once it's set by someone else
+        let _ = database.del("service:c");
+        let _ = database.set("service:c", ValueType::Int(1));
+        assert_eq!(database.del_ephemeral("127.0.0.1:5002"), 0);
+        assert_eq!(database.len(), Ok(QueryResponseType::LEN_OK(3)));
+
+        // a renamed key stays ephemeral, a copy and a key overwritten by a rename don't
+        let _ = database.set_ephemeral("worker:1", ValueType::Int(1), "127.0.0.1:5003");
+        let _ = database.set_ephemeral("worker:2", ValueType::Int(2), "127.0.0.1:5003");
+        let _ = database.set_ephemeral("lease:1", ValueType::Int(3), "127.0.0.1:5004");
+        let _ = database.rename("worker:1", "worker:one", false);
+        let _ = database.copy("worker:2", "worker:copy", false);
+        let _ = database.rename("config", "lease:1", false);
+        let _ = database.rename_prefix("worker:", "node:");
+        assert_eq!(database.del_ephemeral("127.0.0.1:5004"), 0);
+        assert_eq!(database.del_ephemeral("127.0.0.1:5003"), 2);
+        assert_eq!(database.exists("lease:1"), Ok(QueryResponseType::EXISTS_OK(true)));
+        assert_eq!(database.exists("node:copy"), Ok(QueryResponseType::EXISTS_OK(true)));
+        assert_eq!(database.len(), Ok(QueryResponseType::LEN_OK(4)));
+    }
+
+    #[test]
+    fn test_sequences() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Str);
+
+        assert_eq!(database.seq_get("orders"), Ok(QueryResponseType::SEQ_GET_OK(0)));
+        assert_eq!(database.seq_next("orders", 1), Ok(QueryResponseType::SEQ_NEXT_OK(1)));
+        assert_eq!(database.seq_next("orders", 100), Ok(QueryResponseType::SEQ_NEXT_OK(2)));
+        assert_eq!(database.seq_next("orders", 1), Ok(QueryResponseType::SEQ_NEXT_OK(102)));
+        assert_eq!(database.seq_get("orders"), Ok(QueryResponseType::SEQ_GET_OK(102)));
+
+        // sequences only move forward
+        let response = database.seq_set("orders", 50);
+        assert_eq!(response, database_error!(DatabaseErrorType::SequenceDecrease("orders".to_string(), 102)));
+        assert_eq!(database.seq_set("orders", 5000), Ok(QueryResponseType::SEQ_SET_OK));
+        assert_eq!(database.seq_next("orders", 1), Ok(QueryResponseType::SEQ_NEXT_OK(5001)));
+
+        let _ = database.seq_set("ids", u64::MAX - 1);
+        assert_eq!(database.seq_next("ids", 1), Ok(QueryResponseType::SEQ_NEXT_OK(u64::MAX)));
+        let response = database.seq_next("ids", 1);
+        assert_eq!(response, database_error!(DatabaseErrorType::SequenceExhausted("ids".to_string())));
+
+        // reserving no IDs would hand out the next ID without reserving it
+        let response = database.seq_next("ids", 0);
+        assert_eq!(response, database_error!(DatabaseErrorType::InvalidSequenceCount("ids".to_string())));
+        assert_eq!(database.seq_get("ids"), Ok(QueryResponseType::SEQ_GET_OK(u64::MAX)));
+
+        // sequences don't share the keys of the database
+        assert_eq!(database.exists("orders"), Ok(QueryResponseType::EXISTS_OK(false)));
+        let _ = database.clear();
+        assert_eq!(database.seq_get("orders"), Ok(QueryResponseType::SEQ_GET_OK(5001)));
+    }
+
+    #[test]
+    fn test_geo_sets() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Str);
+        let berlin: GeoPoint = GeoPoint { lon: 13.4050, lat: 52.5200 };
+
+        let response = database.geo_add("vehicles", ValueType::Str("car1".to_string()), GeoPoint { lon: 13.4100, lat: 52.5210 });
+        assert_eq!(response, Ok(QueryResponseType::GEO_ADD_OK(true)));
+        let _ = database.geo_add("vehicles", ValueType::Str("car2".to_string()), GeoPoint { lon: 13.4060, lat: 52.5200 });
+        let _ = database.geo_add("vehicles", ValueType::Str("car3".to_string()), GeoPoint { lon: 9.9937, lat: 53.5511 });
+        let response = database.geo_add("vehicles", ValueType::Str("car3".to_string()), GeoPoint { lon: 11.5820, lat: 48.1351 });
+        assert_eq!(response, Ok(QueryResponseType::GEO_ADD_OK(false)));
+
+        let Ok(QueryResponseType::GEO_RADIUS_OK(members)) = database.geo_radius("vehicles", &berlin, 2.0, GeoUnit::Kilometers, None) else { panic!() };
+        let names: Vec<&ValueType> = members.iter().map(|(member, _)| member).collect();
+        assert_eq!(names, vec![&ValueType::Str("car2".to_string()), &ValueType::Str("car1".to_string())]);
+        assert!((members[0].1 - 0.068).abs() < 0.001);
+
+        let Ok(QueryResponseType::GEO_RADIUS_OK(members)) = database.geo_radius("vehicles", &berlin, 1000.0, GeoUnit::Miles, Some(1)) else { panic!() };
+        assert_eq!(members.len(), 1);
+        assert_eq!(database.geo_radius("missing", &berlin, 1.0, GeoUnit::Meters, None), Ok(QueryResponseType::GEO_RADIUS_OK(vec![])));
+
+        let response = database.geo_box("vehicles", &GeoPoint { lon: 11.0, lat: 48.0 }, &GeoPoint { lon: 12.0, lat: 49.0 }, None);
+        assert_eq!(response, Ok(QueryResponseType::GEO_BOX_OK(vec![(ValueType::Str("car3".to_string()), GeoPoint { lon: 11.5820, lat: 48.1351 })])));
+
+        let Ok(QueryResponseType::GEO_DIST_OK(Some(distance))) = database.geo_dist("vehicles", &ValueType::Str("car1".to_string()), &ValueType::Str("car3".to_string()), GeoUnit::Kilometers) else { panic!() };
+        assert!((distance - 504.0).abs() < 5.0, "distance {} is off", distance);
+        let response = database.geo_dist("vehicles", &ValueType::Str("car1".to_string()), &ValueType::Str("bus".to_string()), GeoUnit::Meters);
+        assert_eq!(response, Ok(QueryResponseType::GEO_DIST_OK(None)));
+
+        let response = database.lpush("vehicles", vec![ValueType::Str("x".to_string())]);
+        assert_eq!(response, database_error!(DatabaseErrorType::WrongKind("vehicles".to_string(), "GEO".to_string(), "LIST".to_string())));
+    }
+
+    #[test]
+    fn test_key_changes() {
+        let mut database: database::Database = database::Database::new(DatabaseType::Int);
+        let mut key_changes = database.subscribe_key_changes();
+
+        let _ = database.set("counter", ValueType::Int(1));
+        let _ = database.rpush("jobs", vec![ValueType::Int(7)]);
+        let _ = database.lpop("jobs");
+
+        assert_eq!(key_changes.try_recv().map(|key| key.into_name()), Ok("counter".to_owned()));
+        assert_eq!(key_changes.try_recv().map(|key| key.into_name()), Ok("jobs".to_owned()));
+
+        // reads (and pops) aren't announced
+        assert!(key_changes.try_recv().is_err());
+
+        // deletions are, so GET WAIT can answer them
+        let _ = database.rpush("jobs", vec![ValueType::Int(8)]);
+        let _ = database.set("config", ValueType::Int(2));
+        let _ = key_changes.try_recv();
+        let _ = key_changes.try_recv();
+        let _ = database.del("counter");
+        let _ = database.del_many(vec!["jobs", "missing"]);
+        let _ = database.clear();
+        let deleted: Vec<String> = std::iter::from_fn(|| key_changes.try_recv().ok()).map(|key| key.into_name()).collect();
+        assert_eq!(deleted, vec!["counter", "jobs", "config"]);
+    }
+
 }
@@ -0,0 +1,178 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use serde::{Serialize, Deserialize};
+
+
+/// The maximum amount of fractional digits a decimal can have.
+pub const MAX_DECIMAL_SCALE: u32 = 18;
+
+
+/// A fixed-precision decimal number, stored as an integer mantissa and the amount of fractional digits (scale).
+/// E.g `12.50` is stored as the mantissa `1250` with the scale `2`. The scale of a value is kept as it was sent,
+/// comparisons and equality are done on the numeric value though (`1.5 == 1.50`).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Decimal {
+    mantissa: i128,
+    scale: u32
+}
+
+impl Decimal {
+
+    pub fn new(mantissa: i128, scale: u32) -> Self {
+        Self { mantissa, scale }
+    }
+
+    /// Parses a decimal, e.g `-12.50`. Exponents aren't supported.
+    ///
+    /// # Arguments:
+    /// * `decimal`: The decimal string.
+    ///
+    /// # Returns:
+    /// The decimal or `None` if the string isn't a valid decimal or has more than `MAX_DECIMAL_SCALE` fractional digits.
+    pub fn parse(decimal: &str) -> Option<Self> {
+        let (negative, digits) = match decimal.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, decimal.strip_prefix('+').unwrap_or(decimal)),
+        };
+        let (integer_part, fractional_part) = digits.split_once('.').unwrap_or((digits, ""));
+
+        let all_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+        if integer_part.is_empty() || !all_digits(integer_part) || !all_digits(fractional_part) || (digits.contains('.') && fractional_part.is_empty()) {
+            return None;
+        }
+        if fractional_part.len() as u32 > MAX_DECIMAL_SCALE {
+            return None;
+        }
+
+        let mantissa: i128 = format!("{}{}", integer_part, fractional_part).parse::<i128>().ok()?;
+        Some(Self {
+            mantissa: if negative { -mantissa } else { mantissa },
+            scale: fractional_part.len() as u32
+        })
+    }
+
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    /// Converts the decimal to a float (may lose precision).
+    pub fn to_f64(self) -> f64 {
+        self.mantissa as f64 / 10f64.powi(self.scale as i32)
+    }
+
+    /// Rescales the decimal to a bigger scale, ie. appends zeros.
+    ///
+    /// # Returns:
+    /// The rescaled mantissa or `None` if it would overflow.
+    fn mantissa_at(&self, scale: u32) -> Option<i128> {
+        self.mantissa.checked_mul(10i128.checked_pow(scale.checked_sub(self.scale)?)?)
+    }
+
+    /// Adds two decimals, the result has the bigger scale of both.
+    ///
+    /// # Returns:
+    /// The sum or `None` if it would overflow.
+    pub fn checked_add(&self, other: &Decimal) -> Option<Decimal> {
+        let scale: u32 = self.scale.max(other.scale);
+        Some(Decimal::new(self.mantissa_at(scale)?.checked_add(other.mantissa_at(scale)?)?, scale))
+    }
+
+    /// Divides the decimal by an integer, rounding half away from zero.
+    ///
+    /// # Arguments:
+    /// * `divisor`: The divisor (must not be zero).
+    /// * `scale`: The scale of the result (at least the scale of the decimal).
+    ///
+    /// # Returns:
+    /// The quotient or `None` if it would overflow.
+    pub fn checked_div(&self, divisor: i128, scale: u32) -> Option<Decimal> {
+        let dividend: i128 = self.mantissa_at(scale)?;
+        let quotient: i128 = dividend / divisor;
+        let remainder: i128 = dividend % divisor;
+
+        let round_away: bool = remainder.checked_abs()?.checked_mul(2)? >= divisor.checked_abs()?;
+        let quotient: i128 = match (round_away, (dividend < 0) != (divisor < 0)) {
+            (true, true) => quotient - 1,
+            (true, false) => quotient + 1,
+            (false, _) => quotient,
+        };
+        Some(Decimal::new(quotient, scale))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let scale: u32 = self.scale.max(other.scale);
+        match (self.mantissa_at(scale), other.mantissa_at(scale)) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            // the rescaling can only overflow for huge mantissas, fall back to comparing floats
+            _ => self.to_f64().total_cmp(&other.to_f64()),
+        }
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sign: &str = if self.mantissa < 0 { "-" } else { "" };
+        let digits: String = format!("{:0>width$}", self.mantissa.unsigned_abs(), width = self.scale as usize + 1);
+        let (integer_part, fractional_part) = digits.split_at(digits.len() - self.scale as usize);
+
+        if fractional_part.is_empty() {
+            write!(f, "{}{}", sign, integer_part)
+        }
+        else {
+            write!(f, "{}{}.{}", sign, integer_part, fractional_part)
+        }
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_display() {
+        for decimal in ["0", "12.50", "-0.05", "12345678901234567890.123456789012345678", "-7"] {
+            assert_eq!(Decimal::parse(decimal).unwrap().to_string(), decimal);
+        }
+        assert_eq!(Decimal::parse("+1.5").unwrap().to_string(), "1.5");
+
+        for decimal in ["", "-", "1.", ".5", "1e5", "1.2.3", "abc", "0.1234567890123456789"] {
+            assert_eq!(Decimal::parse(decimal), None);
+        }
+    }
+
+    #[test]
+    fn test_ordering() {
+        assert_eq!(Decimal::parse("1.5").unwrap(), Decimal::parse("1.50").unwrap());
+        assert!(Decimal::parse("-1.01").unwrap() < Decimal::parse("-1").unwrap());
+        assert!(Decimal::parse("0.1").unwrap() > Decimal::parse("0.09").unwrap());
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let sum = Decimal::parse("0.1").unwrap().checked_add(&Decimal::parse("0.20").unwrap()).unwrap();
+        assert_eq!(sum.to_string(), "0.30");
+
+        assert_eq!(Decimal::parse("10").unwrap().checked_div(3, 2).unwrap().to_string(), "3.33");
+        assert_eq!(Decimal::parse("-0.05").unwrap().checked_div(2, 2).unwrap().to_string(), "-0.03");
+        assert_eq!(Decimal::parse("1.00").unwrap().checked_div(8, 3).unwrap().to_string(), "0.125");
+    }
+}
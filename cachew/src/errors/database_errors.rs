@@ -6,7 +6,28 @@ use std::fmt;
 pub enum DatabaseErrorType {
     KeyNotFound(String),
     InvalidRangeOrder,
-    WrongValueType
+    WrongValueType,
+    InvalidPattern(String),
+    UnsupportedAggregation(String),
+    UnsupportedAggregationType(String, String),
+    KeyAlreadyExists(String),
+    PrefixNotFound(String),
+    UnsupportedCommand(String, String),
+    PathNotFound(String),
+    InvalidJson,
+    SchemaViolation(String, String),
+    IndexAlreadyExists(String),
+    IndexNotFound(String),
+    ValueIndexDisabled,
+    ValueTooLarge(usize, usize),
+    WrongKind(String, String, String),
+    FieldNotFound(String, String),
+    ScoreNotANumber,
+    SampleOutOfOrder(String, i64),
+    PrecisionMismatch(String, String),
+    SequenceExhausted(String),
+    InvalidSequenceCount(String),
+    SequenceDecrease(String, u64)
 }
 
 
@@ -25,6 +46,27 @@ impl fmt::Display for DatabaseError {
             DatabaseErrorType::KeyNotFound(key) => write!(f, "'keyNotFound': The key '{}' doesn't exist.", key),
             DatabaseErrorType::InvalidRangeOrder => write!(f, "'invalidRangeOrder': The lower key is bigger than the upper key."),
             DatabaseErrorType::WrongValueType => write!(f, "'wrongValueType': The value doesn't match the database type."),
+            DatabaseErrorType::InvalidPattern(pattern) => write!(f, "'invalidPattern': The pattern '{}' is not valid.", pattern),
+            DatabaseErrorType::UnsupportedAggregation(db_type) => write!(f, "'unsupportedAggregation': Aggregations are not supported on '{}' databases.", db_type),
+            DatabaseErrorType::UnsupportedAggregationType(aggregation, db_type) => write!(f, "'unsupportedAggregation': The aggregation '{}' is not supported on '{}' databases.", aggregation, db_type),
+            DatabaseErrorType::KeyAlreadyExists(key) => write!(f, "'keyAlreadyExists': The key '{}' already exists.", key),
+            DatabaseErrorType::PrefixNotFound(prefix) => write!(f, "'prefixNotFound': No key starts with '{}'.", prefix),
+            DatabaseErrorType::UnsupportedCommand(command, db_type) => write!(f, "'unsupportedCommand': The command '{}' is not supported on '{}' databases.", command, db_type),
+            DatabaseErrorType::InvalidJson => write!(f, "'invalidJson': The value is not valid JSON."),
+            DatabaseErrorType::PathNotFound(path) => write!(f, "'pathNotFound': The path '{}' doesn't exist.", path),
+            DatabaseErrorType::SchemaViolation(path, reason) => write!(f, "'schemaViolation': The value at '{}' violates the JSON schema, {}.", path, reason),
+            DatabaseErrorType::IndexAlreadyExists(name) => write!(f, "'indexAlreadyExists': The index '{}' already exists.", name),
+            DatabaseErrorType::IndexNotFound(name) => write!(f, "'indexNotFound': The index '{}' doesn't exist.", name),
+            DatabaseErrorType::ValueIndexDisabled => write!(f, "'valueIndexDisabled': The value index is not enabled (start the server with '--value-index')."),
+            DatabaseErrorType::ValueTooLarge(size, max_size) => write!(f, "'valueTooLarge': The value has {} bytes, the limit is {} bytes.", size, max_size),
+            DatabaseErrorType::WrongKind(key, kind, expected_kind) => write!(f, "'wrongKind': The key '{}' holds a {}, the command expects a {}.", key, kind, expected_kind),
+            DatabaseErrorType::FieldNotFound(key, field) => write!(f, "'fieldNotFound': The hash '{}' has no field '{}'.", key, field),
+            DatabaseErrorType::ScoreNotANumber => write!(f, "'scoreNotANumber': The increment would make the score not a number."),
+            DatabaseErrorType::SampleOutOfOrder(key, last_timestamp) => write!(f, "'sampleOutOfOrder': The time series '{}' only accepts samples after its last sample ({}).", key, last_timestamp),
+            DatabaseErrorType::PrecisionMismatch(key, other_key) => write!(f, "'precisionMismatch': The HyperLogLogs '{}' and '{}' have different error rates and can't be combined.", key, other_key),
+            DatabaseErrorType::SequenceExhausted(name) => write!(f, "'sequenceExhausted': The sequence '{}' can't hand out that many IDs without exceeding {}.", name, u64::MAX),
+            DatabaseErrorType::InvalidSequenceCount(name) => write!(f, "'invalidSequenceCount': The sequence '{}' can only reserve 1 or more IDs.", name),
+            DatabaseErrorType::SequenceDecrease(name, last_id) => write!(f, "'sequenceDecrease': The sequence '{}' already handed out IDs up to {}, it can only be set higher.", name, last_id),
         }
     }
 }
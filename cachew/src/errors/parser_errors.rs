@@ -12,7 +12,21 @@ pub enum ParserErrorType {
     WrongAuthentication,
     StringQuotesNotFound,
     UnexpectedParameters(String),
-    UnescapedDoubleQuote
+    UnescapedDoubleQuote,
+    InvalidSyntax(String),
+    InvalidPattern(String),
+    InvalidJson,
+    InvalidJsonPath(String),
+    InvalidIndexValue(String),
+    InvalidPredicate(String),
+    InvalidTupleKey(String),
+    InvalidScore(String),
+    InvalidTimeout(String),
+    InvalidTimestamp(String),
+    InvalidDuration(String),
+    InvalidErrorRate(String),
+    InvalidCoordinates(String, String),
+    InvalidRadius(String)
 }
 
 #[derive(Debug)]
@@ -36,6 +50,20 @@ impl fmt::Display for ParserError {
             ParserErrorType::StringQuotesNotFound => write!(f, "'stringQuotesNotFound': Expected double quotes around strings."),
             ParserErrorType::UnexpectedParameters(parameters) => write!(f, "'unexpectedParameters': The command '{}' doesn't take any parameters.", parameters),
             ParserErrorType::UnescapedDoubleQuote => write!(f, "'unescapedDoubleQuote': Double quotes must be escaped."),
+            ParserErrorType::InvalidSyntax(usage) => write!(f, "'invalidSyntax': Expected '{}'.", usage),
+            ParserErrorType::InvalidPattern(pattern) => write!(f, "'invalidPattern': The pattern '{}' is not valid.", pattern),
+            ParserErrorType::InvalidJson => write!(f, "'invalidJson': The value is not valid JSON."),
+            ParserErrorType::InvalidJsonPath(path) => write!(f, "'invalidJsonPath': The JSON path '{}' is not valid (expected e.g '$.field[0]').", path),
+            ParserErrorType::InvalidIndexValue(value) => write!(f, "'invalidIndexValue': The value '{}' is not a JSON string, number, boolean or null.", value),
+            ParserErrorType::InvalidPredicate(reason) => write!(f, "'invalidPredicate': The WHERE predicate is not valid, {}.", reason),
+            ParserErrorType::InvalidTupleKey(key) => write!(f, "'invalidTupleKey': The tuple key '{}' is not valid (expected e.g '(7, 1697000000, \"x\")').", key),
+            ParserErrorType::InvalidScore(score) => write!(f, "'invalidScore': The score '{}' is not a number (use 'inf' and '-inf' for infinity).", score),
+            ParserErrorType::InvalidTimeout(timeout) => write!(f, "'invalidTimeout': The timeout '{}' is not a valid amount of seconds (0 waits forever).", timeout),
+            ParserErrorType::InvalidTimestamp(timestamp) => write!(f, "'invalidTimestamp': The timestamp '{}' is neither RFC 3339 nor epoch milliseconds.", timestamp),
+            ParserErrorType::InvalidDuration(duration) => write!(f, "'invalidDuration': The duration '{}' is not a valid amount of milliseconds.", duration),
+            ParserErrorType::InvalidErrorRate(error_rate) => write!(f, "'invalidErrorRate': The error rate '{}' is not a number between 0 and 1.", error_rate),
+            ParserErrorType::InvalidCoordinates(lon, lat) => write!(f, "'invalidCoordinates': The coordinates '{} {}' are not a longitude between -180 and 180 and a latitude between -90 and 90.", lon, lat),
+            ParserErrorType::InvalidRadius(radius) => write!(f, "'invalidRadius': The radius '{}' is not a number of at least 0.", radius),
         }
     }
 }
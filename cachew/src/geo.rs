@@ -0,0 +1,286 @@
+use std::collections::{BTreeSet, HashMap};
+use std::ops::Range;
+
+use crate::schemas::GeoPoint;
+
+
+/// The mean radius of the earth in meters.
+const EARTH_RADIUS: f64 = 6_371_008.8;
+/// The bits of a geohash per dimension, ie. a geohash has 52 bits (a cell of the finest grid is about 0.6 m wide).
+const STEP_BITS: u32 = 26;
+
+
+/// Gets the great-circle distance between two points in meters (haversine formula).
+pub fn distance(from: &GeoPoint, to: &GeoPoint) -> f64 {
+    let (from_lat, to_lat) = (from.lat.to_radians(), to.lat.to_radians());
+    let (lat_delta, lon_delta) = ((to.lat - from.lat).to_radians(), (to.lon - from.lon).to_radians());
+
+    let a: f64 = (lat_delta / 2.0).sin().powi(2) + from_lat.cos() * to_lat.cos() * (lon_delta / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS * a.sqrt().min(1.0).asin()
+}
+
+/// Maps a coordinate onto the cells of the finest grid (2^26 cells between `min` and `min + range`).
+fn quantize(coordinate: f64, min: f64, range: f64) -> u64 {
+    let cell: f64 = ((coordinate - min) / range * (1u64 << STEP_BITS) as f64).floor();
+    (cell.max(0.0) as u64).min((1 << STEP_BITS) - 1)
+}
+
+/// Interleaves the bits of the cells of both dimensions, the longitude bit first (like a geohash).
+fn interleave(lon_cell: u64, lat_cell: u64) -> u64 {
+    (0..STEP_BITS).fold(0, |hash, bit| {
+        hash | ((lat_cell >> bit) & 1) << (2 * bit) | ((lon_cell >> bit) & 1) << (2 * bit + 1)
+    })
+}
+
+/// Gets the geohash of a point, nearby points mostly (but not always) have nearby geohashes.
+fn geohash(point: &GeoPoint) -> u64 {
+    interleave(quantize(point.lon, -180.0, 360.0), quantize(point.lat, -90.0, 180.0))
+}
+
+/// Gets the geohash ranges covering a box (not crossing the antimeridian). The box is covered by the cells of the finest grid
+/// whose cells are at least as large as the box, so at most 2x2 cells (a cell is a contiguous range of geohashes).
+fn cell_ranges(min: &GeoPoint, max: &GeoPoint) -> Vec<Range<u64>> {
+    let step: u32 = (0..=STEP_BITS)
+        .rev()
+        .find(|step| 360.0 / (1u64 << step) as f64 >= max.lon - min.lon && 180.0 / (1u64 << step) as f64 >= max.lat - min.lat)
+        .unwrap_or(0);
+    let shift: u32 = STEP_BITS - step;
+
+    let lon_cells = (quantize(min.lon, -180.0, 360.0) >> shift)..=(quantize(max.lon, -180.0, 360.0) >> shift);
+    let lat_cells = (quantize(min.lat, -90.0, 180.0) >> shift)..=(quantize(max.lat, -90.0, 180.0) >> shift);
+
+    let mut ranges: Vec<Range<u64>> = vec![];
+    for lon_cell in lon_cells {
+        for lat_cell in lat_cells.clone() {
+            let start: u64 = interleave(lon_cell, lat_cell) << (2 * shift);
+            ranges.push(start..start + (1 << (2 * shift)));
+        }
+    }
+    ranges
+}
+
+/// Checks if a point is inside a box, the box crosses the antimeridian if its min longitude is greater than its max longitude.
+fn in_box(point: &GeoPoint, min: &GeoPoint, max: &GeoPoint) -> bool {
+    let in_lon: bool = match min.lon <= max.lon {
+        true => point.lon >= min.lon && point.lon <= max.lon,
+        false => point.lon >= min.lon || point.lon <= max.lon
+    };
+    in_lon && point.lat >= min.lat && point.lat <= max.lat
+}
+
+
+/// A set of distinct members and their positions, ordered by the geohash of their position.
+/// Two indexes are kept in sync: member to position (for lookups and updates) and geohash to member (for searches).
+/// A search only scans the few geohash ranges covering the searched area, and checks the exact position of their members.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GeoSet {
+    positions: HashMap<Vec<u8>, GeoPoint>,
+    ordered: BTreeSet<(u64, Vec<u8>)>
+}
+
+impl GeoSet {
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Inserts a member or updates its position.
+    ///
+    /// # Arguments:
+    /// * `member`: The serialized member.
+    /// * `position`: The position (a valid longitude and latitude).
+    ///
+    /// # Returns:
+    /// True if the member is new.
+    pub fn insert(&mut self, member: Vec<u8>, position: GeoPoint) -> bool {
+        let previous_position: Option<GeoPoint> = self.positions.insert(member.clone(), position);
+        if let Some(previous_position) = &previous_position {
+            self.ordered.remove(&(geohash(previous_position), member.clone()));
+        }
+        self.ordered.insert((geohash(&position), member));
+        previous_position.is_none()
+    }
+
+    pub fn position(&self, member: &[u8]) -> Option<&GeoPoint> {
+        self.positions.get(member)
+    }
+
+    /// Gets the members inside a box, ordered by geohash.
+    ///
+    /// # Arguments:
+    /// * `min`: The south-west corner of the box.
+    /// * `max`: The north-east corner of the box, a longitude less than the one of `min` crosses the antimeridian.
+    ///
+    /// # Returns:
+    /// The serialized members and their positions.
+    pub fn within_box(&self, min: &GeoPoint, max: &GeoPoint) -> Vec<(&[u8], GeoPoint)> {
+        let boxes: Vec<(GeoPoint, GeoPoint)> = match min.lon <= max.lon {
+            true => vec![(*min, *max)],
+            false => vec![
+                (*min, GeoPoint { lon: 180.0, lat: max.lat }),
+                (GeoPoint { lon: -180.0, lat: min.lat }, *max)
+            ]
+        };
+
+        self.candidates(&boxes)
+            .filter(|(_, position)| in_box(position, min, max))
+            .collect()
+    }
+
+    /// Gets the members within a distance of a point, ordered by their distance.
+    ///
+    /// # Arguments:
+    /// * `center`: The point.
+    /// * `radius`: The distance in meters.
+    ///
+    /// # Returns:
+    /// The serialized members and their distances in meters.
+    pub fn within_radius(&self, center: &GeoPoint, radius: f64) -> Vec<(&[u8], f64)> {
+        let lat_delta: f64 = (radius / EARTH_RADIUS).to_degrees();
+        let (min_lat, max_lat) = (center.lat - lat_delta, center.lat + lat_delta);
+
+        // the circle is inside the box around it, the box is widest at the latitude closest to a pole
+        let boxes: Vec<(GeoPoint, GeoPoint)> = match min_lat <= -90.0 || max_lat >= 90.0 {
+            true => vec![(GeoPoint { lon: -180.0, lat: min_lat.max(-90.0) }, GeoPoint { lon: 180.0, lat: max_lat.min(90.0) })],
+            false => {
+                let lon_delta: f64 = (radius / (EARTH_RADIUS * min_lat.abs().max(max_lat.abs()).to_radians().cos())).to_degrees();
+                let (min_lon, max_lon) = (center.lon - lon_delta, center.lon + lon_delta);
+                let (min, max) = (GeoPoint { lon: min_lon, lat: min_lat }, GeoPoint { lon: max_lon, lat: max_lat });
+
+                if lon_delta >= 180.0 {
+                    vec![(GeoPoint { lon: -180.0, ..min }, GeoPoint { lon: 180.0, ..max })]
+                }
+                else if min_lon < -180.0 {
+                    vec![(GeoPoint { lon: min_lon + 360.0, ..min }, GeoPoint { lon: 180.0, ..max }), (GeoPoint { lon: -180.0, ..min }, max)]
+                }
+                else if max_lon > 180.0 {
+                    vec![(min, GeoPoint { lon: 180.0, ..max }), (GeoPoint { lon: -180.0, ..min }, GeoPoint { lon: max_lon - 360.0, ..max })]
+                }
+                else {
+                    vec![(min, max)]
+                }
+            }
+        };
+
+        let mut members: Vec<(&[u8], f64)> = self.candidates(&boxes)
+            .map(|(member, position)| (member, distance(center, &position)))
+            .filter(|(_, distance)| *distance <= radius)
+            .collect();
+        members.sort_by(|(_, distance), (_, other_distance)| distance.total_cmp(other_distance));
+        members
+    }
+
+    /// Gets the members in the geohash ranges covering boxes (that don't cross the antimeridian), each member once.
+    fn candidates(&self, boxes: &[(GeoPoint, GeoPoint)]) -> impl Iterator<Item = (&[u8], GeoPoint)> {
+        let mut ranges: Vec<Range<u64>> = boxes.iter().flat_map(|(min, max)| cell_ranges(min, max)).collect();
+        ranges.sort_by_key(|range| range.start);
+
+        // the cells of different boxes may overlap
+        let mut merged_ranges: Vec<Range<u64>> = vec![];
+        for range in ranges {
+            match merged_ranges.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged_ranges.push(range)
+            }
+        }
+
+        merged_ranges.into_iter().flat_map(move |range| {
+            self.ordered
+                .range((range.start, vec![])..(range.end, vec![]))
+                .map(|(_, member)| (member.as_slice(), self.positions[member]))
+        })
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BERLIN: GeoPoint = GeoPoint { lon: 13.4050, lat: 52.5200 };
+    const POTSDAM: GeoPoint = GeoPoint { lon: 13.0645, lat: 52.3906 };
+    const HAMBURG: GeoPoint = GeoPoint { lon: 9.9937, lat: 53.5511 };
+
+    fn members<T>(found: Vec<(&[u8], T)>) -> Vec<u8> {
+        found.into_iter().map(|(member, _)| member[0]).collect()
+    }
+
+    #[test]
+    fn test_distance() {
+        assert!((distance(&BERLIN, &HAMBURG) - 255_000.0).abs() < 1000.0);
+        assert_eq!(distance(&BERLIN, &BERLIN), 0.0);
+    }
+
+    #[test]
+    fn test_geohash() {
+        // nearby points share the leading bits
+        assert_eq!(geohash(&BERLIN) >> 30, geohash(&GeoPoint { lon: 13.4051, lat: 52.5201 }) >> 30);
+        assert_eq!(geohash(&GeoPoint { lon: -180.0, lat: -90.0 }), 0);
+        assert_eq!(geohash(&GeoPoint { lon: 180.0, lat: 90.0 }), (1 << 52) - 1);
+    }
+
+    #[test]
+    fn test_within_radius() {
+        let mut geo_set: GeoSet = GeoSet::new();
+        assert!(geo_set.insert(vec![1], BERLIN));
+        assert!(geo_set.insert(vec![2], POTSDAM));
+        assert!(geo_set.insert(vec![3], HAMBURG));
+        assert!(!geo_set.insert(vec![3], HAMBURG));
+
+        assert_eq!(members(geo_set.within_radius(&BERLIN, 10_000.0)), vec![1]);
+        assert_eq!(members(geo_set.within_radius(&BERLIN, 30_000.0)), vec![1, 2]);
+        assert_eq!(members(geo_set.within_radius(&HAMBURG, 300_000.0)), vec![3, 2, 1]);
+
+        // members are moved
+        geo_set.insert(vec![1], HAMBURG);
+        assert_eq!(members(geo_set.within_radius(&BERLIN, 10_000.0)), Vec::<u8>::new());
+        assert_eq!(geo_set.position(&[1]), Some(&HAMBURG));
+    }
+
+    #[test]
+    fn test_searches_match_a_full_scan() {
+        // pseudo-random points, so the searches are checked against every member for many cell layouts
+        let mut seed: u64 = 42;
+        let mut random = |min: f64, max: f64| {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            min + (seed >> 11) as f64 / (1u64 << 53) as f64 * (max - min)
+        };
+
+        let mut geo_set: GeoSet = GeoSet::new();
+        let points: Vec<GeoPoint> = (0..2000).map(|_| GeoPoint { lon: random(-180.0, 180.0), lat: random(-90.0, 90.0) }).collect();
+        for (member, point) in points.iter().enumerate() {
+            geo_set.insert((member as u32).to_le_bytes().to_vec(), *point);
+        }
+
+        for _ in 0..200 {
+            let center: GeoPoint = GeoPoint { lon: random(-180.0, 180.0), lat: random(-90.0, 90.0) };
+            let radius: f64 = random(0.0, 3_000_000.0);
+            let expected: usize = points.iter().filter(|point| distance(&center, point) <= radius).count();
+            assert_eq!(geo_set.within_radius(&center, radius).len(), expected);
+
+            let max: GeoPoint = GeoPoint { lon: random(-180.0, 180.0), lat: random(center.lat, 90.0) };
+            let expected: usize = points.iter().filter(|point| in_box(point, &center, &max)).count();
+            assert_eq!(geo_set.within_box(&center, &max).len(), expected);
+        }
+    }
+
+    #[test]
+    fn test_antimeridian_and_poles() {
+        let mut geo_set: GeoSet = GeoSet::new();
+        geo_set.insert(vec![1], GeoPoint { lon: 179.99, lat: 0.0 });
+        geo_set.insert(vec![2], GeoPoint { lon: -179.99, lat: 0.0 });
+        geo_set.insert(vec![3], GeoPoint { lon: 0.0, lat: 89.99 });
+        geo_set.insert(vec![4], GeoPoint { lon: 180.0, lat: 89.99 });
+
+        assert_eq!(members(geo_set.within_radius(&GeoPoint { lon: 180.0, lat: 0.0 }, 5000.0)), vec![1, 2]);
+        assert_eq!(members(geo_set.within_radius(&GeoPoint { lon: 90.0, lat: 90.0 }, 5000.0)).len(), 2);
+
+        let found = geo_set.within_box(&GeoPoint { lon: 179.0, lat: -1.0 }, &GeoPoint { lon: -179.0, lat: 1.0 });
+        assert_eq!(members(found), vec![2, 1]);
+    }
+}
@@ -0,0 +1,279 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::ops::Bound::Included;
+use serde_json::Value;
+
+use crate::json::JsonPath;
+use crate::schemas::ValueType;
+use crate::decimal::Decimal;
+
+
+/// An orderable scalar JSON value used as index key.
+/// Values of different types are ordered as: null < booleans < numbers < 64 bit ints < decimals < strings.
+/// 64 bit ints and decimals are kept exact (a float can't tell apart ints above 2^53), they're only
+/// compared with values of their own type, which is fine since a value index only holds values of the database type.
+#[derive(Debug, Clone)]
+pub enum IndexValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    Int(i64),
+    Decimal(Decimal),
+    Str(String)
+}
+
+impl IndexValue {
+
+    /// Converts a JSON value into an index value.
+    /// 
+    /// # Arguments:
+    /// * `value`: The JSON value.
+    /// 
+    /// # Returns:
+    /// The index value or `None` if the value is an array or object (those aren't indexed).
+    pub fn from_json(value: &Value) -> Option<Self> {
+        match value {
+            Value::Null => Some(IndexValue::Null),
+            Value::Bool(value) => Some(IndexValue::Bool(*value)),
+            Value::Number(value) => value.as_f64().map(IndexValue::Number),
+            Value::String(value) => Some(IndexValue::Str(value.clone())),
+            _ => None
+        }
+    }
+
+    /// Converts a scalar database value into an index value.
+    /// 
+    /// # Arguments:
+    /// * `value`: The database value.
+    /// 
+    /// # Returns:
+    /// The index value or `None` if the value is a JSON document or bytes.
+    pub fn from_value(value: &ValueType) -> Option<Self> {
+        match value {
+            ValueType::Str(value) => Some(IndexValue::Str(value.clone())),
+            ValueType::Int(value) => Some(IndexValue::Number(*value as f64)),
+            ValueType::Float(value) => Some(IndexValue::Number(*value as f64)),
+            ValueType::Bool(value) => Some(IndexValue::Bool(*value)),
+            ValueType::Int64(value) => Some(IndexValue::Int(*value)),
+            ValueType::Float64(value) => Some(IndexValue::Number(*value)),
+            ValueType::Decimal(value) => Some(IndexValue::Decimal(*value)),
+            ValueType::Timestamp(value) => Some(IndexValue::Number(value.millis() as f64)),
+            ValueType::Json(_) | ValueType::Bytes(_) => None
+        }
+    }
+
+    fn type_rank(&self) -> u8 {
+        match self {
+            IndexValue::Null => 0,
+            IndexValue::Bool(_) => 1,
+            IndexValue::Number(_) => 2,
+            IndexValue::Int(_) => 3,
+            IndexValue::Decimal(_) => 4,
+            IndexValue::Str(_) => 5,
+        }
+    }
+}
+
+impl Ord for IndexValue {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (IndexValue::Bool(a), IndexValue::Bool(b)) => a.cmp(b),
+            (IndexValue::Number(a), IndexValue::Number(b)) => a.total_cmp(b),
+            (IndexValue::Int(a), IndexValue::Int(b)) => a.cmp(b),
+            (IndexValue::Decimal(a), IndexValue::Decimal(b)) => a.cmp(b),
+            (IndexValue::Str(a), IndexValue::Str(b)) => a.cmp(b),
+            _ => self.type_rank().cmp(&other.type_rank()),
+        }
+    }
+}
+
+impl PartialOrd for IndexValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for IndexValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for IndexValue {}
+
+
+/// An index mapping values to the keys holding them.
+pub struct ValueIndex {
+    entries: BTreeMap<IndexValue, BTreeSet<String>>,
+    indexed_values: HashMap<String, IndexValue>
+}
+
+impl ValueIndex {
+
+    pub fn new() -> Self {
+        Self {
+            entries: BTreeMap::new(),
+            indexed_values: HashMap::new()
+        }
+    }
+
+    /// Indexes the value of a key, replacing its previously indexed value.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key.
+    /// * `value`: The value to index the key by.
+    pub fn insert(&mut self, key: &str, value: IndexValue) {
+        self.remove(key);
+
+        self.entries.entry(value.clone()).or_default().insert(key.to_owned());
+        self.indexed_values.insert(key.to_owned(), value);
+    }
+
+    /// Removes a key from the index.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key.
+    pub fn remove(&mut self, key: &str) {
+        if let Some(value) = self.indexed_values.remove(key) {
+            if let Some(keys) = self.entries.get_mut(&value) {
+                keys.remove(key);
+                if keys.is_empty() {
+                    self.entries.remove(&value);
+                }
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.indexed_values.clear();
+    }
+
+    /// Finds all keys whose indexed value lies in a range.
+    /// 
+    /// # Arguments:
+    /// * `lower`: The lower bound (inclusive).
+    /// * `upper`: The upper bound (inclusive).
+    /// 
+    /// # Returns:
+    /// The keys, ordered by their indexed value and then by key.
+    pub fn find(&self, lower: &IndexValue, upper: &IndexValue) -> Vec<String> {
+        if lower > upper {
+            return vec![];
+        }
+
+        self.entries
+            .range((Included(lower), Included(upper)))
+            .flat_map(|(_, keys)| keys.iter().cloned())
+            .collect()
+    }
+}
+
+
+/// A secondary index mapping the values at a JSON path to the keys of the documents holding them.
+pub struct JsonIndex {
+    pub path: JsonPath,
+    index: ValueIndex
+}
+
+impl JsonIndex {
+
+    pub fn new(path: JsonPath) -> Self {
+        Self {
+            path,
+            index: ValueIndex::new()
+        }
+    }
+
+    /// Indexes a document. Documents without a scalar value at the index path are skipped.
+    /// 
+    /// # Arguments:
+    /// * `key`: The key of the document.
+    /// * `document`: The document.
+    pub fn insert(&mut self, key: &str, document: &Value) {
+        match self.path.get(document).and_then(IndexValue::from_json) {
+            Some(value) => self.index.insert(key, value),
+            None => self.index.remove(key)
+        }
+    }
+
+    pub fn remove(&mut self, key: &str) {
+        self.index.remove(key);
+    }
+
+    pub fn clear(&mut self) {
+        self.index.clear();
+    }
+
+    pub fn find(&self, lower: &IndexValue, upper: &IndexValue) -> Vec<String> {
+        self.index.find(lower, upper)
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use super::*;
+
+    #[test]
+    fn test_index_value_order() {
+        assert!(IndexValue::Null < IndexValue::Bool(false));
+        assert!(IndexValue::Bool(true) < IndexValue::Number(-1.0));
+        assert!(IndexValue::Number(2.0) < IndexValue::Number(10.0));
+        assert!(IndexValue::Number(1e9) < IndexValue::Str("a".to_string()));
+        assert_eq!(IndexValue::from_json(&json!(3)), Some(IndexValue::Number(3.0)));
+        assert_eq!(IndexValue::from_json(&json!([3])), None);
+        assert_eq!(IndexValue::from_value(&ValueType::Int(3)), Some(IndexValue::Number(3.0)));
+        assert_eq!(IndexValue::from_value(&ValueType::Json("3".to_string())), None);
+
+        // 64 bit ints and decimals beyond the precision of a float stay apart
+        assert!(IndexValue::from_value(&ValueType::Int64(9007199254740992)) < IndexValue::from_value(&ValueType::Int64(9007199254740993)));
+        let (decimal, next_decimal) = (Decimal::parse("1000000000000000.01").unwrap(), Decimal::parse("1000000000000000.02").unwrap());
+        assert!(IndexValue::Decimal(decimal) < IndexValue::Decimal(next_decimal));
+        assert_eq!(IndexValue::Decimal(Decimal::parse("1.5").unwrap()), IndexValue::Decimal(Decimal::parse("1.50").unwrap()));
+    }
+
+    #[test]
+    fn test_value_index() {
+        let mut index: ValueIndex = ValueIndex::new();
+
+        index.insert("flag1", IndexValue::Bool(true));
+        index.insert("flag2", IndexValue::Bool(false));
+        index.insert("flag3", IndexValue::Bool(true));
+        assert_eq!(index.find(&IndexValue::Bool(true), &IndexValue::Bool(true)), vec!["flag1", "flag3"]);
+
+        index.insert("flag1", IndexValue::Bool(false));
+        index.remove("flag3");
+        assert_eq!(index.find(&IndexValue::Bool(true), &IndexValue::Bool(true)), Vec::<String>::new());
+        assert_eq!(index.find(&IndexValue::Bool(false), &IndexValue::Bool(true)), vec!["flag1", "flag2"]);
+    }
+
+    #[test]
+    fn test_json_index() {
+        let mut index: JsonIndex = JsonIndex::new(JsonPath::parse("$.status").unwrap());
+
+        index.insert("job1", &json!({"status": "failed"}));
+        index.insert("job2", &json!({"status": "ok"}));
+        index.insert("job3", &json!({"status": "failed"}));
+        index.insert("job4", &json!({"state": "failed"}));
+
+        let failed = IndexValue::Str("failed".to_string());
+        assert_eq!(index.find(&failed, &failed), vec!["job1", "job3"]);
+
+        // re-indexing a document replaces its old value
+        index.insert("job1", &json!({"status": "ok"}));
+        assert_eq!(index.find(&failed, &failed), vec!["job3"]);
+
+        index.remove("job3");
+        assert_eq!(index.find(&failed, &failed), Vec::<String>::new());
+
+        let ok = IndexValue::Str("ok".to_string());
+        assert_eq!(index.find(&IndexValue::Str("a".to_string()), &IndexValue::Str("z".to_string())), vec!["job1", "job2"]);
+        assert_eq!(index.find(&ok, &failed), Vec::<String>::new());
+
+        index.clear();
+        assert_eq!(index.find(&ok, &ok), Vec::<String>::new());
+    }
+}
@@ -0,0 +1,272 @@
+use std::fmt;
+use serde_json::{Map, Value};
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonPathSegment {
+    Key(String),
+    Index(usize)
+}
+
+
+/// A path into a JSON document, e.g `$.user.tags[0]`.
+/// `$` is the document root, `.name` selects an object member and `[n]` an array element.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonPath {
+    raw: String,
+    segments: Vec<JsonPathSegment>
+}
+
+impl JsonPath {
+
+    /// Parses a JSON path.
+    /// 
+    /// # Arguments:
+    /// * `path`: The path, e.g `$.user.tags[0]` or `$["key with spaces"]`.
+    /// 
+    /// # Returns:
+    /// The parsed path or `None` if the path is invalid.
+    pub fn parse(path: &str) -> Option<Self> {
+        let mut segments: Vec<JsonPathSegment> = Vec::new();
+        let mut rest: &str = path.strip_prefix('$')?;
+
+        while !rest.is_empty() {
+            if let Some(member) = rest.strip_prefix('.') {
+                let end: usize = member.find(['.', '[']).unwrap_or(member.len());
+                if end == 0 {
+                    return None;
+                }
+                segments.push(JsonPathSegment::Key(member[..end].to_string()));
+                rest = &member[end..];
+            }
+            else if let Some(subscript) = rest.strip_prefix('[') {
+                let end: usize = subscript.find(']')?;
+                let inner: &str = &subscript[..end];
+
+                let quoted_key = inner.strip_prefix('"').and_then(|key| key.strip_suffix('"'))
+                    .or_else(|| inner.strip_prefix('\'').and_then(|key| key.strip_suffix('\'')));
+                match quoted_key {
+                    Some(key) => segments.push(JsonPathSegment::Key(key.to_string())),
+                    None => segments.push(JsonPathSegment::Index(inner.parse::<usize>().ok()?)),
+                }
+                rest = &subscript[end + 1..];
+            }
+            else {
+                return None;
+            }
+        }
+
+        Some(Self { raw: path.to_string(), segments })
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    fn step<'a>(value: &'a Value, segment: &JsonPathSegment) -> Option<&'a Value> {
+        match segment {
+            JsonPathSegment::Key(key) => value.as_object()?.get(key),
+            JsonPathSegment::Index(index) => value.as_array()?.get(*index),
+        }
+    }
+
+    fn step_mut<'a>(value: &'a mut Value, segment: &JsonPathSegment) -> Option<&'a mut Value> {
+        match segment {
+            JsonPathSegment::Key(key) => value.as_object_mut()?.get_mut(key),
+            JsonPathSegment::Index(index) => value.as_array_mut()?.get_mut(*index),
+        }
+    }
+
+    /// Gets the value the path points to.
+    /// 
+    /// # Arguments:
+    /// * `document`: The JSON document.
+    /// 
+    /// # Returns:
+    /// The value or `None` if the path doesn't exist in the document.
+    pub fn get<'a>(&self, document: &'a Value) -> Option<&'a Value> {
+        self.segments.iter().try_fold(document, Self::step)
+    }
+
+    /// Sets the value the path points to. The parent of the value has to exist already,
+    /// missing object members are inserted and an array index equal to the array length appends.
+    /// 
+    /// # Arguments:
+    /// * `document`: The JSON document.
+    /// * `value`: The new value.
+    /// 
+    /// # Returns:
+    /// `false` if the parent of the path doesn't exist in the document.
+    pub fn set(&self, document: &mut Value, value: Value) -> bool {
+        let (last, parents) = match self.segments.split_last() {
+            Some(split) => split,
+            None => {
+                *document = value;
+                return true;
+            }
+        };
+
+        let parent: &mut Value = match parents.iter().try_fold(document, Self::step_mut) {
+            Some(parent) => parent,
+            None => return false
+        };
+
+        match (parent, last) {
+            (Value::Object(map), JsonPathSegment::Key(key)) => {
+                map.insert(key.clone(), value);
+                true
+            }
+            (Value::Array(array), JsonPathSegment::Index(index)) if *index < array.len() => {
+                array[*index] = value;
+                true
+            }
+            (Value::Array(array), JsonPathSegment::Index(index)) if *index == array.len() => {
+                array.push(value);
+                true
+            }
+            _ => false
+        }
+    }
+
+    /// Deletes the value the path points to. The root can't be deleted.
+    /// 
+    /// # Arguments:
+    /// * `document`: The JSON document.
+    /// 
+    /// # Returns:
+    /// `false` if the path doesn't exist in the document.
+    pub fn delete(&self, document: &mut Value) -> bool {
+        let (last, parents) = match self.segments.split_last() {
+            Some(split) => split,
+            None => return false
+        };
+
+        match (parents.iter().try_fold(document, Self::step_mut), last) {
+            (Some(Value::Object(map)), JsonPathSegment::Key(key)) => map.remove(key).is_some(),
+            (Some(Value::Array(array)), JsonPathSegment::Index(index)) if *index < array.len() => {
+                array.remove(*index);
+                true
+            }
+            _ => false
+        }
+    }
+}
+
+impl fmt::Display for JsonPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.raw)
+    }
+}
+
+
+/// Applies a JSON merge patch (RFC 7396) to a document.
+/// 
+/// # Arguments:
+/// * `target`: The document to patch.
+/// * `patch`: The patch, `null` members remove the according members of the document.
+pub fn merge_patch(target: &mut Value, patch: &Value) {
+    match patch {
+        Value::Object(patch_map) => {
+            if !target.is_object() {
+                *target = Value::Object(Map::new());
+            }
+            let target_map: &mut Map<String, Value> = target.as_object_mut().unwrap();
+
+            for (key, value) in patch_map {
+                if value.is_null() {
+                    target_map.remove(key);
+                }
+                else {
+                    merge_patch(target_map.entry(key.clone()).or_insert(Value::Null), value);
+                }
+            }
+        }
+        _ => *target = patch.clone()
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use super::*;
+
+    #[test]
+    fn test_parse_path() {
+        let path = JsonPath::parse("$.user.tags[1]").unwrap();
+        assert_eq!(path.segments, vec![
+            JsonPathSegment::Key("user".to_string()),
+            JsonPathSegment::Key("tags".to_string()),
+            JsonPathSegment::Index(1)
+        ]);
+
+        let path = JsonPath::parse("$[\"first name\"]").unwrap();
+        assert_eq!(path.segments, vec![JsonPathSegment::Key("first name".to_string())]);
+
+        assert!(JsonPath::parse("$").unwrap().is_root());
+        assert_eq!(JsonPath::parse("user.name"), None);
+        assert_eq!(JsonPath::parse("$..name"), None);
+        assert_eq!(JsonPath::parse("$.tags[x]"), None);
+        assert_eq!(JsonPath::parse("$.tags[0"), None);
+    }
+
+    #[test]
+    fn test_get() {
+        let document: Value = json!({"user": {"name": "anon", "tags": ["a", "b"]}});
+
+        assert_eq!(JsonPath::parse("$.user.name").unwrap().get(&document), Some(&json!("anon")));
+        assert_eq!(JsonPath::parse("$.user.tags[1]").unwrap().get(&document), Some(&json!("b")));
+        assert_eq!(JsonPath::parse("$").unwrap().get(&document), Some(&document));
+        assert_eq!(JsonPath::parse("$.user.age").unwrap().get(&document), None);
+        assert_eq!(JsonPath::parse("$.user.name.first").unwrap().get(&document), None);
+    }
+
+    #[test]
+    fn test_set() {
+        let mut document: Value = json!({"user": {"tags": ["a"]}});
+
+        assert!(JsonPath::parse("$.user.name").unwrap().set(&mut document, json!("anon")));
+        assert!(JsonPath::parse("$.user.tags[0]").unwrap().set(&mut document, json!("x")));
+        assert!(JsonPath::parse("$.user.tags[1]").unwrap().set(&mut document, json!("y")));
+        assert_eq!(document, json!({"user": {"name": "anon", "tags": ["x", "y"]}}));
+
+        assert!(!JsonPath::parse("$.user.tags[5]").unwrap().set(&mut document, json!("z")));
+        assert!(!JsonPath::parse("$.account.id").unwrap().set(&mut document, json!(1)));
+
+        assert!(JsonPath::parse("$").unwrap().set(&mut document, json!(1)));
+        assert_eq!(document, json!(1));
+    }
+
+    #[test]
+    fn test_delete() {
+        let mut document: Value = json!({"user": {"name": "anon", "tags": ["a", "b"]}});
+
+        assert!(JsonPath::parse("$.user.tags[0]").unwrap().delete(&mut document));
+        assert!(JsonPath::parse("$.user.name").unwrap().delete(&mut document));
+        assert_eq!(document, json!({"user": {"tags": ["b"]}}));
+
+        assert!(!JsonPath::parse("$.user.name").unwrap().delete(&mut document));
+        assert!(!JsonPath::parse("$").unwrap().delete(&mut document));
+    }
+
+    #[test]
+    fn test_merge_patch() {
+        // examples from RFC 7396
+        let mut document: Value = json!({"a": "b", "c": {"d": "e", "f": "g"}});
+        merge_patch(&mut document, &json!({"a": "z", "c": {"f": null}}));
+        assert_eq!(document, json!({"a": "z", "c": {"d": "e"}}));
+
+        let mut document: Value = json!({"a": ["b"]});
+        merge_patch(&mut document, &json!({"a": "c"}));
+        assert_eq!(document, json!({"a": "c"}));
+
+        let mut document: Value = json!(["a", "b"]);
+        merge_patch(&mut document, &json!({"a": {"bb": null}}));
+        assert_eq!(document, json!({"a": {}}));
+
+        let mut document: Value = json!({"a": "foo"});
+        merge_patch(&mut document, &json!(null));
+        assert_eq!(document, json!(null));
+    }
+}
@@ -0,0 +1,242 @@
+use regex::Regex;
+use serde_json::{Map, Value};
+
+
+/// A JSON schema documents of a JSON database have to conform to.
+/// Supports a subset of JSON Schema: `type`, `enum`, `const`, `properties`, `required`, `additionalProperties`,
+/// `items`, `minItems`, `maxItems`, `minLength`, `maxLength`, `pattern`, `minimum`, `maximum`,
+/// `exclusiveMinimum` and `exclusiveMaximum`. Unknown keywords are ignored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonSchema {
+    schema: Value
+}
+
+
+/// A failed schema validation, consisting of the path of the failing value and the reason.
+#[derive(Debug, PartialEq)]
+pub struct SchemaViolation {
+    pub path: String,
+    pub reason: String
+}
+
+
+impl JsonSchema {
+
+    /// Creates a schema from its JSON representation.
+    /// 
+    /// # Arguments:
+    /// * `schema`: The schema, either an object or a boolean.
+    /// 
+    /// # Returns:
+    /// Either the schema or an error message if the schema itself is invalid.
+    pub fn new(schema: Value) -> Result<Self, String> {
+        Self::check_schema(&schema, "$")?;
+        Ok(Self { schema })
+    }
+
+    pub fn as_value(&self) -> &Value {
+        &self.schema
+    }
+
+    fn check_schema(schema: &Value, path: &str) -> Result<(), String> {
+        let schema: &Map<String, Value> = match schema {
+            Value::Bool(_) => return Ok(()),
+            Value::Object(schema) => schema,
+            _ => return Err(format!("The schema at '{}' must be an object or a boolean.", path))
+        };
+
+        if let Some(pattern) = schema.get("pattern") {
+            if pattern.as_str().is_none_or(|pattern| Regex::new(pattern).is_err()) {
+                return Err(format!("The pattern of the schema at '{}' is not a valid regex.", path));
+            }
+        }
+        if let Some(Value::Object(properties)) = schema.get("properties") {
+            for (name, property) in properties {
+                Self::check_schema(property, &format!("{}.{}", path, name))?;
+            }
+        }
+        for keyword in ["items", "additionalProperties"] {
+            if let Some(subschema) = schema.get(keyword) {
+                Self::check_schema(subschema, &format!("{}[{}]", path, keyword))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Validates a document against the schema.
+    /// 
+    /// # Arguments:
+    /// * `document`: The JSON document.
+    /// 
+    /// # Returns:
+    /// Either nothing or the first violation found.
+    pub fn validate(&self, document: &Value) -> Result<(), SchemaViolation> {
+        Self::validate_node(&self.schema, document, "$")
+    }
+
+    fn type_matches(type_name: &str, value: &Value) -> bool {
+        match type_name {
+            "object" => value.is_object(),
+            "array" => value.is_array(),
+            "string" => value.is_string(),
+            "number" => value.is_number(),
+            "integer" => value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|number| number.fract() == 0.0),
+            "boolean" => value.is_boolean(),
+            "null" => value.is_null(),
+            _ => false
+        }
+    }
+
+    fn validate_node(schema: &Value, value: &Value, path: &str) -> Result<(), SchemaViolation> {
+        let violation = |reason: String| Err(SchemaViolation { path: path.to_string(), reason });
+
+        let schema: &Map<String, Value> = match schema {
+            Value::Bool(false) => return violation("no value is allowed".to_string()),
+            Value::Object(schema) => schema,
+            _ => return Ok(())
+        };
+
+        match schema.get("type") {
+            Some(Value::String(type_name)) if !Self::type_matches(type_name, value) => {
+                return violation(format!("expected type '{}'", type_name));
+            }
+            Some(Value::Array(type_names)) if !type_names.iter().any(|type_name| type_name.as_str().is_some_and(|type_name| Self::type_matches(type_name, value))) => {
+                return violation(format!("expected one of the types {}", Value::Array(type_names.clone())));
+            }
+            _ => {}
+        }
+
+        if let Some(Value::Array(allowed)) = schema.get("enum") {
+            if !allowed.contains(value) {
+                return violation(format!("expected one of {}", Value::Array(allowed.clone())));
+            }
+        }
+        if let Some(constant) = schema.get("const") {
+            if constant != value {
+                return violation(format!("expected {}", constant));
+            }
+        }
+
+        if let Some(number) = value.as_f64() {
+            let bound = |keyword: &str| schema.get(keyword).and_then(Value::as_f64);
+            if bound("minimum").is_some_and(|minimum| number < minimum) {
+                return violation(format!("expected a number >= {}", schema["minimum"]));
+            }
+            if bound("maximum").is_some_and(|maximum| number > maximum) {
+                return violation(format!("expected a number <= {}", schema["maximum"]));
+            }
+            if bound("exclusiveMinimum").is_some_and(|minimum| number <= minimum) {
+                return violation(format!("expected a number > {}", schema["exclusiveMinimum"]));
+            }
+            if bound("exclusiveMaximum").is_some_and(|maximum| number >= maximum) {
+                return violation(format!("expected a number < {}", schema["exclusiveMaximum"]));
+            }
+        }
+
+        if let Value::String(string) = value {
+            let length: u64 = string.chars().count() as u64;
+            if schema.get("minLength").and_then(Value::as_u64).is_some_and(|minimum| length < minimum) {
+                return violation(format!("expected at least {} characters", schema["minLength"]));
+            }
+            if schema.get("maxLength").and_then(Value::as_u64).is_some_and(|maximum| length > maximum) {
+                return violation(format!("expected at most {} characters", schema["maxLength"]));
+            }
+            if let Some(Value::String(pattern)) = schema.get("pattern") {
+                if !Regex::new(pattern).is_ok_and(|re| re.is_match(string)) {
+                    return violation(format!("expected to match the pattern '{}'", pattern));
+                }
+            }
+        }
+
+        if let Value::Array(items) = value {
+            let length: u64 = items.len() as u64;
+            if schema.get("minItems").and_then(Value::as_u64).is_some_and(|minimum| length < minimum) {
+                return violation(format!("expected at least {} items", schema["minItems"]));
+            }
+            if schema.get("maxItems").and_then(Value::as_u64).is_some_and(|maximum| length > maximum) {
+                return violation(format!("expected at most {} items", schema["maxItems"]));
+            }
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    Self::validate_node(item_schema, item, &format!("{}[{}]", path, index))?;
+                }
+            }
+        }
+
+        if let Value::Object(members) = value {
+            if let Some(Value::Array(required)) = schema.get("required") {
+                for name in required.iter().filter_map(Value::as_str) {
+                    if !members.contains_key(name) {
+                        return violation(format!("missing required property '{}'", name));
+                    }
+                }
+            }
+
+            let properties: Option<&Map<String, Value>> = schema.get("properties").and_then(Value::as_object);
+            for (name, member) in members {
+                let member_path: String = format!("{}.{}", path, name);
+                match (properties.and_then(|properties| properties.get(name)), schema.get("additionalProperties")) {
+                    (Some(property_schema), _) => Self::validate_node(property_schema, member, &member_path)?,
+                    (None, Some(Value::Bool(false))) => {
+                        return Err(SchemaViolation { path: member_path, reason: "additional properties are not allowed".to_string() });
+                    }
+                    (None, Some(additional_schema)) => Self::validate_node(additional_schema, member, &member_path)?,
+                    (None, None) => {}
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use super::*;
+
+    fn user_schema() -> JsonSchema {
+        JsonSchema::new(json!({
+            "type": "object",
+            "required": ["name", "status"],
+            "additionalProperties": false,
+            "properties": {
+                "name": { "type": "string", "minLength": 1, "pattern": "^[a-z]+$" },
+                "status": { "enum": ["ok", "failed"] },
+                "age": { "type": "integer", "minimum": 0, "exclusiveMaximum": 150 },
+                "tags": { "type": "array", "maxItems": 2, "items": { "type": "string" } }
+            }
+        })).unwrap()
+    }
+
+    #[test]
+    fn test_new() {
+        assert!(JsonSchema::new(json!(true)).is_ok());
+        assert_eq!(JsonSchema::new(json!(1)), Err("The schema at '$' must be an object or a boolean.".to_string()));
+        assert_eq!(
+            JsonSchema::new(json!({ "properties": { "name": { "pattern": "(" } } })),
+            Err("The pattern of the schema at '$.name' is not a valid regex.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let schema: JsonSchema = user_schema();
+
+        assert_eq!(schema.validate(&json!({"name": "anon", "status": "ok", "age": 30, "tags": ["a"]})), Ok(()));
+
+        let violation = |path: &str, reason: &str| Err(SchemaViolation { path: path.to_string(), reason: reason.to_string() });
+
+        assert_eq!(schema.validate(&json!([])), violation("$", "expected type 'object'"));
+        assert_eq!(schema.validate(&json!({"name": "anon"})), violation("$", "missing required property 'status'"));
+        assert_eq!(schema.validate(&json!({"name": "Anon", "status": "ok"})), violation("$.name", "expected to match the pattern '^[a-z]+$'"));
+        assert_eq!(schema.validate(&json!({"name": "anon", "status": "unknown"})), violation("$.status", "expected one of [\"ok\",\"failed\"]"));
+        assert_eq!(schema.validate(&json!({"name": "anon", "status": "ok", "age": 1.5})), violation("$.age", "expected type 'integer'"));
+        assert_eq!(schema.validate(&json!({"name": "anon", "status": "ok", "age": 150})), violation("$.age", "expected a number < 150"));
+        assert_eq!(schema.validate(&json!({"name": "anon", "status": "ok", "tags": ["a", 1]})), violation("$.tags[1]", "expected type 'string'"));
+        assert_eq!(schema.validate(&json!({"name": "anon", "status": "ok", "tags": ["a", "b", "c"]})), violation("$.tags", "expected at most 2 items"));
+        assert_eq!(schema.validate(&json!({"name": "anon", "status": "ok", "id": 1})), violation("$.id", "additional properties are not allowed"));
+    }
+}
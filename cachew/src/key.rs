@@ -0,0 +1,247 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::tuple::Tuple;
+
+
+/// The collation the keys of a database are ordered by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeyOrder {
+    /// Compares the UTF-8 bytes, ie. `key10` < `key2`.
+    #[default]
+    Bytewise,
+    /// Compares runs of ASCII digits by their numeric value, ie. `key2` < `key10`.
+    /// Keys that only differ in leading zeros (`key01`, `key1`) are ordered bytewise.
+    Natural,
+    /// Compares the lowercase form of the keys, keys that only differ in case are the same key (`User:1` == `user:1`).
+    CaseInsensitive
+}
+
+impl KeyOrder {
+
+    /// Compares two keys. Encoded tuple keys are always compared bytewise (their encoding preserves the
+    /// order of the tuples) and come before all plain keys.
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        if Tuple::is_encoded(a) || Tuple::is_encoded(b) {
+            return a.cmp(b);
+        }
+
+        match self {
+            KeyOrder::Bytewise => a.cmp(b),
+            KeyOrder::Natural => natural_cmp(a, b).then_with(|| a.cmp(b)),
+            KeyOrder::CaseInsensitive => a.chars().flat_map(char::to_lowercase).cmp(b.chars().flat_map(char::to_lowercase)),
+        }
+    }
+
+    /// Strips a prefix from a key using the collation, ie. case-insensitively for `CaseInsensitive`.
+    ///
+    /// # Arguments:
+    /// * `key`: The key.
+    /// * `prefix`: The prefix.
+    ///
+    /// # Returns:
+    /// The rest of the key after the prefix or `None` if the key doesn't start with the prefix.
+    pub fn strip_prefix<'a>(&self, key: &'a str, prefix: &str) -> Option<&'a str> {
+        if *self != KeyOrder::CaseInsensitive {
+            return key.strip_prefix(prefix);
+        }
+
+        let mut prefix_chars = prefix.chars().flat_map(char::to_lowercase).peekable();
+        for (position, character) in key.char_indices() {
+            if prefix_chars.peek().is_none() {
+                return Some(&key[position..]);
+            }
+            for lowercase_character in character.to_lowercase() {
+                if prefix_chars.next() != Some(lowercase_character) {
+                    return None;
+                }
+            }
+        }
+        prefix_chars.peek().is_none().then_some("")
+    }
+
+    /// Checks if all keys with the same prefix are next to each other (and not smaller than the prefix) in this order,
+    /// so prefix scans can stop at the first key without the prefix. This doesn't hold for natural ordering,
+    /// e.g `key1` < `key2` < `key10`.
+    pub fn has_contiguous_prefixes(&self) -> bool {
+        *self != KeyOrder::Natural
+    }
+}
+
+impl fmt::Display for KeyOrder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KeyOrder::Bytewise => write!(f, "bytewise"),
+            KeyOrder::Natural => write!(f, "natural"),
+            KeyOrder::CaseInsensitive => write!(f, "case-insensitive"),
+        }
+    }
+}
+
+
+/// A key of the storage, ordered by the collation of the database.
+/// Tuple keys like ``(7, 1697000000, "x")`` are stored in their encoded form (see `Tuple::encode`).
+#[derive(Debug, Clone)]
+pub struct Key {
+    name: String,
+    order: KeyOrder
+}
+
+impl Key {
+
+    pub fn new(name: String, order: KeyOrder) -> Self {
+        let name: String = match name.starts_with('(') {
+            true => Tuple::parse(&name).map(|tuple| tuple.encode()).unwrap_or(name),
+            false => name
+        };
+        Self { name, order }
+    }
+
+    /// Creates the upper bound of a key range. A tuple bound includes every tuple it is a prefix of,
+    /// e.g ``(7)`` includes ``(7, 1697000000, "x")``.
+    pub fn new_upper_bound(name: String, order: KeyOrder) -> Self {
+        let key: Key = Key::new(name, order);
+        match Tuple::is_encoded(&key.name) {
+            true => Self { name: Tuple::encoded_upper_bound(&key.name), order },
+            false => key
+        }
+    }
+
+    /// The key as it is stored, ie. encoded for tuple keys.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn into_name(self) -> String {
+        self.name
+    }
+
+    /// Strips a prefix from the key using its collation (see `KeyOrder::strip_prefix`).
+    pub fn strip_prefix(&self, prefix: &str) -> Option<&str> {
+        self.order.strip_prefix(&self.name, prefix)
+    }
+
+    pub fn starts_with(&self, prefix: &str) -> bool {
+        self.strip_prefix(prefix).is_some()
+    }
+}
+
+impl fmt::Display for Key {
+    /// Displays the key as it is sent to the client, tuple keys are decoded and their quotes escaped.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match Tuple::decode(&self.name) {
+            Some(tuple) => write!(f, "{}", tuple.to_string().replace('"', "\\\"")),
+            None => write!(f, "{}", self.name)
+        }
+    }
+}
+
+impl Ord for Key {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.order.compare(&self.name, &other.name)
+    }
+}
+
+impl PartialOrd for Key {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Key {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Key {}
+
+
+/// Compares two strings, runs of ASCII digits are compared by their numeric value.
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let (mut i, mut j) = (0, 0);
+
+    while i < a.len() && j < b.len() {
+        if a[i].is_ascii_digit() && b[j].is_ascii_digit() {
+            let a_run: &[u8] = digit_run(&a[i..]);
+            let b_run: &[u8] = digit_run(&b[j..]);
+            i += a_run.len();
+            j += b_run.len();
+
+            // without leading zeros, the longer number is bigger, numbers of the same length compare like strings
+            let a_number: &[u8] = trim_leading_zeros(a_run);
+            let b_number: &[u8] = trim_leading_zeros(b_run);
+            let ordering: Ordering = a_number.len().cmp(&b_number.len()).then_with(|| a_number.cmp(b_number));
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+        }
+        else {
+            if a[i] != b[j] {
+                return a[i].cmp(&b[j]);
+            }
+            i += 1;
+            j += 1;
+        }
+    }
+
+    (a.len() - i).cmp(&(b.len() - j))
+}
+
+fn digit_run(bytes: &[u8]) -> &[u8] {
+    let length: usize = bytes.iter().take_while(|byte| byte.is_ascii_digit()).count();
+    &bytes[..length]
+}
+
+fn trim_leading_zeros(digits: &[u8]) -> &[u8] {
+    let zeros: usize = digits.iter().take_while(|digit| **digit == b'0').count();
+    &digits[zeros..]
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(keys: &[&str], order: KeyOrder) -> Vec<String> {
+        let mut keys: Vec<Key> = keys.iter().map(|key| Key::new(key.to_string(), order)).collect();
+        keys.sort();
+        keys.into_iter().map(Key::into_name).collect()
+    }
+
+    #[test]
+    fn test_bytewise() {
+        assert_eq!(sorted(&["key2", "key10", "Key1"], KeyOrder::Bytewise), vec!["Key1", "key10", "key2"]);
+    }
+
+    #[test]
+    fn test_natural() {
+        assert_eq!(sorted(&["key10", "key2", "key1", "key", "key01", "a100b2", "a100b10"], KeyOrder::Natural), vec!["a100b2", "a100b10", "key", "key01", "key1", "key2", "key10"]);
+        assert_ne!(Key::new("key01".to_string(), KeyOrder::Natural), Key::new("key1".to_string(), KeyOrder::Natural));
+        assert!(!KeyOrder::Natural.has_contiguous_prefixes());
+    }
+
+    #[test]
+    fn test_case_insensitive() {
+        assert_eq!(sorted(&["b", "A", "a2", "B1"], KeyOrder::CaseInsensitive), vec!["A", "a2", "b", "B1"]);
+        assert_eq!(Key::new("User:1".to_string(), KeyOrder::CaseInsensitive), Key::new("user:1".to_string(), KeyOrder::CaseInsensitive));
+
+        assert_eq!(KeyOrder::CaseInsensitive.strip_prefix("User:1", "user:"), Some("1"));
+        assert_eq!(KeyOrder::CaseInsensitive.strip_prefix("User", "USER"), Some(""));
+        assert_eq!(KeyOrder::CaseInsensitive.strip_prefix("Use", "user"), None);
+        assert_eq!(KeyOrder::Bytewise.strip_prefix("User:1", "user:"), None);
+    }
+
+    #[test]
+    fn test_tuple_keys() {
+        // tuple keys are ordered by their segments (independent of the collation) and before plain keys
+        assert_eq!(
+            sorted(&["key", "(7, 10)", "(7, 9, \"x\")", "(-1)"], KeyOrder::Natural),
+            vec!["\u{1}\u{2}7FFFFFFFFFFFFFFF", "\u{1}\u{2}8000000000000007\u{2}8000000000000009\u{3}x\u{0}", "\u{1}\u{2}8000000000000007\u{2}800000000000000A", "key"]
+        );
+        assert_eq!(Key::new("(7,\"x\")".to_string(), KeyOrder::Bytewise).to_string(), "(7, \\\"x\\\")");
+        assert!(Key::new("(7, 9, \"x\")".to_string(), KeyOrder::Bytewise) < Key::new_upper_bound("(7, 9)".to_string(), KeyOrder::Bytewise));
+    }
+}
@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+
+use crate::schemas::LockInfo;
+
+
+/// A lock held by an owner until it's unlocked or expires.
+#[derive(Debug, Clone, PartialEq)]
+struct Lock {
+    owner: String,
+    token: u64,
+    /// The time the lock expires at in epoch milliseconds.
+    expires_at: i64
+}
+
+/// Named locks with fencing tokens, independent of the keys of the database.
+/// Every acquired lock gets a token greater than every token before it (of any lock), so a resource can reject writes
+/// of an owner whose lock expired in the meantime by comparing the tokens. Expired locks are treated as released.
+#[derive(Debug, Default)]
+pub struct Locks {
+    locks: HashMap<String, Lock>,
+    last_token: u64
+}
+
+impl Locks {
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gets a lock unless it expired.
+    fn held(&self, name: &str, now: i64) -> Option<&Lock> {
+        self.locks.get(name).filter(|lock| lock.expires_at > now)
+    }
+
+    /// Gets a lock unless it expired or is held by someone else.
+    fn held_by(&mut self, name: &str, owner: &str, now: i64) -> Option<&mut Lock> {
+        self.locks.get_mut(name).filter(|lock| lock.expires_at > now && lock.owner == owner)
+    }
+
+    /// Acquires a lock if it isn't held (even by the same owner).
+    ///
+    /// # Arguments:
+    /// * `name`: The name of the lock.
+    /// * `owner`: The owner acquiring it.
+    /// * `ttl`: The milliseconds after which the lock expires.
+    /// * `now`: The current time in epoch milliseconds.
+    ///
+    /// # Returns:
+    /// The fencing token of the lock, or `None` if it's held.
+    pub fn lock(&mut self, name: &str, owner: &str, ttl: i64, now: i64) -> Option<u64> {
+        if self.held(name, now).is_some() {
+            return None;
+        }
+
+        // drops every expired lock, so locks that are never unlocked don't pile up
+        self.locks.retain(|_, lock| lock.expires_at > now);
+
+        self.last_token += 1;
+        let lock: Lock = Lock { owner: owner.to_owned(), token: self.last_token, expires_at: now.saturating_add(ttl) };
+        self.locks.insert(name.to_owned(), lock);
+        Some(self.last_token)
+    }
+
+    /// Releases a lock held by the owner.
+    ///
+    /// # Returns:
+    /// False if the lock isn't held by the owner (e.g because it expired), nothing is released then.
+    pub fn unlock(&mut self, name: &str, owner: &str, now: i64) -> bool {
+        if self.held_by(name, owner, now).is_none() {
+            return false;
+        }
+        self.locks.remove(name);
+        true
+    }
+
+    /// Extends a lock held by the owner to expire `ttl` milliseconds from now, it keeps its fencing token.
+    ///
+    /// # Returns:
+    /// False if the lock isn't held by the owner (e.g because it expired), an expired lock has to be acquired again.
+    pub fn renew(&mut self, name: &str, owner: &str, ttl: i64, now: i64) -> bool {
+        match self.held_by(name, owner, now) {
+            Some(lock) => {
+                lock.expires_at = now.saturating_add(ttl);
+                true
+            }
+            None => false
+        }
+    }
+
+    /// Gets the owner, the fencing token and the remaining milliseconds of a lock, or `None` if it isn't held.
+    pub fn info(&self, name: &str, now: i64) -> Option<LockInfo> {
+        self.held(name, now).map(|lock| LockInfo { owner: lock.owner.clone(), token: lock.token, ttl: lock.expires_at - now })
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock() {
+        let mut locks: Locks = Locks::new();
+        assert_eq!(locks.lock("leader", "worker-1", 1000, 0), Some(1));
+        assert_eq!(locks.lock("leader", "worker-2", 1000, 500), None);
+        assert_eq!(locks.lock("leader", "worker-1", 1000, 500), None);
+        assert_eq!(locks.lock("cleanup", "worker-2", 1000, 500), Some(2));
+
+        // an expired lock can be acquired by anyone, with a greater token
+        assert_eq!(locks.lock("leader", "worker-2", 1000, 1000), Some(3));
+        assert_eq!(locks.info("leader", 1200), Some(LockInfo { owner: "worker-2".to_string(), token: 3, ttl: 800 }));
+        assert_eq!(locks.info("cleanup", 1500), None);
+    }
+
+    #[test]
+    fn test_unlock_and_renew() {
+        let mut locks: Locks = Locks::new();
+        locks.lock("leader", "worker-1", 1000, 0);
+
+        assert!(!locks.unlock("leader", "worker-2", 100));
+        assert!(!locks.renew("leader", "worker-2", 1000, 100));
+
+        assert!(locks.renew("leader", "worker-1", 5000, 900));
+        assert_eq!(locks.info("leader", 1000), Some(LockInfo { owner: "worker-1".to_string(), token: 1, ttl: 4900 }));
+
+        assert!(locks.unlock("leader", "worker-1", 1000));
+        assert_eq!(locks.info("leader", 1000), None);
+        assert!(!locks.unlock("leader", "worker-1", 1000));
+
+        // an expired lock can't be renewed or unlocked
+        locks.lock("leader", "worker-1", 1000, 2000);
+        assert!(!locks.renew("leader", "worker-1", 1000, 3000));
+        assert!(!locks.unlock("leader", "worker-1", 3000));
+        assert_eq!(locks.lock("leader", "worker-2", 1000, 3000), Some(3));
+    }
+}
@@ -2,6 +2,23 @@ mod parser;
 mod server;
 mod schemas;
 mod database;
+mod aggregation;
+mod decimal;
+mod timestamp;
+mod base64;
+mod json;
+mod json_schema;
+mod index;
+mod key;
+mod tuple;
+mod collection;
+mod sorted_set;
+mod time_series;
+mod probabilistic;
+mod token_bucket;
+mod lock;
+mod geo;
+mod predicate;
 mod response;
 mod state;
 mod cli;
@@ -12,6 +29,8 @@ mod errors;
 use state::State;
 use cli::arguments::{CachewDbArgs, get_cachew_db_args};
 use log::{info};
+use schemas::DatabaseType;
+use key::KeyOrder;
 
 
 #[tokio::main]
@@ -25,7 +44,27 @@ async fn main() {
     let cachew_db_args: CachewDbArgs = get_cachew_db_args();
 
     info!("Initializing b-tree storage of type '{}'.", cachew_db_args.database_type);
-    let state: State = State::new(cachew_db_args.database_type, cachew_db_args.password);
+    let mut state: State = State::new(cachew_db_args.database_type, cachew_db_args.password);
+
+    if let Some(json_schema) = cachew_db_args.json_schema {
+        info!("Enforcing JSON schema on all documents.");
+        state.db.set_json_schema(json_schema);
+    }
+
+    if cachew_db_args.database_type == DatabaseType::Bytes {
+        info!("Limiting values to {} bytes.", cachew_db_args.max_bytes_size);
+        state.db.set_max_bytes_size(cachew_db_args.max_bytes_size);
+    }
+
+    if cachew_db_args.key_order != KeyOrder::Bytewise {
+        info!("Ordering keys using the '{}' collation.", cachew_db_args.key_order);
+        state.db.set_key_order(cachew_db_args.key_order);
+    }
+
+    if cachew_db_args.value_index {
+        info!("Enabling value index for reverse lookups.");
+        state.db.enable_value_index();
+    }
 
     server::serve(state, &cachew_db_args.host, &cachew_db_args.port).await;
 }
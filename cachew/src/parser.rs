@@ -1,10 +1,21 @@
 use regex::Regex;
+use std::time::Duration;
 
-use crate::schemas::{QueryRequest, KeyValuePair, ValueType, DatabaseType};
+use crate::schemas::{QueryRequest, KeyValuePair, ScoredMember, Downsampling, ThrottleLimit, GeoPoint, GeoUnit, ValueType, DatabaseType, AggregationType};
 use crate::{parser_error};
 use crate::errors::parser_errors::{ParserErrorType};
+use crate::json::JsonPath;
+use crate::index::IndexValue;
+use crate::decimal::Decimal;
+use crate::timestamp::Timestamp;
+use crate::base64;
+use crate::tuple::{Tuple, TupleSegment, TUPLE_MARKER};
+use crate::predicate::{Predicate, ComparisonOperator};
 
 
+/// The maximum amount of keys a KEYS query returns if no COUNT is given.
+const KEYS_DEFAULT_COUNT: usize = 1000;
+
 
 /// Parses a string expected to be consist of two ordered keys seperated by space.
 /// 
@@ -62,6 +73,22 @@ fn parse_many_keys(query_keys: &str) -> Result<Vec<&str>, String> {
 fn validate_key(key: &str) -> Result<&str, String> {
     let forbidden_cars: [char; 2] = [',', '/'];
 
+    // keys in parentheses are tuple keys, e.g (7, 1697000000, "x"), their strings can't contain the characters unquoted keys can't
+    if key.starts_with('(') {
+        let tuple: Tuple = match Tuple::parse(key) {
+            Some(tuple) => tuple,
+            None => return parser_error!(ParserErrorType::InvalidTupleKey(key.to_string()))
+        };
+        let has_invalid_string: bool = tuple.segments().iter().any(|segment| match segment {
+            TupleSegment::Str(string) => string.starts_with(TUPLE_MARKER) || string.contains(forbidden_cars),
+            TupleSegment::Int(_) => false
+        });
+        if has_invalid_string {
+            return parser_error!(ParserErrorType::UnexpectedCharacter);
+        }
+        return Ok(key);
+    }
+
     // check if the key consists of multiple entities, ie. there is a spaces in the key but the key is not within quotes
     if split_at_delimiter(key, ' ').len() > 1 {
         println!("{:?}", split_at_delimiter(key, ' '));
@@ -78,11 +105,16 @@ fn validate_key(key: &str) -> Result<&str, String> {
         return parser_error!(ParserErrorType::UnexpectedCharacter);
     }
 
-    // check if the key is empty
-    if key_norm.is_empty() {
+    // check if the key is empty or starts like an encoded tuple key
+    if key_norm.is_empty() || key_norm.starts_with(TUPLE_MARKER) {
         return parser_error!(ParserErrorType::UnexpectedCharacter);
     }
 
+    // keys starting with a parenthesis are reserved for tuple keys, even in quotes
+    if key_norm.starts_with('(') {
+        return parser_error!(ParserErrorType::InvalidTupleKey(key_norm.to_string()));
+    }
+
     // check for unescaped double quotes
     let escaped_quote_pattern = r#"(^|[^\\])""#;
     let re = Regex::new(escaped_quote_pattern).unwrap();
@@ -94,7 +126,34 @@ fn validate_key(key: &str) -> Result<&str, String> {
 }
 
 
-/// Splits a string at a delimiter, unless the delimiter is in a substring enclosed by quotes.
+/// Undoes the escaping of double quotes in a quoted value, ie. removes the backslash in front of each quote (responses
+/// escape them the same way). Other backslashes are part of the value, e.g the JSON document `{"msg": "say \"hi\""}`
+/// is sent as `{\"msg\": \"say \\"hi\\"\"}`.
+///
+/// # Arguments:
+/// * `value`: The value without its enclosing quotes.
+///
+/// # Returns:
+/// The unescaped value or an error if it contains an unescaped double quote.
+fn unescape_quotes(value: &str) -> Result<String, String> {
+    let mut unescaped: String = String::with_capacity(value.len());
+    let mut characters = value.chars().peekable();
+
+    while let Some(character) = characters.next() {
+        match character {
+            '\\' if characters.peek() == Some(&'"') => {
+                unescaped.push('"');
+                characters.next();
+            }
+            '"' => return parser_error!(ParserErrorType::UnescapedDoubleQuote),
+            character => unescaped.push(character)
+        }
+    }
+    Ok(unescaped)
+}
+
+/// Splits a string at a delimiter, unless the delimiter is in a substring enclosed by quotes
+/// or in a part enclosed by parentheses (ie. a tuple key).
 /// 
 /// # Arguments:
 /// * `string`: The string to split.
@@ -106,6 +165,7 @@ fn split_at_delimiter(string: &str, delimiter: char) -> Vec<&str> {
     let mut parts: Vec<&str> = Vec::new();
     let mut current_part = 0;
     let mut inside_quotes = false;
+    let mut inside_parentheses = false;
     let mut prev_char: Option<char> = None;
 
     for (index, ch) in string.char_indices() {
@@ -115,7 +175,14 @@ fn split_at_delimiter(string: &str, delimiter: char) -> Vec<&str> {
                     inside_quotes = !inside_quotes;
                 }
             }
-            ch if ch == delimiter && !inside_quotes => {
+            // only a part starting with a parenthesis is a tuple, e.g the key 'f(x' is not
+            '(' if !inside_quotes && string[current_part..index].trim_matches(' ').is_empty() => {
+                inside_parentheses = true;
+            }
+            ')' if !inside_quotes => {
+                inside_parentheses = false;
+            }
+            ch if ch == delimiter && !inside_quotes && !inside_parentheses => {
                 let part = &string[current_part..index];
                 parts.push(part.trim_matches(|c| c == ' '));
                 current_part = index + ch.len_utf8();
@@ -131,28 +198,116 @@ fn split_at_delimiter(string: &str, delimiter: char) -> Vec<&str> {
     parts
 }
 
+/// Parses a single WHERE predicate and type-checks it against the database type.
+/// 
+/// # Arguments:
+/// * `tokens`: The tokens of the predicate, e.g `["value", ">", "10"]` or `["$.field", "=", "3"]`.
+/// * `database_type`: The type of the database.
+/// 
+/// # Returns:
+/// The parsed predicate or an error.
+fn parse_predicate(tokens: &[&str], database_type: &DatabaseType) -> Result<Predicate, String> {
+    let [operand, operator, literal] = tokens else {
+        return parser_error!(ParserErrorType::InvalidPredicate("expected '<operand> <operator> <value>'".to_string()));
+    };
+    let operator: ComparisonOperator = match ComparisonOperator::parse(operator) {
+        Some(operator) => operator,
+        None => return parser_error!(ParserErrorType::InvalidPredicate(format!("unknown operator '{}'", operator)))
+    };
+
+    if *operand == "value" {
+        if *database_type == DatabaseType::Json {
+            return parser_error!(ParserErrorType::InvalidPredicate("use a JSON path like '$.field' on JSON databases".to_string()));
+        }
+        let ordering_operator: bool = !matches!(operator, ComparisonOperator::Eq | ComparisonOperator::Ne | ComparisonOperator::Contains);
+        let time_operator: bool = matches!(operator, ComparisonOperator::After | ComparisonOperator::Before);
+        if (operator == ComparisonOperator::Contains && *database_type != DatabaseType::Str)
+            || (ordering_operator && *database_type == DatabaseType::Bool)
+            || (time_operator && *database_type != DatabaseType::Timestamp) {
+            return parser_error!(ParserErrorType::InvalidPredicate(format!("the operator '{}' is not supported on '{}' databases", operator, database_type)));
+        }
+        return Ok(Predicate::Value { operator, literal: parse_set_value(literal, database_type)? });
+    }
+
+    if operand.starts_with('$') {
+        if *database_type != DatabaseType::Json {
+            return parser_error!(ParserErrorType::InvalidPredicate("JSON paths can only be used on JSON databases".to_string()));
+        }
+        let path: JsonPath = parse_json_path(operand)?;
+        if matches!(operator, ComparisonOperator::After | ComparisonOperator::Before) {
+            return parser_error!(ParserErrorType::InvalidPredicate(format!("the operator '{}' is only supported on 'TIMESTAMP' databases", operator)));
+        }
+        let literal: IndexValue = parse_index_value(literal)?;
+        if operator == ComparisonOperator::Contains && !matches!(literal, IndexValue::Str(_)) {
+            return parser_error!(ParserErrorType::InvalidPredicate("the operator 'CONTAINS' expects a string".to_string()));
+        }
+        return Ok(Predicate::Path { path, operator, literal });
+    }
+
+    parser_error!(ParserErrorType::InvalidPredicate(format!("expected 'value' or a JSON path, found '{}'", operand)))
+}
+
+/// Parses the parameters of a GET RANGE query with an optional WHERE clause.
+/// 
+/// # Arguments:
+/// * `query`: A string containing the parameters of the query, e.g "a z WHERE value > 10 AND value < 20".
+/// * `database_type`: The type of the database, used to type-check the predicates.
+/// 
+/// # Returns:
+/// An instance of `QueryRequest`, variants: GET_RANGE or ERROR (if the parse failed).
+fn parse_get_range<'a>(query: &str, database_type: &DatabaseType) -> Result<QueryRequest<'a>, String> {
+    let tokens: Vec<&str> = split_at_delimiter(query, ' ');
+    if tokens.len() <= 2 || tokens[2] != "WHERE" {
+        let range_keys: Vec<&str> = parse_ranged_keys(query)?;
+        return Ok(QueryRequest::GET_RANGE { key_lower: range_keys[0].to_owned(), key_upper: range_keys[1].to_owned(), predicates: vec![] });
+    }
+
+    // predicates are chained with AND, every one has to match
+    let predicates: Vec<Predicate> = tokens[3..]
+        .split(|token| *token == "AND")
+        .map(|predicate_tokens| parse_predicate(predicate_tokens, database_type))
+        .collect::<Result<Vec<Predicate>, String>>()?;
+
+    Ok(QueryRequest::GET_RANGE {
+        key_lower: validate_key(tokens[0])?.to_owned(),
+        key_upper: validate_key(tokens[1])?.to_owned(),
+        predicates
+    })
+}
+
 /// Parses the parameters of a GET query.
 /// 
 /// # Arguments:
 /// * `query`: A string containing the parameters of the query, e.g if the query was "GET key" or "GET RANGE a b" the the parameters are everything after "GET ".
+/// * `database_type`: The type of the database, used to type-check WHERE predicates.
 /// 
 /// # Returns:
-/// An instance of `QueryRequest`, variants: GET, GET_RANGE, GET_MANY or ERROR (if the parse failed).
-fn parse_get(query: &str) -> Result<QueryRequest, String> {
+/// An instance of `QueryRequest`, variants: GET, GET_WAIT, GET_RANGE, GET_MANY, GET_MANY_NULLS or ERROR (if the parse failed).
+fn parse_get<'a>(query: &'a str, database_type: &DatabaseType) -> Result<QueryRequest<'a>, String> {
     if query.starts_with("RANGE ") {
-        match parse_ranged_keys(query.strip_prefix("RANGE ").unwrap()) {
-            Ok(range_keys) => return Ok(QueryRequest::GET_RANGE { key_lower: range_keys[0].to_owned(), key_upper: range_keys[1].to_owned() }),
-            Err(error) => return Err(error),
-        }
+        return parse_get_range(query.strip_prefix("RANGE ").unwrap(), database_type);
     }
 
     if query.starts_with("MANY ") {
+        // a trailing NULLS returns a null marker for missing keys instead of failing
+        if let Some(keys) = query.strip_prefix("MANY ").unwrap().strip_suffix(" NULLS") {
+            match parse_many_keys(keys) {
+                Ok(keys) => return Ok(QueryRequest::GET_MANY_NULLS(keys)),
+                Err(error) => return Err(error),
+            }
+        }
+
         match parse_many_keys(query.strip_prefix("MANY ").unwrap()) {
             Ok(keys) => return Ok(QueryRequest::GET_MANY(keys)),
             Err(error) => return Err(error),
         }
     }
 
+    // GET key WAIT timeout blocks until the key is written
+    if let [key, "WAIT", timeout] = split_at_delimiter(query, ' ')[..] {
+        return Ok(QueryRequest::GET_WAIT { key: validate_key(key)?.to_owned(), timeout: parse_timeout(timeout)? });
+    }
+
     let key = match validate_key(query) {
         Ok(key) => key,
         Err(error) => return Err(error)
@@ -243,17 +398,52 @@ fn parse_set_value(value_query_parameter: &str, database_type: &DatabaseType) ->
 
             let value = value_query_parameter.strip_prefix('"').unwrap().strip_suffix('"').unwrap();
 
-            let escaped_quote_pattern = r#"([^\\])""#;
-            let re = Regex::new(escaped_quote_pattern).unwrap();
-            if re.is_match(value) {
-                return parser_error!(ParserErrorType::UnescapedDoubleQuote);
-            }
-
-            let parsed_value: String = match value.parse::<String>() {
+            // the document is stored in its canonical form (compact, ordered object keys)
+            let parsed_value: serde_json::Value = match serde_json::from_str(&unescape_quotes(value)?) {
+                Ok(parsed) => parsed,
+                Err(_) => return parser_error!(ParserErrorType::InvalidJson)
+            };
+            Ok(ValueType::Json(parsed_value.to_string()))
+        }
+        DatabaseType::Int64 => {
+            let parsed_value: i64 = match value_query_parameter.parse::<i64>() {
+                Ok(parsed) => parsed,
+                Err(_) => return parser_error!(ParserErrorType::WrongValueType(database_type.to_string()))
+            };
+            Ok(ValueType::Int64(parsed_value))
+        },
+        DatabaseType::Float64 => {
+            let parsed_value: f64 = match value_query_parameter.parse::<f64>() {
                 Ok(parsed) => parsed,
                 Err(_) => return parser_error!(ParserErrorType::WrongValueType(database_type.to_string()))
             };
-            Ok(ValueType::Json(parsed_value))
+            Ok(ValueType::Float64(parsed_value))
+        },
+        DatabaseType::Decimal => {
+            let parsed_value: Decimal = match Decimal::parse(value_query_parameter) {
+                Some(parsed) => parsed,
+                None => return parser_error!(ParserErrorType::WrongValueType(database_type.to_string()))
+            };
+            Ok(ValueType::Decimal(parsed_value))
+        }
+        DatabaseType::Bytes => {
+            // bytes are sent base64 encoded and in quotes, since the base64 alphabet contains slashes
+            let parsed_value: Option<Vec<u8>> = value_query_parameter
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+                .and_then(base64::decode);
+            match parsed_value {
+                Some(parsed) => Ok(ValueType::Bytes(parsed)),
+                None => parser_error!(ParserErrorType::WrongValueType(database_type.to_string()))
+            }
+        }
+        DatabaseType::Timestamp => {
+            // RFC 3339 or epoch milliseconds, stored as epoch milliseconds (UTC)
+            let parsed_value: Timestamp = match Timestamp::parse(value_query_parameter) {
+                Some(parsed) => parsed,
+                None => return parser_error!(ParserErrorType::WrongValueType(database_type.to_string()))
+            };
+            Ok(ValueType::Timestamp(parsed_value))
         }
     }
 
@@ -287,8 +477,12 @@ fn parse_set<'a>(query: &'a str, database_type: &DatabaseType) -> Result<QueryRe
         return Ok(QueryRequest::SET_MANY(parsed_pairs));
     }
 
-    // check if the query consists just of a key and value
-    let parameters: Vec<&str> = split_at_delimiter(query, ' ');
+    // check if the query consists just of a key and value (and the EPHEMERAL flag)
+    let mut parameters: Vec<&str> = split_at_delimiter(query, ' ');
+    let ephemeral: bool = parameters.len() == 3 && parameters[2] == "EPHEMERAL";
+    if ephemeral {
+        parameters.pop();
+    }
     if parameters.len() != 2 {
         return parser_error!(ParserErrorType::InvalidKeyValuePair(parameters.len()));
     }
@@ -301,6 +495,7 @@ fn parse_set<'a>(query: &'a str, database_type: &DatabaseType) -> Result<QueryRe
     // parse value into the right value type
     let parsed_value: Result<ValueType, String> = parse_set_value(parameters[1], database_type);
     match parsed_value {
+        Ok(value) if ephemeral => Ok(QueryRequest::SET_EPHEMERAL(KeyValuePair { key: key.to_owned(), value})),
         Ok(value) => Ok(QueryRequest::SET(KeyValuePair { key: key.to_owned(), value})),
         Err(err) => Err(err),
     }
@@ -326,6 +521,722 @@ fn parse_exists(query: &str) -> Result<QueryRequest, String> {
 }
 
 
+/// Translates a glob pattern into an anchored regex pattern.
+/// Supported wildcards are `*` (any sequence), `?` (any character) and `[...]` / `[!...]` (character classes).
+/// A backslash escapes the following character.
+/// 
+/// # Arguments:
+/// * `glob`: The glob pattern, e.g "session:*:2026-10-*".
+/// 
+/// # Returns:
+/// The equivalent regex pattern.
+fn glob_to_regex(glob: &str) -> String {
+    let mut pattern: String = String::from("^");
+    let mut chars = glob.chars();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '*' => pattern.push_str(".*"),
+            '?' => pattern.push('.'),
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    pattern.push_str(&regex::escape(&escaped.to_string()));
+                }
+            }
+            '[' => {
+                let rest: String = chars.clone().collect();
+                match rest.find(']') {
+                    Some(end) => {
+                        let class: &str = &rest[..end];
+                        pattern.push('[');
+                        match class.strip_prefix('!') {
+                            Some(negated) => pattern.push_str(&format!("^{}", negated)),
+                            None => pattern.push_str(class),
+                        }
+                        pattern.push(']');
+
+                        // skip the class and its closing bracket
+                        chars.nth(class.chars().count());
+                    }
+                    // an unterminated class is matched literally
+                    None => pattern.push_str(&regex::escape("[")),
+                }
+            }
+            _ => pattern.push_str(&regex::escape(&ch.to_string())),
+        }
+    }
+
+    pattern.push('$');
+    pattern
+}
+
+/// Gets the literal prefix of a glob pattern, ie. everything before the first wildcard.
+/// 
+/// # Arguments:
+/// * `glob`: The glob pattern, e.g "session:*".
+/// 
+/// # Returns:
+/// The literal prefix, e.g "session:".
+fn glob_literal_prefix(glob: &str) -> String {
+    let mut prefix: String = String::new();
+    let mut chars = glob.chars();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '*' | '?' | '[' => break,
+            '\\' => match chars.next() {
+                Some(escaped) => prefix.push(escaped),
+                None => break,
+            },
+            _ => prefix.push(ch),
+        }
+    }
+    prefix
+}
+
+/// Gets the literal prefix of a regex pattern. Only patterns anchored with `^` and without alternations have one.
+/// 
+/// # Arguments:
+/// * `regex`: The regex pattern, e.g "^user:[0-9]+$".
+/// 
+/// # Returns:
+/// The literal prefix, e.g "user:".
+fn regex_literal_prefix(regex: &str) -> String {
+    const META_CHARS: &str = "\\.+*?()|[]{}^$";
+
+    let anchored = match regex.strip_prefix('^') {
+        Some(anchored) => anchored,
+        None => return String::new()
+    };
+    if anchored.contains('|') {
+        return String::new();
+    }
+
+    let mut prefix: String = anchored.chars().take_while(|c| !META_CHARS.contains(*c)).collect();
+
+    // a quantifier after the prefix makes its last character optional
+    if matches!(anchored[prefix.len()..].chars().next(), Some('*' | '?' | '{')) {
+        prefix.pop();
+    }
+    prefix
+}
+
+/// Parses the parameters of a KEYS query.
+/// 
+/// # Arguments:
+/// * `query`: A string containing the parameters of the query, e.g if the query was "KEYS MATCH user:*" the parameters are everything after "KEYS ".
+/// 
+/// # Returns:
+/// An instance of `QueryRequest`, variants: KEYS or ERROR (if the parse failed).
+fn parse_keys(query: &str) -> Result<QueryRequest, String> {
+    const USAGE: &str = "KEYS MATCH <pattern> [REGEX] [COUNT <n>]";
+
+    let tokens: Vec<&str> = split_at_delimiter(query, ' ');
+    if tokens.len() < 2 || tokens[0] != "MATCH" {
+        return parser_error!(ParserErrorType::InvalidSyntax(USAGE.to_string()));
+    }
+
+    let mut raw_pattern: &str = tokens[1];
+    if raw_pattern.len() > 1 && raw_pattern.starts_with('"') && raw_pattern.ends_with('"') {
+        raw_pattern = &raw_pattern[1..raw_pattern.len() - 1];
+    }
+    if raw_pattern.is_empty() {
+        return parser_error!(ParserErrorType::InvalidPattern(raw_pattern.to_string()));
+    }
+
+    let mut options: &[&str] = &tokens[2..];
+    let is_regex: bool = options.first() == Some(&"REGEX");
+    if is_regex {
+        options = &options[1..];
+    }
+
+    let count: usize = match options {
+        [] => KEYS_DEFAULT_COUNT,
+        ["COUNT", count] => match count.parse::<usize>() {
+            Ok(count) if count > 0 => count,
+            _ => return parser_error!(ParserErrorType::InvalidSyntax(USAGE.to_string()))
+        },
+        _ => return parser_error!(ParserErrorType::InvalidSyntax(USAGE.to_string()))
+    };
+
+    let (pattern, prefix) = match is_regex {
+        true => (raw_pattern.to_owned(), regex_literal_prefix(raw_pattern)),
+        false => (glob_to_regex(raw_pattern), glob_literal_prefix(raw_pattern)),
+    };
+
+    if Regex::new(&pattern).is_err() {
+        return parser_error!(ParserErrorType::InvalidPattern(raw_pattern.to_string()));
+    }
+
+    Ok(QueryRequest::KEYS { pattern, prefix, count })
+}
+
+
+/// Parses the parameter of a single-key query, ie. NEXT, PREV, FLOOR, CEIL or STRLEN.
+/// 
+/// # Arguments:
+/// * `query`: A string containing the key, e.g if the query was "NEXT key" the parameter is everything after "NEXT ".
+/// * `query_request`: The `QueryRequest` variant to build from the key.
+/// 
+/// # Returns:
+/// An instance of `QueryRequest` or ERROR (if the parse failed).
+fn parse_navigation<'a>(query: &'a str, query_request: fn(String) -> QueryRequest<'a>) -> Result<QueryRequest<'a>, String> {
+    let key = match validate_key(query) {
+        Ok(key) => key,
+        Err(error) => return Err(error)
+    };
+
+    Ok(query_request(key.to_owned()))
+}
+
+
+/// Parses the name of an aggregation (SUM, AVG, MIN, MAX or COUNT).
+fn parse_aggregation_type(aggregation: &str) -> Option<AggregationType> {
+    match aggregation {
+        "SUM" => Some(AggregationType::Sum),
+        "AVG" => Some(AggregationType::Avg),
+        "MIN" => Some(AggregationType::Min),
+        "MAX" => Some(AggregationType::Max),
+        "COUNT" => Some(AggregationType::Count),
+        _ => None
+    }
+}
+
+/// Parses the parameters of an AGG query.
+/// 
+/// # Arguments:
+/// * `query`: A string containing the parameters of the query, e.g if the query was "AGG SUM a b" the parameters are everything after "AGG ".
+/// 
+/// # Returns:
+/// An instance of `QueryRequest`, variants: AGG or ERROR (if the parse failed).
+fn parse_agg(query: &str) -> Result<QueryRequest, String> {
+    let (aggregation, range_keys) = match query.split_once(' ').and_then(|(aggregation, range_keys)| Some((parse_aggregation_type(aggregation)?, range_keys))) {
+        Some(parsed) => parsed,
+        None => return parser_error!(ParserErrorType::InvalidSyntax("AGG SUM|AVG|MIN|MAX|COUNT <lower key> <upper key>".to_string()))
+    };
+
+    match parse_ranged_keys(range_keys) {
+        Ok(range_keys) => Ok(QueryRequest::AGG { aggregation, key_lower: range_keys[0].to_owned(), key_upper: range_keys[1].to_owned() }),
+        Err(error) => Err(error),
+    }
+}
+
+
+/// Parses the parameters of a RENAME query.
+/// 
+/// # Arguments:
+/// * `query`: A string containing the parameters of the query, e.g if the query was "RENAME a b NX" the parameters are everything after "RENAME ".
+/// 
+/// # Returns:
+/// An instance of `QueryRequest`, variants: RENAME, RENAME_PREFIX or ERROR (if the parse failed).
+fn parse_rename(query: &str) -> Result<QueryRequest, String> {
+    const USAGE: &str = "RENAME <key> <new key> [NX]' or 'RENAME PREFIX <prefix> <new prefix>";
+
+    let tokens: Vec<&str> = split_at_delimiter(query, ' ');
+    match tokens[..] {
+        ["PREFIX", prefix, new_prefix] => Ok(QueryRequest::RENAME_PREFIX {
+            prefix: validate_key(prefix)?.to_owned(),
+            new_prefix: validate_key(new_prefix)?.to_owned()
+        }),
+        [key, new_key] | [key, new_key, "NX"] => Ok(QueryRequest::RENAME {
+            key: validate_key(key)?.to_owned(),
+            new_key: validate_key(new_key)?.to_owned(),
+            nx: tokens.len() == 3
+        }),
+        _ => parser_error!(ParserErrorType::InvalidSyntax(USAGE.to_string()))
+    }
+}
+
+/// Parses the parameters of a COPY query.
+/// 
+/// # Arguments:
+/// * `query`: A string containing the parameters of the query, e.g if the query was "COPY a b REPLACE" the parameters are everything after "COPY ".
+/// 
+/// # Returns:
+/// An instance of `QueryRequest`, variants: COPY or ERROR (if the parse failed).
+fn parse_copy(query: &str) -> Result<QueryRequest, String> {
+    let tokens: Vec<&str> = split_at_delimiter(query, ' ');
+    match tokens[..] {
+        [key, new_key] | [key, new_key, "REPLACE"] => Ok(QueryRequest::COPY {
+            key: validate_key(key)?.to_owned(),
+            new_key: validate_key(new_key)?.to_owned(),
+            replace: tokens.len() == 3
+        }),
+        _ => parser_error!(ParserErrorType::InvalidSyntax("COPY <key> <new key> [REPLACE]".to_string()))
+    }
+}
+
+
+/// Parses a JSON path parameter.
+fn parse_json_path(path: &str) -> Result<JsonPath, String> {
+    match JsonPath::parse(path) {
+        Some(path) => Ok(path),
+        None => parser_error!(ParserErrorType::InvalidJsonPath(path.to_string()))
+    }
+}
+
+/// Parses the parameters of a JSON.GET, JSON.SET, JSON.DEL or JSON.MERGE query.
+/// 
+/// # Arguments:
+/// * `command`: The JSON command, e.g "GET" if the query was "JSON.GET key $.field".
+/// * `query`: A string containing the parameters of the query, ie. everything after the command.
+/// 
+/// # Returns:
+/// An instance of `QueryRequest`, variants: JSON_GET, JSON_SET, JSON_DEL, JSON_MERGE or ERROR (if the parse failed).
+fn parse_json<'a>(command: &str, query: &'a str) -> Result<QueryRequest<'a>, String> {
+    let tokens: Vec<&str> = split_at_delimiter(query, ' ');
+
+    match (command, &tokens[..]) {
+        ("GET", [key, path]) => Ok(QueryRequest::JSON_GET { key: validate_key(key)?.to_owned(), path: parse_json_path(path)? }),
+        ("DEL", [key, path]) => Ok(QueryRequest::JSON_DEL { key: validate_key(key)?.to_owned(), path: parse_json_path(path)? }),
+        ("SET", [key, path, value]) => {
+            let key: &str = validate_key(key)?;
+            let path: JsonPath = parse_json_path(path)?;
+            match parse_set_value(value, &DatabaseType::Json)? {
+                ValueType::Json(value) => Ok(QueryRequest::JSON_SET { key: key.to_owned(), path, value }),
+                _ => unreachable!()
+            }
+        }
+        ("MERGE", [key, patch]) => {
+            let key: &str = validate_key(key)?;
+            match parse_set_value(patch, &DatabaseType::Json)? {
+                ValueType::Json(patch) => Ok(QueryRequest::JSON_MERGE { key: key.to_owned(), patch }),
+                _ => unreachable!()
+            }
+        }
+        ("GET", _) => parser_error!(ParserErrorType::InvalidSyntax("JSON.GET <key> <path>".to_string())),
+        ("DEL", _) => parser_error!(ParserErrorType::InvalidSyntax("JSON.DEL <key> <path>".to_string())),
+        ("SET", _) => parser_error!(ParserErrorType::InvalidSyntax("JSON.SET <key> <path> <value>".to_string())),
+        ("MERGE", _) => parser_error!(ParserErrorType::InvalidSyntax("JSON.MERGE <key> <patch>".to_string())),
+        _ => parser_error!(ParserErrorType::UnknownQueryOperation(format!("JSON.{} {}", command, query)))
+    }
+}
+
+/// Parses the parameters of an INDEX CREATE or INDEX DROP query.
+/// 
+/// # Arguments:
+/// * `query`: A string containing the parameters of the query, e.g if the query was "INDEX CREATE by_age ON $.age" the parameters are everything after "INDEX ".
+/// 
+/// # Returns:
+/// An instance of `QueryRequest`, variants: INDEX_CREATE, INDEX_DROP or ERROR (if the parse failed).
+fn parse_index(query: &str) -> Result<QueryRequest, String> {
+    const USAGE: &str = "INDEX CREATE <name> ON <path>' or 'INDEX DROP <name>";
+
+    let tokens: Vec<&str> = split_at_delimiter(query, ' ');
+    match tokens[..] {
+        ["CREATE", name, "ON", path] => Ok(QueryRequest::INDEX_CREATE {
+            name: validate_key(name)?.to_owned(),
+            path: parse_json_path(path)?
+        }),
+        ["DROP", name] => Ok(QueryRequest::INDEX_DROP(validate_key(name)?.to_owned())),
+        _ => parser_error!(ParserErrorType::InvalidSyntax(USAGE.to_string()))
+    }
+}
+
+/// Parses a scalar JSON literal used to query an index, e.g `"failed"`, `42` or `true`.
+fn parse_index_value(value: &str) -> Result<IndexValue, String> {
+    match serde_json::from_str(value).ok().and_then(|value| IndexValue::from_json(&value)) {
+        Some(value) => Ok(value),
+        None => parser_error!(ParserErrorType::InvalidIndexValue(value.to_string()))
+    }
+}
+
+/// Parses the parameters of a FIND query.
+/// 
+/// # Arguments:
+/// * `query`: A string containing the parameters of the query, e.g if the query was "FIND by_age BETWEEN 18 30" the parameters are everything after "FIND ".
+/// * `database_type`: The type of the database, used to parse the values of "FIND VALUE" queries.
+/// 
+/// # Returns:
+/// An instance of `QueryRequest`, variants: FIND, FIND_VALUE or ERROR (if the parse failed).
+fn parse_find<'a>(query: &str, database_type: &DatabaseType) -> Result<QueryRequest<'a>, String> {
+    const USAGE: &str = "FIND <index> = <value>' or 'FIND <index> BETWEEN <lower value> <upper value>' or 'FIND VALUE <value>' or 'FIND VALUE BETWEEN <lower value> <upper value>";
+
+    let tokens: Vec<&str> = split_at_delimiter(query, ' ');
+    match tokens[..] {
+        ["VALUE", "BETWEEN", lower, upper] => Ok(QueryRequest::FIND_VALUE {
+            lower: parse_set_value(lower, database_type)?,
+            upper: Some(parse_set_value(upper, database_type)?)
+        }),
+        ["VALUE", value] => Ok(QueryRequest::FIND_VALUE { lower: parse_set_value(value, database_type)?, upper: None }),
+        [name, "=", value] => {
+            let value: IndexValue = parse_index_value(value)?;
+            Ok(QueryRequest::FIND { name: validate_key(name)?.to_owned(), lower: value.clone(), upper: value })
+        }
+        [name, "BETWEEN", lower, upper] => Ok(QueryRequest::FIND {
+            name: validate_key(name)?.to_owned(),
+            lower: parse_index_value(lower)?,
+            upper: parse_index_value(upper)?
+        }),
+        _ => parser_error!(ParserErrorType::InvalidSyntax(USAGE.to_string()))
+    }
+}
+
+
+/// The commands working on lists, sets, hashes, sorted sets, time series, HyperLogLogs, Bloom filters, token buckets and geospatial sets.
+const COLLECTION_COMMANDS: [&str; 33] = [
+    "LPUSH", "RPUSH", "LPOP", "RPOP", "BLPOP", "LRANGE", "SADD", "SREM", "SMEMBERS", "SISMEMBER", "HSET", "HGET", "HDEL", "HGETALL",
+    "ZADD", "ZINCRBY", "ZRANGE", "ZRANK", "ZREM", "ZCOUNT", "TS.ADD", "TS.RANGE",
+    "PFADD", "PFCOUNT", "PFMERGE", "BF.RESERVE", "BF.ADD", "BF.EXISTS", "THROTTLE",
+    "GEO.ADD", "GEO.RADIUS", "GEO.BOX", "GEO.DIST"
+];
+
+/// The commands working on locks.
+const LOCK_COMMANDS: [&str; 4] = ["LOCK", "UNLOCK", "RENEW", "LOCKINFO"];
+
+/// The commands working on sequences.
+const SEQUENCE_COMMANDS: [&str; 3] = ["SEQ.NEXT", "SEQ.SET", "SEQ.GET"];
+
+const TS_RANGE_USAGE: &str = "<key> <from> <to> [AGG SUM|AVG|MIN|MAX|COUNT BUCKET <milliseconds>]";
+
+/// Parses the score of a sorted set member, any float but NaN (e.g ``12.5``, ``-3``, ``inf``).
+fn parse_score(score: &str) -> Result<f64, String> {
+    match score.parse::<f64>() {
+        Ok(parsed) if !parsed.is_nan() => Ok(parsed),
+        _ => parser_error!(ParserErrorType::InvalidScore(score.to_string()))
+    }
+}
+
+/// Parses the timeout of a blocking query in seconds (e.g ``5``, ``0.5``), 0 waits forever.
+fn parse_timeout(timeout: &str) -> Result<Option<Duration>, String> {
+    match timeout.parse::<f64>().ok().and_then(|seconds| Duration::try_from_secs_f64(seconds).ok()) {
+        Some(timeout) if timeout.is_zero() => Ok(None),
+        Some(timeout) => Ok(Some(timeout)),
+        None => parser_error!(ParserErrorType::InvalidTimeout(timeout.to_string()))
+    }
+}
+
+/// Parses the timestamp of a time series sample in epoch milliseconds, RFC 3339 (e.g ``2023-10-11T07:06:40+02:00``) or epoch milliseconds.
+/// The placeholder for the current time (``*``) and the open range bounds (``-`` and ``+``) are handled by the callers.
+fn parse_sample_timestamp(timestamp: &str) -> Result<i64, String> {
+    match Timestamp::parse(timestamp) {
+        Some(parsed) => Ok(parsed.millis()),
+        None => parser_error!(ParserErrorType::InvalidTimestamp(timestamp.to_string()))
+    }
+}
+
+/// Parses a duration in milliseconds, e.g the retention of a time series or the size of its buckets.
+fn parse_millis(duration: &str, allow_zero: bool) -> Result<i64, String> {
+    match duration.parse::<i64>() {
+        Ok(parsed) if parsed > 0 || (allow_zero && parsed == 0) => Ok(parsed),
+        _ => parser_error!(ParserErrorType::InvalidDuration(duration.to_string()))
+    }
+}
+
+/// Parses the error rate of a HyperLogLog or a Bloom filter, a number between 0 and 1 (both exclusive, e.g ``0.01``).
+fn parse_error_rate(error_rate: &str) -> Result<f64, String> {
+    match error_rate.parse::<f64>() {
+        Ok(parsed) if parsed > 0.0 && parsed < 1.0 => Ok(parsed),
+        _ => parser_error!(ParserErrorType::InvalidErrorRate(error_rate.to_string()))
+    }
+}
+
+/// Parses a position, a longitude between -180 and 180 and a latitude between -90 and 90 (in degrees, e.g ``13.405 52.52``).
+fn parse_geo_point(lon: &str, lat: &str) -> Result<GeoPoint, String> {
+    match (lon.parse::<f64>(), lat.parse::<f64>()) {
+        (Ok(parsed_lon), Ok(parsed_lat)) if (-180.0..=180.0).contains(&parsed_lon) && (-90.0..=90.0).contains(&parsed_lat) => {
+            Ok(GeoPoint { lon: parsed_lon, lat: parsed_lat })
+        }
+        _ => parser_error!(ParserErrorType::InvalidCoordinates(lon.to_string(), lat.to_string()))
+    }
+}
+
+/// Parses the radius of a GEO.RADIUS query, any finite number of at least 0.
+fn parse_radius(radius: &str) -> Result<f64, String> {
+    match radius.parse::<f64>() {
+        Ok(parsed) if parsed.is_finite() && parsed >= 0.0 => Ok(parsed),
+        _ => parser_error!(ParserErrorType::InvalidRadius(radius.to_string()))
+    }
+}
+
+/// Parses a distance unit (``m``, ``km``, ``mi`` or ``ft``).
+fn parse_geo_unit(unit: &str) -> Option<GeoUnit> {
+    match unit {
+        "m" => Some(GeoUnit::Meters),
+        "km" => Some(GeoUnit::Kilometers),
+        "mi" => Some(GeoUnit::Miles),
+        "ft" => Some(GeoUnit::Feet),
+        _ => None
+    }
+}
+
+/// Parses the optional ``COUNT <n>`` of a GEO.RADIUS or GEO.BOX query.
+///
+/// # Returns:
+/// The count (`None` without a count), or `None` if the options are invalid.
+fn parse_geo_count(options: &[&str]) -> Option<Option<usize>> {
+    match options {
+        [] => Some(None),
+        ["COUNT", count] => count.parse::<usize>().ok().filter(|count| *count > 0).map(Some),
+        _ => None
+    }
+}
+
+/// Parses the parameters of a list, set, hash, sorted set, time series, HyperLogLog, Bloom filter, token bucket or geospatial set query.
+/// 
+/// # Arguments:
+/// * `command`: The command, e.g "LPUSH" if the query was "LPUSH queue 1 2".
+/// * `query`: A string containing the parameters of the query, ie. everything after the command.
+/// * `database_type`: The type of the database, the elements of lists, sets and hashes are values of this type.
+/// 
+/// # Returns:
+/// An instance of `QueryRequest`, variants: LPUSH, RPUSH, LPOP, RPOP, BLPOP, LRANGE, SADD, SREM, SMEMBERS, SISMEMBER, HSET, HGET, HDEL, HGETALL,
+/// ZADD, ZINCRBY, ZRANGE, ZRANGE_BYSCORE, ZRANK, ZREM, ZCOUNT, TS_ADD, TS_RANGE, PFADD, PFCOUNT, PFMERGE, BF_RESERVE, BF_ADD, BF_EXISTS,
+/// THROTTLE, GEO_ADD, GEO_RADIUS, GEO_BOX, GEO_DIST or ERROR (if the parse failed).
+fn parse_collection<'a>(command: &str, query: &'a str, database_type: &DatabaseType) -> Result<QueryRequest<'a>, String> {
+    let tokens: Vec<&str> = split_at_delimiter(query, ' ');
+    let parse_values = |values: &[&str]| -> Result<Vec<ValueType>, String> {
+        values.iter().map(|value| parse_set_value(value, database_type)).collect()
+    };
+    let is_positive = |number: &str| number.parse::<u64>().is_ok_and(|number| number > 0);
+
+    match (command, &tokens[..]) {
+        ("LPUSH", [key, values @ ..]) if !values.is_empty() => Ok(QueryRequest::LPUSH { key: validate_key(key)?.to_owned(), values: parse_values(values)? }),
+        ("RPUSH", [key, values @ ..]) if !values.is_empty() => Ok(QueryRequest::RPUSH { key: validate_key(key)?.to_owned(), values: parse_values(values)? }),
+        ("LPOP", [key]) => Ok(QueryRequest::LPOP(validate_key(key)?.to_owned())),
+        ("RPOP", [key]) => Ok(QueryRequest::RPOP(validate_key(key)?.to_owned())),
+        ("BLPOP", [key, timeout]) => Ok(QueryRequest::BLPOP { key: validate_key(key)?.to_owned(), timeout: parse_timeout(timeout)? }),
+        ("LRANGE", [key, start, stop]) if start.parse::<i64>().is_ok() && stop.parse::<i64>().is_ok() => Ok(QueryRequest::LRANGE {
+            key: validate_key(key)?.to_owned(),
+            start: start.parse::<i64>().unwrap(),
+            stop: stop.parse::<i64>().unwrap()
+        }),
+        ("SADD", [key, members @ ..]) if !members.is_empty() => Ok(QueryRequest::SADD { key: validate_key(key)?.to_owned(), members: parse_values(members)? }),
+        ("SREM", [key, members @ ..]) if !members.is_empty() => Ok(QueryRequest::SREM { key: validate_key(key)?.to_owned(), members: parse_values(members)? }),
+        ("SMEMBERS", [key]) => Ok(QueryRequest::SMEMBERS(validate_key(key)?.to_owned())),
+        ("SISMEMBER", [key, member]) => Ok(QueryRequest::SISMEMBER { key: validate_key(key)?.to_owned(), member: parse_set_value(member, database_type)? }),
+        ("HSET", [key, fields @ ..]) if !fields.is_empty() && fields.len() % 2 == 0 => {
+            let key: &str = validate_key(key)?;
+            let fields: Vec<KeyValuePair> = fields
+                .chunks(2)
+                .map(|field| Ok(KeyValuePair { key: validate_key(field[0])?.to_owned(), value: parse_set_value(field[1], database_type)? }))
+                .collect::<Result<Vec<KeyValuePair>, String>>()?;
+            Ok(QueryRequest::HSET { key: key.to_owned(), fields })
+        }
+        ("HGET", [key, field]) => Ok(QueryRequest::HGET { key: validate_key(key)?.to_owned(), field: validate_key(field)?.to_owned() }),
+        ("HDEL", [key, fields @ ..]) if !fields.is_empty() => Ok(QueryRequest::HDEL {
+            key: validate_key(key)?.to_owned(),
+            fields: fields.iter().map(|field| validate_key(field).map(str::to_owned)).collect::<Result<Vec<String>, String>>()?
+        }),
+        ("HGETALL", [key]) => Ok(QueryRequest::HGETALL(validate_key(key)?.to_owned())),
+        ("ZADD", [key, members @ ..]) if !members.is_empty() && members.len() % 2 == 0 => {
+            let key: &str = validate_key(key)?;
+            let members: Vec<ScoredMember> = members
+                .chunks(2)
+                .map(|member| Ok(ScoredMember { member: parse_set_value(member[1], database_type)?, score: parse_score(member[0])? }))
+                .collect::<Result<Vec<ScoredMember>, String>>()?;
+            Ok(QueryRequest::ZADD { key: key.to_owned(), members })
+        }
+        ("ZINCRBY", [key, increment, member]) => Ok(QueryRequest::ZINCRBY {
+            key: validate_key(key)?.to_owned(),
+            increment: parse_score(increment)?,
+            member: parse_set_value(member, database_type)?
+        }),
+        ("ZRANGE", [key, min, max, "BYSCORE"]) => Ok(QueryRequest::ZRANGE_BYSCORE {
+            key: validate_key(key)?.to_owned(),
+            min: parse_score(min)?,
+            max: parse_score(max)?
+        }),
+        ("ZRANGE", [key, start, stop] | [key, start, stop, "REV"]) if start.parse::<i64>().is_ok() && stop.parse::<i64>().is_ok() => Ok(QueryRequest::ZRANGE {
+            key: validate_key(key)?.to_owned(),
+            start: start.parse::<i64>().unwrap(),
+            stop: stop.parse::<i64>().unwrap(),
+            reverse: tokens.len() == 4
+        }),
+        ("ZRANK", [key, member] | [key, member, "REV"]) => Ok(QueryRequest::ZRANK {
+            key: validate_key(key)?.to_owned(),
+            member: parse_set_value(member, database_type)?,
+            reverse: tokens.len() == 3
+        }),
+        ("ZREM", [key, members @ ..]) if !members.is_empty() => Ok(QueryRequest::ZREM { key: validate_key(key)?.to_owned(), members: parse_values(members)? }),
+        ("ZCOUNT", [key, min, max]) => Ok(QueryRequest::ZCOUNT {
+            key: validate_key(key)?.to_owned(),
+            min: parse_score(min)?,
+            max: parse_score(max)?
+        }),
+        ("TS.ADD", [key, timestamp, value] | [key, timestamp, value, "RETENTION", _]) => Ok(QueryRequest::TS_ADD {
+            key: validate_key(key)?.to_owned(),
+            timestamp: match *timestamp {
+                "*" => None,
+                timestamp => Some(parse_sample_timestamp(timestamp)?)
+            },
+            value: parse_set_value(value, database_type)?,
+            retention: match tokens.get(4) {
+                Some(retention) => Some(parse_millis(retention, true)?),
+                None => None
+            }
+        }),
+        ("TS.RANGE", [key, from, to] | [key, from, to, "AGG", _, "BUCKET", _]) => {
+            let downsampling: Option<Downsampling> = match &tokens[3..] {
+                [_, aggregation, _, bucket] => match parse_aggregation_type(aggregation) {
+                    Some(aggregation) => Some(Downsampling { aggregation, bucket: parse_millis(bucket, false)? }),
+                    None => return parser_error!(ParserErrorType::InvalidSyntax(format!("{} {}", command, TS_RANGE_USAGE)))
+                },
+                _ => None
+            };
+
+            Ok(QueryRequest::TS_RANGE {
+                key: validate_key(key)?.to_owned(),
+                from: match *from {
+                    "-" => i64::MIN,
+                    from => parse_sample_timestamp(from)?
+                },
+                to: match *to {
+                    "+" => i64::MAX,
+                    to => parse_sample_timestamp(to)?
+                },
+                downsampling
+            })
+        }
+        ("PFADD", [key, "ERROR", error_rate, values @ ..]) => Ok(QueryRequest::PFADD {
+            key: validate_key(key)?.to_owned(),
+            error_rate: Some(parse_error_rate(error_rate)?),
+            values: parse_values(values)?
+        }),
+        ("PFADD", [key, values @ ..]) => Ok(QueryRequest::PFADD { key: validate_key(key)?.to_owned(), error_rate: None, values: parse_values(values)? }),
+        ("PFCOUNT", _) => Ok(QueryRequest::PFCOUNT(parse_many_keys(query)?)),
+        ("PFMERGE", [destination, _, ..]) => Ok(QueryRequest::PFMERGE {
+            destination: validate_key(destination)?.to_owned(),
+            sources: parse_many_keys(query)?.split_off(1)
+        }),
+        ("BF.RESERVE", [key, error_rate, capacity]) if capacity.parse::<u64>().is_ok_and(|capacity| capacity > 0) => Ok(QueryRequest::BF_RESERVE {
+            key: validate_key(key)?.to_owned(),
+            error_rate: parse_error_rate(error_rate)?,
+            capacity: capacity.parse::<u64>().unwrap()
+        }),
+        ("BF.ADD", [key, value]) => Ok(QueryRequest::BF_ADD { key: validate_key(key)?.to_owned(), value: parse_set_value(value, database_type)? }),
+        ("BF.EXISTS", [key, value]) => Ok(QueryRequest::BF_EXISTS { key: validate_key(key)?.to_owned(), value: parse_set_value(value, database_type)? }),
+        ("THROTTLE", [key, max_burst, rate, period, cost @ ..])
+            if is_positive(max_burst) && is_positive(rate) && cost.len() <= 1 && cost.iter().all(|cost| cost.parse::<u64>().is_ok()) => Ok(QueryRequest::THROTTLE {
+                key: validate_key(key)?.to_owned(),
+                limit: ThrottleLimit { max_burst: max_burst.parse::<u64>().unwrap(), rate: rate.parse::<u64>().unwrap(), period: parse_millis(period, false)? },
+                cost: cost.first().map_or(1, |cost| cost.parse::<u64>().unwrap())
+            }),
+        ("GEO.ADD", [key, member, lon, lat]) => Ok(QueryRequest::GEO_ADD {
+            key: validate_key(key)?.to_owned(),
+            member: parse_set_value(member, database_type)?,
+            position: parse_geo_point(lon, lat)?
+        }),
+        ("GEO.RADIUS", [key, lon, lat, radius, unit, options @ ..])
+            if parse_geo_unit(unit).is_some() && parse_geo_count(options).is_some() => Ok(QueryRequest::GEO_RADIUS {
+                key: validate_key(key)?.to_owned(),
+                center: parse_geo_point(lon, lat)?,
+                radius: parse_radius(radius)?,
+                unit: parse_geo_unit(unit).unwrap(),
+                count: parse_geo_count(options).unwrap()
+            }),
+        ("GEO.BOX", [key, min_lon, min_lat, max_lon, max_lat, options @ ..]) if parse_geo_count(options).is_some() => Ok(QueryRequest::GEO_BOX {
+            key: validate_key(key)?.to_owned(),
+            min: parse_geo_point(min_lon, min_lat)?,
+            max: parse_geo_point(max_lon, max_lat)?,
+            count: parse_geo_count(options).unwrap()
+        }),
+        ("GEO.DIST", [key, from, to, unit @ ..]) if unit.len() <= 1 && unit.iter().all(|unit| parse_geo_unit(unit).is_some()) => Ok(QueryRequest::GEO_DIST {
+            key: validate_key(key)?.to_owned(),
+            from: parse_set_value(from, database_type)?,
+            to: parse_set_value(to, database_type)?,
+            unit: unit.first().map_or(GeoUnit::Meters, |unit| parse_geo_unit(unit).unwrap())
+        }),
+        _ => {
+            let usage: &str = match command {
+                "LPUSH" | "RPUSH" | "SADD" | "SREM" | "ZREM" => "<key> <value1> ... <valueN>",
+                "BLPOP" => "<key> <timeout seconds>",
+                "LRANGE" => "<key> <start index> <stop index>",
+                "SISMEMBER" => "<key> <value>",
+                "HSET" => "<key> <field1> <value1> ... <fieldN> <valueN>",
+                "HGET" => "<key> <field>",
+                "HDEL" => "<key> <field1> ... <fieldN>",
+                "ZADD" => "<key> <score1> <value1> ... <scoreN> <valueN>",
+                "ZINCRBY" => "<key> <increment> <value>",
+                "ZRANGE" => "<key> <start rank> <stop rank> [REV]' or 'ZRANGE <key> <min score> <max score> BYSCORE",
+                "ZRANK" => "<key> <value> [REV]",
+                "ZCOUNT" => "<key> <min score> <max score>",
+                "TS.ADD" => "<key> <timestamp> <value> [RETENTION <milliseconds>]",
+                "TS.RANGE" => TS_RANGE_USAGE,
+                "PFMERGE" => "<destination key> <source key1> ... <source keyN>",
+                "BF.RESERVE" => "<key> <error rate> <capacity>",
+                "BF.ADD" | "BF.EXISTS" => "<key> <value>",
+                "THROTTLE" => "<key> <max burst> <rate per period> <period milliseconds> [cost]",
+                "GEO.ADD" => "<key> <value> <longitude> <latitude>",
+                "GEO.RADIUS" => "<key> <longitude> <latitude> <radius> m|km|mi|ft [COUNT <n>]",
+                "GEO.BOX" => "<key> <min longitude> <min latitude> <max longitude> <max latitude> [COUNT <n>]",
+                "GEO.DIST" => "<key> <value1> <value2> [m|km|mi|ft]",
+                _ => "<key>"
+            };
+            parser_error!(ParserErrorType::InvalidSyntax(format!("{} {}", command, usage)))
+        }
+    }
+}
+
+
+/// Parses the parameters of a LOCK, UNLOCK, RENEW or LOCKINFO query.
+/// 
+/// # Arguments:
+/// * `command`: The command, e.g "LOCK" if the query was "LOCK leader worker-1 30000".
+/// * `query`: A string containing the parameters of the query, ie. everything after the command.
+/// 
+/// # Returns:
+/// An instance of `QueryRequest`, variants: LOCK, UNLOCK, RENEW, LOCKINFO or ERROR (if the parse failed).
+fn parse_lock<'a>(command: &str, query: &'a str) -> Result<QueryRequest<'a>, String> {
+    let tokens: Vec<&str> = split_at_delimiter(query, ' ');
+
+    match (command, &tokens[..]) {
+        ("LOCK", [name, owner, ttl]) => Ok(QueryRequest::LOCK {
+            name: validate_key(name)?.to_owned(),
+            owner: validate_key(owner)?.to_owned(),
+            ttl: parse_millis(ttl, false)?
+        }),
+        ("UNLOCK", [name, owner]) => Ok(QueryRequest::UNLOCK { name: validate_key(name)?.to_owned(), owner: validate_key(owner)?.to_owned() }),
+        ("RENEW", [name, owner, ttl]) => Ok(QueryRequest::RENEW {
+            name: validate_key(name)?.to_owned(),
+            owner: validate_key(owner)?.to_owned(),
+            ttl: parse_millis(ttl, false)?
+        }),
+        ("LOCKINFO", [name]) => Ok(QueryRequest::LOCKINFO(validate_key(name)?.to_owned())),
+        _ => {
+            let usage: &str = match command {
+                "LOCK" | "RENEW" => "<name> <owner> <ttl milliseconds>",
+                "UNLOCK" => "<name> <owner>",
+                _ => "<name>"
+            };
+            parser_error!(ParserErrorType::InvalidSyntax(format!("{} {}", command, usage)))
+        }
+    }
+}
+
+/// Parses the parameters of a SEQ.NEXT, SEQ.SET or SEQ.GET query.
+/// 
+/// # Arguments:
+/// * `command`: The command, e.g "SEQ.NEXT" if the query was "SEQ.NEXT orders 100".
+/// * `query`: A string containing the parameters of the query, ie. everything after the command.
+/// 
+/// # Returns:
+/// An instance of `QueryRequest`, variants: SEQ_NEXT, SEQ_SET, SEQ_GET or ERROR (if the parse failed).
+fn parse_sequence<'a>(command: &str, query: &'a str) -> Result<QueryRequest<'a>, String> {
+    let tokens: Vec<&str> = split_at_delimiter(query, ' ');
+
+    match (command, &tokens[..]) {
+        ("SEQ.NEXT", [name]) => Ok(QueryRequest::SEQ_NEXT { name: validate_key(name)?.to_owned(), count: 1 }),
+        ("SEQ.NEXT", [name, count]) if count.parse::<u64>().is_ok_and(|count| count > 0) => Ok(QueryRequest::SEQ_NEXT {
+            name: validate_key(name)?.to_owned(),
+            count: count.parse::<u64>().unwrap()
+        }),
+        ("SEQ.SET", [name, last_id]) if last_id.parse::<u64>().is_ok() => Ok(QueryRequest::SEQ_SET {
+            name: validate_key(name)?.to_owned(),
+            last_id: last_id.parse::<u64>().unwrap()
+        }),
+        ("SEQ.GET", [name]) => Ok(QueryRequest::SEQ_GET(validate_key(name)?.to_owned())),
+        _ => {
+            let usage: &str = match command {
+                "SEQ.NEXT" => "<name> [count]",
+                "SEQ.SET" => "<name> <last id>",
+                _ => "<name>"
+            };
+            parser_error!(ParserErrorType::InvalidSyntax(format!("{} {}", command, usage)))
+        }
+    }
+}
+
 fn parse_single_command<'a>(request: &'a str, expected_command: &'a str, query_request: QueryRequest<'a>) -> Result<QueryRequest<'a>, String> {
     if request.len() > expected_command.len() {
         return parser_error!(ParserErrorType::UnexpectedParameters(expected_command.to_string()));
@@ -343,7 +1254,7 @@ fn parse_single_command<'a>(request: &'a str, expected_command: &'a str, query_r
 /// An instance of `QueryRequest`, variants: GET, GET_RANGE, GET_MANY, SET, DEL, DEL_RANGE, DEL_MANY, or ERROR (if the parse failed).
 pub fn parse<'a>(request: &'a str, database_type: &DatabaseType) -> Result<QueryRequest<'a>, String> {
     if request.starts_with("GET ") {
-        return parse_get(request.strip_prefix("GET ").unwrap());
+        return parse_get(request.strip_prefix("GET ").unwrap(), database_type);
     }
     else if request.starts_with("DEL ") {
         return parse_del(request.strip_prefix("DEL ").unwrap());
@@ -366,9 +1277,66 @@ pub fn parse<'a>(request: &'a str, database_type: &DatabaseType) -> Result<Query
     else if request.starts_with("EXISTS ") {
         return parse_exists(request.strip_prefix("EXISTS ").unwrap());
     }
+    else if request.starts_with("KEYS ") {
+        return parse_keys(request.strip_prefix("KEYS ").unwrap());
+    }
+    else if request.starts_with("FIRST") {
+        return parse_single_command(request, "FIRST", QueryRequest::FIRST);
+    }
+    else if request.starts_with("LAST") {
+        return parse_single_command(request, "LAST", QueryRequest::LAST);
+    }
+    else if request.starts_with("NEXT ") {
+        return parse_navigation(request.strip_prefix("NEXT ").unwrap(), QueryRequest::NEXT);
+    }
+    else if request.starts_with("PREV ") {
+        return parse_navigation(request.strip_prefix("PREV ").unwrap(), QueryRequest::PREV);
+    }
+    else if request.starts_with("FLOOR ") {
+        return parse_navigation(request.strip_prefix("FLOOR ").unwrap(), QueryRequest::FLOOR);
+    }
+    else if request.starts_with("CEIL ") {
+        return parse_navigation(request.strip_prefix("CEIL ").unwrap(), QueryRequest::CEIL);
+    }
+    else if request.starts_with("AGG ") {
+        return parse_agg(request.strip_prefix("AGG ").unwrap());
+    }
+    else if request.starts_with("RENAME ") {
+        return parse_rename(request.strip_prefix("RENAME ").unwrap());
+    }
+    else if request.starts_with("COPY ") {
+        return parse_copy(request.strip_prefix("COPY ").unwrap());
+    }
+    else if request.starts_with("JSON.") {
+        return match request.strip_prefix("JSON.").unwrap().split_once(' ') {
+            Some((command, query)) => parse_json(command, query),
+            None => parser_error!(ParserErrorType::UnknownQueryOperation(request.to_string()))
+        };
+    }
+    else if request.starts_with("INDEX ") {
+        return parse_index(request.strip_prefix("INDEX ").unwrap());
+    }
+    else if request.starts_with("FIND ") {
+        return parse_find(request.strip_prefix("FIND ").unwrap(), database_type);
+    }
+    else if request.starts_with("STRLEN ") {
+        return parse_navigation(request.strip_prefix("STRLEN ").unwrap(), QueryRequest::STRLEN);
+    }
+    else if request.starts_with("SCHEMA GET") {
+        return parse_single_command(request, "SCHEMA GET", QueryRequest::SCHEMA_GET);
+    }
     else if request.starts_with("SHUTDOWN") {
         return parse_single_command(request, "SHUTDOWN", QueryRequest::SHUTDOWN);
     }
+    else if let Some((command, query)) = request.split_once(' ').filter(|(command, _)| COLLECTION_COMMANDS.contains(command)) {
+        return parse_collection(command, query, database_type);
+    }
+    else if let Some((command, query)) = request.split_once(' ').filter(|(command, _)| LOCK_COMMANDS.contains(command)) {
+        return parse_lock(command, query);
+    }
+    else if let Some((command, query)) = request.split_once(' ').filter(|(command, _)| SEQUENCE_COMMANDS.contains(command)) {
+        return parse_sequence(command, query);
+    }
 
     parser_error!(ParserErrorType::UnknownQueryOperation(request.to_string()))
 }
@@ -402,39 +1370,154 @@ mod tests {
 
         let key = validate_key("key\"");
         assert_eq!(key, parser_error!(ParserErrorType::UnescapedDoubleQuote));
+
+        // tuple keys
+        let key = validate_key("(7, 1697000000, \"x\")");
+        assert_eq!(key, Ok("(7, 1697000000, \"x\")"));
+
+        let key = validate_key("(7, x)");
+        assert_eq!(key, parser_error!(ParserErrorType::InvalidTupleKey("(7, x)".to_string())));
+
+        let key = validate_key("\"(7)\"");
+        assert_eq!(key, parser_error!(ParserErrorType::InvalidTupleKey("(7)".to_string())));
+
+        let key = validate_key("(7, \"a/b\")");
+        assert_eq!(key, parser_error!(ParserErrorType::UnexpectedCharacter));
+
+        let key = validate_key("(7, \"a, b\")");
+        assert_eq!(key, parser_error!(ParserErrorType::UnexpectedCharacter));
+
+        let key = validate_key("(7, \"\u{1}x\")");
+        assert_eq!(key, parser_error!(ParserErrorType::UnexpectedCharacter));
     }
 
     #[test]
     fn test_parse_get() {
-        let get_query = parse_get("key");
+        let get_query = parse_get("key", &DatabaseType::Str);
         assert_eq!(get_query, Ok(QueryRequest::GET("key".to_string())));
 
-        let get_query = parse_get("\"key 1\"");
+        let get_query = parse_get("\"key 1\"", &DatabaseType::Str);
         assert_eq!(get_query, Ok(QueryRequest::GET("key 1".to_string())));
 
-        let get_query = parse_get("key0 key1");
+        let get_query = parse_get("key0 key1", &DatabaseType::Str);
         assert_eq!(get_query, parser_error!(ParserErrorType::UnexpectedCharacter));
     }
 
+    #[test]
+    fn test_parse_blocking() {
+        let get_query = parse_get("key WAIT 2.5", &DatabaseType::Str);
+        assert_eq!(get_query, Ok(QueryRequest::GET_WAIT { key: "key".to_string(), timeout: Some(Duration::from_millis(2500)) }));
+
+        let get_query = parse_get("\"key 1\" WAIT 0", &DatabaseType::Str);
+        assert_eq!(get_query, Ok(QueryRequest::GET_WAIT { key: "key 1".to_string(), timeout: None }));
+
+        let query = parse_collection("BLPOP", "jobs 5", &DatabaseType::Int);
+        assert_eq!(query, Ok(QueryRequest::BLPOP { key: "jobs".to_string(), timeout: Some(Duration::from_secs(5)) }));
+
+        let query = parse_collection("BLPOP", "jobs", &DatabaseType::Int);
+        assert_eq!(query, parser_error!(ParserErrorType::InvalidSyntax("BLPOP <key> <timeout seconds>".to_string())));
+
+        for timeout in ["-1", "inf", "NaN", "soon"] {
+            let query: String = format!("jobs {}", timeout);
+            let query = parse_collection("BLPOP", &query, &DatabaseType::Int);
+            assert_eq!(query, parser_error!(ParserErrorType::InvalidTimeout(timeout.to_string())));
+        }
+    }
+
+    #[test]
+    fn test_parse_get_range_where() {
+        let get_range_query = parse_get("RANGE a z WHERE value > 10", &DatabaseType::Int);
+        assert_eq!(get_range_query, Ok(QueryRequest::GET_RANGE {
+            key_lower: "a".to_string(),
+            key_upper: "z".to_string(),
+            predicates: vec![Predicate::Value { operator: ComparisonOperator::Gt, literal: ValueType::Int(10) }]
+        }));
+
+        let get_range_query = parse_get("RANGE a z WHERE value CONTAINS \"err or\" AND value != \"error\"", &DatabaseType::Str);
+        assert_eq!(get_range_query, Ok(QueryRequest::GET_RANGE {
+            key_lower: "a".to_string(),
+            key_upper: "z".to_string(),
+            predicates: vec![
+                Predicate::Value { operator: ComparisonOperator::Contains, literal: ValueType::Str("err or".to_string()) },
+                Predicate::Value { operator: ComparisonOperator::Ne, literal: ValueType::Str("error".to_string()) },
+            ]
+        }));
+
+        let get_range_query = parse_get("RANGE a z WHERE $.field = 3", &DatabaseType::Json);
+        assert_eq!(get_range_query, Ok(QueryRequest::GET_RANGE {
+            key_lower: "a".to_string(),
+            key_upper: "z".to_string(),
+            predicates: vec![Predicate::Path { path: JsonPath::parse("$.field").unwrap(), operator: ComparisonOperator::Eq, literal: IndexValue::Number(3.0) }]
+        }));
+
+        let get_range_query = parse_get("RANGE a z WHERE value AFTER 2023-10-11T07:06:40+02:00", &DatabaseType::Timestamp);
+        assert_eq!(get_range_query, Ok(QueryRequest::GET_RANGE {
+            key_lower: "a".to_string(),
+            key_upper: "z".to_string(),
+            predicates: vec![Predicate::Value { operator: ComparisonOperator::After, literal: ValueType::Timestamp(Timestamp::from_millis(1697000800000).unwrap()) }]
+        }));
+
+        // type errors
+        let get_range_query = parse_get("RANGE a z WHERE value > \"10\"", &DatabaseType::Int);
+        assert_eq!(get_range_query, parser_error!(ParserErrorType::WrongValueType("INT".to_string())));
+
+        let get_range_query = parse_get("RANGE a z WHERE value CONTAINS 1", &DatabaseType::Int);
+        assert_eq!(get_range_query, parser_error!(ParserErrorType::InvalidPredicate("the operator 'CONTAINS' is not supported on 'INT' databases".to_string())));
+
+        let get_range_query = parse_get("RANGE a z WHERE value < true", &DatabaseType::Bool);
+        assert_eq!(get_range_query, parser_error!(ParserErrorType::InvalidPredicate("the operator '<' is not supported on 'BOOL' databases".to_string())));
+
+        let get_range_query = parse_get("RANGE a z WHERE value AFTER 10", &DatabaseType::Int);
+        assert_eq!(get_range_query, parser_error!(ParserErrorType::InvalidPredicate("the operator 'AFTER' is not supported on 'INT' databases".to_string())));
+
+        let get_range_query = parse_get("RANGE a z WHERE value BEFORE yesterday", &DatabaseType::Timestamp);
+        assert_eq!(get_range_query, parser_error!(ParserErrorType::WrongValueType("TIMESTAMP".to_string())));
+
+        let get_range_query = parse_get("RANGE a z WHERE $.field = 3", &DatabaseType::Float);
+        assert_eq!(get_range_query, parser_error!(ParserErrorType::InvalidPredicate("JSON paths can only be used on JSON databases".to_string())));
+
+        let get_range_query = parse_get("RANGE a z WHERE value = 3", &DatabaseType::Json);
+        assert_eq!(get_range_query, parser_error!(ParserErrorType::InvalidPredicate("use a JSON path like '$.field' on JSON databases".to_string())));
+
+        let get_range_query = parse_get("RANGE a z WHERE $.field CONTAINS 3", &DatabaseType::Json);
+        assert_eq!(get_range_query, parser_error!(ParserErrorType::InvalidPredicate("the operator 'CONTAINS' expects a string".to_string())));
+
+        let get_range_query = parse_get("RANGE a z WHERE value ~ 3", &DatabaseType::Int);
+        assert_eq!(get_range_query, parser_error!(ParserErrorType::InvalidPredicate("unknown operator '~'".to_string())));
+
+        let get_range_query = parse_get("RANGE a z WHERE value > 3 AND", &DatabaseType::Int);
+        assert_eq!(get_range_query, parser_error!(ParserErrorType::InvalidPredicate("expected '<operand> <operator> <value>'".to_string())));
+    }
+
     #[test]
     fn test_parse_get_range() {
-        let get_range_query = parse_get("RANGE key0 key1");
-        assert_eq!(get_range_query, Ok(QueryRequest::GET_RANGE { key_lower: "key0".to_string(), key_upper: "key1".to_string() }));
+        let get_range_query = parse_get("RANGE key0 key1", &DatabaseType::Str);
+        assert_eq!(get_range_query, Ok(QueryRequest::GET_RANGE { key_lower: "key0".to_string(), key_upper: "key1".to_string(), predicates: vec![] }));
 
-        let get_range_query = parse_get("RANGE \"key / 1\" \"key / 2\"");
-        assert_eq!(get_range_query, Ok(QueryRequest::GET_RANGE { key_lower: "key / 1".to_string(), key_upper: "key / 2".to_string() }));
+        let get_range_query = parse_get("RANGE \"key / 1\" \"key / 2\"", &DatabaseType::Str);
+        assert_eq!(get_range_query, Ok(QueryRequest::GET_RANGE { key_lower: "key / 1".to_string(), key_upper: "key / 2".to_string(), predicates: vec![] }));
 
-        let get_range_query = parse_get("RANGE key0");
+        let get_range_query = parse_get("RANGE key0", &DatabaseType::Str);
         assert_eq!(get_range_query, parser_error!(ParserErrorType::InvalidRange(1)));
+
+        // partial tuples
+        let get_range_query = parse_get("RANGE (7, 1697000000) (7, 1697999999)", &DatabaseType::Str);
+        assert_eq!(get_range_query, Ok(QueryRequest::GET_RANGE { key_lower: "(7, 1697000000)".to_string(), key_upper: "(7, 1697999999)".to_string(), predicates: vec![] }));
     }
 
     #[test]
     fn test_parse_get_many() {
-        let get_query = parse_get("MANY key0 key1 key2");
+        let get_query = parse_get("MANY key0 key1 key2", &DatabaseType::Str);
         assert_eq!(get_query, Ok(QueryRequest::GET_MANY(vec!["key0", "key1", "key2"])));
 
-        let get_query = parse_get("MANY \"key,1\" \"key 2\" \"key/3\" \"key \\\"4\\\"\"");
+        let get_query = parse_get("MANY \"key,1\" \"key 2\" \"key/3\" \"key \\\"4\\\"\"", &DatabaseType::Str);
         assert_eq!(get_query, Ok(QueryRequest::GET_MANY(vec!["key,1", "key 2", "key/3", "key \\\"4\\\""])));
+
+        let get_query = parse_get("MANY key0 key1 NULLS", &DatabaseType::Str);
+        assert_eq!(get_query, Ok(QueryRequest::GET_MANY_NULLS(vec!["key0", "key1"])));
+
+        let get_query = parse_get("MANY key0 \"NULLS\"", &DatabaseType::Str);
+        assert_eq!(get_query, Ok(QueryRequest::GET_MANY(vec!["key0", "NULLS"])));
     }
 
     // Unit tests for the `parse_ranged_keys` function:
@@ -499,6 +1582,9 @@ mod tests {
         let set_query = parse_set("key 1", &DatabaseType::Int);
         assert_eq!(set_query, Ok(QueryRequest::SET(KeyValuePair { key: "key".to_owned(), value: ValueType::Int(1) })));
 
+        let set_query = parse_set("service:api 8080 EPHEMERAL", &DatabaseType::Int);
+        assert_eq!(set_query, Ok(QueryRequest::SET_EPHEMERAL(KeyValuePair { key: "service:api".to_owned(), value: ValueType::Int(8080) })));
+
         let set_query = parse_set("key 0.95", &DatabaseType::Float);
         assert_eq!(set_query, Ok(QueryRequest::SET(KeyValuePair { key: "key".to_owned(), value: ValueType::Float(0.95) })));
 
@@ -508,8 +1594,48 @@ mod tests {
         let set_query = parse_set("key false", &DatabaseType::Bool);
         assert_eq!(set_query, Ok(QueryRequest::SET(KeyValuePair { key: "key".to_owned(), value: ValueType::Bool(false) })));
 
+        let set_query = parse_set("key \"{\\\"key2\\\": 20, \\\"key1\\\": [1, 2]}\"", &DatabaseType::Json);
+        assert_eq!(set_query, Ok(QueryRequest::SET(KeyValuePair { key: "key".to_owned(), value: ValueType::Json("{\"key1\":[1,2],\"key2\":20}".to_owned()) })));
+
         let set_query = parse_set("key \"{key1: 10, key2: 20}\"", &DatabaseType::Json);
-        assert_eq!(set_query, Ok(QueryRequest::SET(KeyValuePair { key: "key".to_owned(), value: ValueType::Json("{key1: 10, key2: 20}".to_owned()) })));
+        assert_eq!(set_query, parser_error!(ParserErrorType::InvalidJson));
+
+        // only the escaping of the protocol is undone, quotes escaped inside JSON strings stay escaped
+        let set_query = parse_set("key \"{\\\"msg\\\":\\\"say \\\\\"hi\\\\\"\\\"}\"", &DatabaseType::Json);
+        assert_eq!(set_query, Ok(QueryRequest::SET(KeyValuePair { key: "key".to_owned(), value: ValueType::Json("{\"msg\":\"say \\\"hi\\\"\"}".to_owned()) })));
+
+        let set_query = parse_set("key \"\"hi\\\"\"", &DatabaseType::Json);
+        assert_eq!(set_query, parser_error!(ParserErrorType::UnescapedDoubleQuote));
+
+        let set_query = parse_set("key 1697000000123", &DatabaseType::Int64);
+        assert_eq!(set_query, Ok(QueryRequest::SET(KeyValuePair { key: "key".to_owned(), value: ValueType::Int64(1697000000123) })));
+
+        let set_query = parse_set("key 1697000000123", &DatabaseType::Int);
+        assert_eq!(set_query, parser_error!(ParserErrorType::WrongValueType("INT".to_string())));
+
+        let set_query = parse_set("key 0.1234567890123", &DatabaseType::Float64);
+        assert_eq!(set_query, Ok(QueryRequest::SET(KeyValuePair { key: "key".to_owned(), value: ValueType::Float64(0.1234567890123) })));
+
+        let set_query = parse_set("key -19.99", &DatabaseType::Decimal);
+        assert_eq!(set_query, Ok(QueryRequest::SET(KeyValuePair { key: "key".to_owned(), value: ValueType::Decimal(Decimal::new(-1999, 2)) })));
+
+        let set_query = parse_set("key 1e5", &DatabaseType::Decimal);
+        assert_eq!(set_query, parser_error!(ParserErrorType::WrongValueType("DECIMAL".to_string())));
+
+        let set_query = parse_set("key \"AP/+Pj8=\"", &DatabaseType::Bytes);
+        assert_eq!(set_query, Ok(QueryRequest::SET(KeyValuePair { key: "key".to_owned(), value: ValueType::Bytes(vec![0, 255, 254, 62, 63]) })));
+
+        let set_query = parse_set("key \"AP/+Pj8\"", &DatabaseType::Bytes);
+        assert_eq!(set_query, parser_error!(ParserErrorType::WrongValueType("BYTES".to_string())));
+
+        let set_query = parse_set("key 2023-10-11T05:06:40.123Z", &DatabaseType::Timestamp);
+        assert_eq!(set_query, Ok(QueryRequest::SET(KeyValuePair { key: "key".to_owned(), value: ValueType::Timestamp(Timestamp::from_millis(1697000800123).unwrap()) })));
+
+        let set_query = parse_set("key 1697000800123", &DatabaseType::Timestamp);
+        assert_eq!(set_query, Ok(QueryRequest::SET(KeyValuePair { key: "key".to_owned(), value: ValueType::Timestamp(Timestamp::from_millis(1697000800123).unwrap()) })));
+
+        let set_query = parse_set("key 2023-10-11", &DatabaseType::Timestamp);
+        assert_eq!(set_query, parser_error!(ParserErrorType::WrongValueType("TIMESTAMP".to_string())));
 
         // test escaped quotes
         let set_query = parse_set("key \"name: \\\"ANON\\\"\"",&DatabaseType::Str);
@@ -613,6 +1739,454 @@ mod tests {
         assert_eq!(failed_exists_request, parser_error!(ParserErrorType::UnexpectedCharacter));
     }
 
+    // Unit tests for the `parse_keys` function:
+
+    #[test]
+    fn test_glob_to_regex() {
+        assert_eq!(glob_to_regex("session:*:2026-10-*"), "^session:.*:2026\\-10\\-.*$");
+        assert_eq!(glob_to_regex("key?"), "^key.$");
+        assert_eq!(glob_to_regex("key[0-9]"), "^key[0-9]$");
+        assert_eq!(glob_to_regex("key[!ab]"), "^key[^ab]$");
+        assert_eq!(glob_to_regex("key\\*"), "^key\\*$");
+        assert_eq!(glob_to_regex("key["), "^key\\[$");
+    }
+
+    #[test]
+    fn test_literal_prefix() {
+        assert_eq!(glob_literal_prefix("session:*:2026-10-*"), "session:");
+        assert_eq!(glob_literal_prefix("key\\*1*"), "key*1");
+        assert_eq!(glob_literal_prefix("*:2026"), "");
+
+        assert_eq!(regex_literal_prefix("^user:[0-9]+$"), "user:");
+        assert_eq!(regex_literal_prefix("^users?"), "user");
+        assert_eq!(regex_literal_prefix("^user|^admin"), "");
+        assert_eq!(regex_literal_prefix("user:"), "");
+    }
+
+    #[test]
+    fn test_parse_keys() {
+        let keys_query = parse_keys("MATCH session:*");
+        assert_eq!(keys_query, Ok(QueryRequest::KEYS { pattern: "^session:.*$".to_string(), prefix: "session:".to_string(), count: KEYS_DEFAULT_COUNT }));
+
+        let keys_query = parse_keys("MATCH \"my key*\" COUNT 5");
+        assert_eq!(keys_query, Ok(QueryRequest::KEYS { pattern: "^my key.*$".to_string(), prefix: "my key".to_string(), count: 5 }));
+
+        let keys_query = parse_keys("MATCH ^user:[0-9]+$ REGEX COUNT 10");
+        assert_eq!(keys_query, Ok(QueryRequest::KEYS { pattern: "^user:[0-9]+$".to_string(), prefix: "user:".to_string(), count: 10 }));
+
+        let keys_query = parse_keys("MATCH user:( REGEX");
+        assert_eq!(keys_query, parser_error!(ParserErrorType::InvalidPattern("user:(".to_string())));
+
+        let keys_query = parse_keys("MATCH * COUNT 0");
+        assert_eq!(keys_query, parser_error!(ParserErrorType::InvalidSyntax("KEYS MATCH <pattern> [REGEX] [COUNT <n>]".to_string())));
+
+        let keys_query = parse_keys("session:*");
+        assert_eq!(keys_query, parser_error!(ParserErrorType::InvalidSyntax("KEYS MATCH <pattern> [REGEX] [COUNT <n>]".to_string())));
+    }
+
+    // Unit tests for the `parse_navigation` function:
+
+    #[test]
+    fn test_parse_navigation() {
+        let next_request = parse_navigation("key", QueryRequest::NEXT);
+        assert_eq!(next_request, Ok(QueryRequest::NEXT("key".to_string())));
+
+        let floor_request = parse_navigation("\"key 1\"", QueryRequest::FLOOR);
+        assert_eq!(floor_request, Ok(QueryRequest::FLOOR("key 1".to_string())));
+
+        let failed_request = parse_navigation("key1 key2", QueryRequest::CEIL);
+        assert_eq!(failed_request, parser_error!(ParserErrorType::UnexpectedCharacter));
+    }
+
+    // Unit tests for the `parse_agg` function:
+
+    #[test]
+    fn test_parse_agg() {
+        let agg_query = parse_agg("SUM key0 key9");
+        assert_eq!(agg_query, Ok(QueryRequest::AGG { aggregation: AggregationType::Sum, key_lower: "key0".to_string(), key_upper: "key9".to_string() }));
+
+        let agg_query = parse_agg("COUNT \"key 0\" \"key 9\"");
+        assert_eq!(agg_query, Ok(QueryRequest::AGG { aggregation: AggregationType::Count, key_lower: "key 0".to_string(), key_upper: "key 9".to_string() }));
+
+        let agg_query = parse_agg("AVG key0");
+        assert_eq!(agg_query, parser_error!(ParserErrorType::InvalidRange(1)));
+
+        let agg_query = parse_agg("MEDIAN key0 key9");
+        assert_eq!(agg_query, parser_error!(ParserErrorType::InvalidSyntax("AGG SUM|AVG|MIN|MAX|COUNT <lower key> <upper key>".to_string())));
+    }
+
+    // Unit tests for the `parse_rename` and `parse_copy` functions:
+
+    #[test]
+    fn test_parse_rename() {
+        let rename_query = parse_rename("key1 key2");
+        assert_eq!(rename_query, Ok(QueryRequest::RENAME { key: "key1".to_string(), new_key: "key2".to_string(), nx: false }));
+
+        let rename_query = parse_rename("key1 \"key 2\" NX");
+        assert_eq!(rename_query, Ok(QueryRequest::RENAME { key: "key1".to_string(), new_key: "key 2".to_string(), nx: true }));
+
+        let rename_query = parse_rename("PREFIX user: customer:");
+        assert_eq!(rename_query, Ok(QueryRequest::RENAME_PREFIX { prefix: "user:".to_string(), new_prefix: "customer:".to_string() }));
+
+        // a key called PREFIX
+        let rename_query = parse_rename("PREFIX key");
+        assert_eq!(rename_query, Ok(QueryRequest::RENAME { key: "PREFIX".to_string(), new_key: "key".to_string(), nx: false }));
+
+        let rename_query = parse_rename("key1 key/2");
+        assert_eq!(rename_query, parser_error!(ParserErrorType::UnexpectedCharacter));
+
+        let rename_query = parse_rename("key1 key2 XX");
+        assert_eq!(rename_query, parser_error!(ParserErrorType::InvalidSyntax("RENAME <key> <new key> [NX]' or 'RENAME PREFIX <prefix> <new prefix>".to_string())));
+    }
+
+    #[test]
+    fn test_parse_copy() {
+        let copy_query = parse_copy("key1 key2");
+        assert_eq!(copy_query, Ok(QueryRequest::COPY { key: "key1".to_string(), new_key: "key2".to_string(), replace: false }));
+
+        let copy_query = parse_copy("key1 key2 REPLACE");
+        assert_eq!(copy_query, Ok(QueryRequest::COPY { key: "key1".to_string(), new_key: "key2".to_string(), replace: true }));
+
+        let copy_query = parse_copy("key1");
+        assert_eq!(copy_query, parser_error!(ParserErrorType::InvalidSyntax("COPY <key> <new key> [REPLACE]".to_string())));
+    }
+
+    // Unit tests for the `parse_json` function:
+
+    #[test]
+    fn test_parse_json() {
+        let json_query = parse_json("GET", "key $.user.name");
+        assert_eq!(json_query, Ok(QueryRequest::JSON_GET { key: "key".to_string(), path: JsonPath::parse("$.user.name").unwrap() }));
+
+        let json_query = parse_json("SET", "key $.tags[0] \"\\\"new\\\"\"");
+        assert_eq!(json_query, Ok(QueryRequest::JSON_SET { key: "key".to_string(), path: JsonPath::parse("$.tags[0]").unwrap(), value: "\"new\"".to_string() }));
+
+        let json_query = parse_json("DEL", "key $.tags");
+        assert_eq!(json_query, Ok(QueryRequest::JSON_DEL { key: "key".to_string(), path: JsonPath::parse("$.tags").unwrap() }));
+
+        let json_query = parse_json("MERGE", "key \"{\\\"a\\\": null}\"");
+        assert_eq!(json_query, Ok(QueryRequest::JSON_MERGE { key: "key".to_string(), patch: "{\"a\":null}".to_string() }));
+
+        let json_query = parse_json("GET", "key user.name");
+        assert_eq!(json_query, parser_error!(ParserErrorType::InvalidJsonPath("user.name".to_string())));
+
+        let json_query = parse_json("SET", "key $.a \"{\"");
+        assert_eq!(json_query, parser_error!(ParserErrorType::InvalidJson));
+
+        let json_query = parse_json("SET", "key $.a");
+        assert_eq!(json_query, parser_error!(ParserErrorType::InvalidSyntax("JSON.SET <key> <path> <value>".to_string())));
+    }
+
+    // Unit tests for the `parse_index` and `parse_find` functions:
+
+    #[test]
+    fn test_parse_index() {
+        let index_query = parse_index("CREATE by_status ON $.status");
+        assert_eq!(index_query, Ok(QueryRequest::INDEX_CREATE { name: "by_status".to_string(), path: JsonPath::parse("$.status").unwrap() }));
+
+        let index_query = parse_index("DROP by_status");
+        assert_eq!(index_query, Ok(QueryRequest::INDEX_DROP("by_status".to_string())));
+
+        let index_query = parse_index("CREATE by_status ON status");
+        assert_eq!(index_query, parser_error!(ParserErrorType::InvalidJsonPath("status".to_string())));
+
+        let index_query = parse_index("CREATE by_status $.status");
+        assert_eq!(index_query, parser_error!(ParserErrorType::InvalidSyntax("INDEX CREATE <name> ON <path>' or 'INDEX DROP <name>".to_string())));
+    }
+
+    #[test]
+    fn test_parse_find() {
+        let find_query = parse_find("by_status = \"in progress\"", &DatabaseType::Json);
+        assert_eq!(find_query, Ok(QueryRequest::FIND {
+            name: "by_status".to_string(),
+            lower: IndexValue::Str("in progress".to_string()),
+            upper: IndexValue::Str("in progress".to_string())
+        }));
+
+        let find_query = parse_find("by_age BETWEEN 18 30.5", &DatabaseType::Json);
+        assert_eq!(find_query, Ok(QueryRequest::FIND { name: "by_age".to_string(), lower: IndexValue::Number(18.0), upper: IndexValue::Number(30.5) }));
+
+        let find_query = parse_find("by_age = [18]", &DatabaseType::Json);
+        assert_eq!(find_query, parser_error!(ParserErrorType::InvalidIndexValue("[18]".to_string())));
+
+        let find_query = parse_find("by_status = failed", &DatabaseType::Json);
+        assert_eq!(find_query, parser_error!(ParserErrorType::InvalidIndexValue("failed".to_string())));
+
+        let find_query = parse_find("by_age BETWEEN 18", &DatabaseType::Json);
+        assert_eq!(find_query, parser_error!(ParserErrorType::InvalidSyntax("FIND <index> = <value>' or 'FIND <index> BETWEEN <lower value> <upper value>' or 'FIND VALUE <value>' or 'FIND VALUE BETWEEN <lower value> <upper value>".to_string())));
+
+        let find_query = parse_find("VALUE true", &DatabaseType::Bool);
+        assert_eq!(find_query, Ok(QueryRequest::FIND_VALUE { lower: ValueType::Bool(true), upper: None }));
+
+        let find_query = parse_find("VALUE BETWEEN -5 10", &DatabaseType::Int);
+        assert_eq!(find_query, Ok(QueryRequest::FIND_VALUE { lower: ValueType::Int(-5), upper: Some(ValueType::Int(10)) }));
+
+        let find_query = parse_find("VALUE BETWEEN 1.5 x", &DatabaseType::Float);
+        assert_eq!(find_query, parser_error!(ParserErrorType::WrongValueType("FLOAT".to_string())));
+    }
+
+    #[test]
+    fn test_parse_collection() {
+        let query = parse_collection("LPUSH", "queue 1 -2", &DatabaseType::Int);
+        assert_eq!(query, Ok(QueryRequest::LPUSH { key: "queue".to_string(), values: vec![ValueType::Int(1), ValueType::Int(-2)] }));
+
+        let query = parse_collection("RPUSH", "queue \"a b\"", &DatabaseType::Str);
+        assert_eq!(query, Ok(QueryRequest::RPUSH { key: "queue".to_string(), values: vec![ValueType::Str("a b".to_string())] }));
+
+        let query = parse_collection("LPUSH", "queue", &DatabaseType::Int);
+        assert_eq!(query, parser_error!(ParserErrorType::InvalidSyntax("LPUSH <key> <value1> ... <valueN>".to_string())));
+
+        let query = parse_collection("LPUSH", "queue 1 x", &DatabaseType::Int);
+        assert_eq!(query, parser_error!(ParserErrorType::WrongValueType("INT".to_string())));
+
+        let query = parse_collection("RPOP", "queue", &DatabaseType::Int);
+        assert_eq!(query, Ok(QueryRequest::RPOP("queue".to_string())));
+
+        let query = parse_collection("LRANGE", "queue 0 -1", &DatabaseType::Int);
+        assert_eq!(query, Ok(QueryRequest::LRANGE { key: "queue".to_string(), start: 0, stop: -1 }));
+
+        let query = parse_collection("LRANGE", "queue 0 last", &DatabaseType::Int);
+        assert_eq!(query, parser_error!(ParserErrorType::InvalidSyntax("LRANGE <key> <start index> <stop index>".to_string())));
+
+        let query = parse_collection("SADD", "tags \"a\" \"b\"", &DatabaseType::Str);
+        assert_eq!(query, Ok(QueryRequest::SADD { key: "tags".to_string(), members: vec![ValueType::Str("a".to_string()), ValueType::Str("b".to_string())] }));
+
+        let query = parse_collection("SISMEMBER", "tags \"a\"", &DatabaseType::Str);
+        assert_eq!(query, Ok(QueryRequest::SISMEMBER { key: "tags".to_string(), member: ValueType::Str("a".to_string()) }));
+
+        let query = parse_collection("SMEMBERS", "tags extra", &DatabaseType::Str);
+        assert_eq!(query, parser_error!(ParserErrorType::InvalidSyntax("SMEMBERS <key>".to_string())));
+
+        let query = parse_collection("HSET", "user:1 age 30 height 180", &DatabaseType::Int);
+        assert_eq!(query, Ok(QueryRequest::HSET { key: "user:1".to_string(), fields: vec![
+            KeyValuePair { key: "age".to_string(), value: ValueType::Int(30) },
+            KeyValuePair { key: "height".to_string(), value: ValueType::Int(180) }
+        ]}));
+
+        let query = parse_collection("HSET", "user:1 age 30 height", &DatabaseType::Int);
+        assert_eq!(query, parser_error!(ParserErrorType::InvalidSyntax("HSET <key> <field1> <value1> ... <fieldN> <valueN>".to_string())));
+
+        let query = parse_collection("HGET", "user:1 age", &DatabaseType::Int);
+        assert_eq!(query, Ok(QueryRequest::HGET { key: "user:1".to_string(), field: "age".to_string() }));
+
+        let query = parse_collection("HDEL", "user:1 age height", &DatabaseType::Int);
+        assert_eq!(query, Ok(QueryRequest::HDEL { key: "user:1".to_string(), fields: vec!["age".to_string(), "height".to_string()] }));
+
+        let query = parse_collection("HGETALL", "user:1", &DatabaseType::Int);
+        assert_eq!(query, Ok(QueryRequest::HGETALL("user:1".to_string())));
+    }
+
+    #[test]
+    fn test_parse_sorted_set() {
+        let query = parse_collection("ZADD", "board 12.5 \"alice\" -inf \"bob\"", &DatabaseType::Str);
+        assert_eq!(query, Ok(QueryRequest::ZADD { key: "board".to_string(), members: vec![
+            ScoredMember { member: ValueType::Str("alice".to_string()), score: 12.5 },
+            ScoredMember { member: ValueType::Str("bob".to_string()), score: f64::NEG_INFINITY }
+        ]}));
+
+        let query = parse_collection("ZADD", "board 12.5", &DatabaseType::Str);
+        assert_eq!(query, parser_error!(ParserErrorType::InvalidSyntax("ZADD <key> <score1> <value1> ... <scoreN> <valueN>".to_string())));
+
+        let query = parse_collection("ZADD", "board NaN \"alice\"", &DatabaseType::Str);
+        assert_eq!(query, parser_error!(ParserErrorType::InvalidScore("NaN".to_string())));
+
+        let query = parse_collection("ZINCRBY", "board -2 7", &DatabaseType::Int);
+        assert_eq!(query, Ok(QueryRequest::ZINCRBY { key: "board".to_string(), increment: -2.0, member: ValueType::Int(7) }));
+
+        let query = parse_collection("ZRANGE", "board 0 9 REV", &DatabaseType::Int);
+        assert_eq!(query, Ok(QueryRequest::ZRANGE { key: "board".to_string(), start: 0, stop: 9, reverse: true }));
+
+        let query = parse_collection("ZRANGE", "board 10 inf BYSCORE", &DatabaseType::Int);
+        assert_eq!(query, Ok(QueryRequest::ZRANGE_BYSCORE { key: "board".to_string(), min: 10.0, max: f64::INFINITY }));
+
+        let query = parse_collection("ZRANGE", "board 1.5 2", &DatabaseType::Int);
+        assert_eq!(query, parser_error!(ParserErrorType::InvalidSyntax("ZRANGE <key> <start rank> <stop rank> [REV]' or 'ZRANGE <key> <min score> <max score> BYSCORE".to_string())));
+
+        let query = parse_collection("ZRANK", "board 7 REV", &DatabaseType::Int);
+        assert_eq!(query, Ok(QueryRequest::ZRANK { key: "board".to_string(), member: ValueType::Int(7), reverse: true }));
+
+        let query = parse_collection("ZREM", "board 7 8", &DatabaseType::Int);
+        assert_eq!(query, Ok(QueryRequest::ZREM { key: "board".to_string(), members: vec![ValueType::Int(7), ValueType::Int(8)] }));
+
+        let query = parse_collection("ZCOUNT", "board -inf 0", &DatabaseType::Int);
+        assert_eq!(query, Ok(QueryRequest::ZCOUNT { key: "board".to_string(), min: f64::NEG_INFINITY, max: 0.0 }));
+    }
+
+    #[test]
+    fn test_parse_time_series() {
+        let query = parse_collection("TS.ADD", "temp 2023-10-11T05:06:40.123Z 21.5", &DatabaseType::Float);
+        assert_eq!(query, Ok(QueryRequest::TS_ADD { key: "temp".to_string(), timestamp: Some(1697000800123), value: ValueType::Float(21.5), retention: None }));
+
+        let query = parse_collection("TS.ADD", "temp * 21.5 RETENTION 3600000", &DatabaseType::Float);
+        assert_eq!(query, Ok(QueryRequest::TS_ADD { key: "temp".to_string(), timestamp: None, value: ValueType::Float(21.5), retention: Some(3600000) }));
+
+        let query = parse_collection("TS.ADD", "temp yesterday 21.5", &DatabaseType::Float);
+        assert_eq!(query, parser_error!(ParserErrorType::InvalidTimestamp("yesterday".to_string())));
+
+        let query = parse_collection("TS.ADD", "temp * 21.5 RETENTION -1", &DatabaseType::Float);
+        assert_eq!(query, parser_error!(ParserErrorType::InvalidDuration("-1".to_string())));
+
+        let query = parse_collection("TS.RANGE", "temp - +", &DatabaseType::Float);
+        assert_eq!(query, Ok(QueryRequest::TS_RANGE { key: "temp".to_string(), from: i64::MIN, to: i64::MAX, downsampling: None }));
+
+        let query = parse_collection("TS.RANGE", "temp 1697000000000 + AGG AVG BUCKET 60000", &DatabaseType::Float);
+        assert_eq!(query, Ok(QueryRequest::TS_RANGE {
+            key: "temp".to_string(),
+            from: 1697000000000,
+            to: i64::MAX,
+            downsampling: Some(Downsampling { aggregation: AggregationType::Avg, bucket: 60000 })
+        }));
+
+        let query = parse_collection("TS.RANGE", "temp - + AGG AVG BUCKET 0", &DatabaseType::Float);
+        assert_eq!(query, parser_error!(ParserErrorType::InvalidDuration("0".to_string())));
+
+        let query = parse_collection("TS.RANGE", "temp - + AGG MEDIAN BUCKET 60000", &DatabaseType::Float);
+        assert_eq!(query, parser_error!(ParserErrorType::InvalidSyntax("TS.RANGE <key> <from> <to> [AGG SUM|AVG|MIN|MAX|COUNT BUCKET <milliseconds>]".to_string())));
+    }
+
+    #[test]
+    fn test_parse_probabilistic() {
+        let query = parse_collection("PFADD", "visitors \"u1\" \"u2\"", &DatabaseType::Str);
+        assert_eq!(query, Ok(QueryRequest::PFADD { key: "visitors".to_string(), error_rate: None, values: vec![ValueType::Str("u1".to_string()), ValueType::Str("u2".to_string())] }));
+
+        let query = parse_collection("PFADD", "visitors ERROR 0.02 7", &DatabaseType::Int);
+        assert_eq!(query, Ok(QueryRequest::PFADD { key: "visitors".to_string(), error_rate: Some(0.02), values: vec![ValueType::Int(7)] }));
+
+        let query = parse_collection("PFADD", "visitors ERROR 2 7", &DatabaseType::Int);
+        assert_eq!(query, parser_error!(ParserErrorType::InvalidErrorRate("2".to_string())));
+
+        let query = parse_collection("PFCOUNT", "monday tuesday", &DatabaseType::Int);
+        assert_eq!(query, Ok(QueryRequest::PFCOUNT(vec!["monday", "tuesday"])));
+
+        let query = parse_collection("PFMERGE", "week monday tuesday", &DatabaseType::Int);
+        assert_eq!(query, Ok(QueryRequest::PFMERGE { destination: "week".to_string(), sources: vec!["monday", "tuesday"] }));
+
+        let query = parse_collection("PFMERGE", "week", &DatabaseType::Int);
+        assert_eq!(query, parser_error!(ParserErrorType::InvalidSyntax("PFMERGE <destination key> <source key1> ... <source keyN>".to_string())));
+
+        let query = parse_collection("BF.RESERVE", "seen 0.001 1000000", &DatabaseType::Int);
+        assert_eq!(query, Ok(QueryRequest::BF_RESERVE { key: "seen".to_string(), error_rate: 0.001, capacity: 1000000 }));
+
+        let query = parse_collection("BF.RESERVE", "seen 0.001 0", &DatabaseType::Int);
+        assert_eq!(query, parser_error!(ParserErrorType::InvalidSyntax("BF.RESERVE <key> <error rate> <capacity>".to_string())));
+
+        let query = parse_collection("BF.EXISTS", "seen 42", &DatabaseType::Int);
+        assert_eq!(query, Ok(QueryRequest::BF_EXISTS { key: "seen".to_string(), value: ValueType::Int(42) }));
+    }
+
+    #[test]
+    fn test_parse_throttle() {
+        let query = parse_collection("THROTTLE", "api:alice 10 5 1000", &DatabaseType::Int);
+        assert_eq!(query, Ok(QueryRequest::THROTTLE {
+            key: "api:alice".to_string(),
+            limit: ThrottleLimit { max_burst: 10, rate: 5, period: 1000 },
+            cost: 1
+        }));
+
+        let query = parse("THROTTLE api:alice 10 5 60000 3", &DatabaseType::Str);
+        assert_eq!(query, Ok(QueryRequest::THROTTLE {
+            key: "api:alice".to_string(),
+            limit: ThrottleLimit { max_burst: 10, rate: 5, period: 60000 },
+            cost: 3
+        }));
+
+        let query = parse_collection("THROTTLE", "api:alice 10 5 0", &DatabaseType::Int);
+        assert_eq!(query, parser_error!(ParserErrorType::InvalidDuration("0".to_string())));
+
+        let usage: String = "THROTTLE <key> <max burst> <rate per period> <period milliseconds> [cost]".to_string();
+        let query = parse_collection("THROTTLE", "api:alice 0 5 1000", &DatabaseType::Int);
+        assert_eq!(query, parser_error!(ParserErrorType::InvalidSyntax(usage.clone())));
+        let query = parse_collection("THROTTLE", "api:alice 10 5 1000 -1", &DatabaseType::Int);
+        assert_eq!(query, parser_error!(ParserErrorType::InvalidSyntax(usage.clone())));
+        let query = parse_collection("THROTTLE", "api:alice 10", &DatabaseType::Int);
+        assert_eq!(query, parser_error!(ParserErrorType::InvalidSyntax(usage)));
+    }
+
+    #[test]
+    fn test_parse_lock() {
+        let query = parse("LOCK leader worker-1 30000", &DatabaseType::Int);
+        assert_eq!(query, Ok(QueryRequest::LOCK { name: "leader".to_string(), owner: "worker-1".to_string(), ttl: 30000 }));
+
+        let query = parse_lock("RENEW", "leader \"worker 1\" 30000");
+        assert_eq!(query, Ok(QueryRequest::RENEW { name: "leader".to_string(), owner: "worker 1".to_string(), ttl: 30000 }));
+
+        let query = parse_lock("UNLOCK", "leader worker-1");
+        assert_eq!(query, Ok(QueryRequest::UNLOCK { name: "leader".to_string(), owner: "worker-1".to_string() }));
+
+        let query = parse("LOCKINFO leader", &DatabaseType::Int);
+        assert_eq!(query, Ok(QueryRequest::LOCKINFO("leader".to_string())));
+
+        let query = parse_lock("LOCK", "leader worker-1 0");
+        assert_eq!(query, parser_error!(ParserErrorType::InvalidDuration("0".to_string())));
+
+        let query = parse_lock("LOCK", "leader worker-1");
+        assert_eq!(query, parser_error!(ParserErrorType::InvalidSyntax("LOCK <name> <owner> <ttl milliseconds>".to_string())));
+    }
+
+    #[test]
+    fn test_parse_sequence() {
+        let query = parse("SEQ.NEXT orders", &DatabaseType::Str);
+        assert_eq!(query, Ok(QueryRequest::SEQ_NEXT { name: "orders".to_string(), count: 1 }));
+
+        let query = parse_sequence("SEQ.NEXT", "orders 100");
+        assert_eq!(query, Ok(QueryRequest::SEQ_NEXT { name: "orders".to_string(), count: 100 }));
+
+        let query = parse_sequence("SEQ.SET", "orders 18446744073709551615");
+        assert_eq!(query, Ok(QueryRequest::SEQ_SET { name: "orders".to_string(), last_id: u64::MAX }));
+
+        let query = parse("SEQ.GET orders", &DatabaseType::Str);
+        assert_eq!(query, Ok(QueryRequest::SEQ_GET("orders".to_string())));
+
+        let query = parse_sequence("SEQ.NEXT", "orders 0");
+        assert_eq!(query, parser_error!(ParserErrorType::InvalidSyntax("SEQ.NEXT <name> [count]".to_string())));
+
+        let query = parse_sequence("SEQ.SET", "orders -1");
+        assert_eq!(query, parser_error!(ParserErrorType::InvalidSyntax("SEQ.SET <name> <last id>".to_string())));
+    }
+
+    #[test]
+    fn test_parse_geo() {
+        let query = parse_collection("GEO.ADD", "vehicles \"car1\" 13.405 52.52", &DatabaseType::Str);
+        assert_eq!(query, Ok(QueryRequest::GEO_ADD {
+            key: "vehicles".to_string(),
+            member: ValueType::Str("car1".to_string()),
+            position: GeoPoint { lon: 13.405, lat: 52.52 }
+        }));
+
+        let query = parse("GEO.RADIUS vehicles 13.405 52.52 2 km COUNT 10", &DatabaseType::Str);
+        assert_eq!(query, Ok(QueryRequest::GEO_RADIUS {
+            key: "vehicles".to_string(),
+            center: GeoPoint { lon: 13.405, lat: 52.52 },
+            radius: 2.0,
+            unit: GeoUnit::Kilometers,
+            count: Some(10)
+        }));
+
+        let query = parse_collection("GEO.BOX", "vehicles 179 -1 -179 1", &DatabaseType::Str);
+        assert_eq!(query, Ok(QueryRequest::GEO_BOX {
+            key: "vehicles".to_string(),
+            min: GeoPoint { lon: 179.0, lat: -1.0 },
+            max: GeoPoint { lon: -179.0, lat: 1.0 },
+            count: None
+        }));
+
+        let query = parse_collection("GEO.DIST", "vehicles 1 2", &DatabaseType::Int);
+        assert_eq!(query, Ok(QueryRequest::GEO_DIST { key: "vehicles".to_string(), from: ValueType::Int(1), to: ValueType::Int(2), unit: GeoUnit::Meters }));
+
+        let query = parse_collection("GEO.ADD", "vehicles 1 52.52 13.405x", &DatabaseType::Int);
+        assert_eq!(query, parser_error!(ParserErrorType::InvalidCoordinates("52.52".to_string(), "13.405x".to_string())));
+
+        let query = parse_collection("GEO.ADD", "vehicles 1 13.405 91", &DatabaseType::Int);
+        assert_eq!(query, parser_error!(ParserErrorType::InvalidCoordinates("13.405".to_string(), "91".to_string())));
+
+        let query = parse_collection("GEO.RADIUS", "vehicles 13.405 52.52 -2 km", &DatabaseType::Int);
+        assert_eq!(query, parser_error!(ParserErrorType::InvalidRadius("-2".to_string())));
+
+        let query = parse_collection("GEO.RADIUS", "vehicles 13.405 52.52 2 yards", &DatabaseType::Int);
+        let usage: String = "GEO.RADIUS <key> <longitude> <latitude> <radius> m|km|mi|ft [COUNT <n>]".to_string();
+        assert_eq!(query, parser_error!(ParserErrorType::InvalidSyntax(usage)));
+    }
+
     // Unit tests for the `parse` function:
 
     #[test]
@@ -621,7 +2195,7 @@ mod tests {
         assert_eq!(get_query, Ok(QueryRequest::GET("key".to_string())));
 
         let get_range_query = parse("GET RANGE key0 key1", &DatabaseType::Int);
-        assert_eq!(get_range_query, Ok(QueryRequest::GET_RANGE { key_lower: "key0".to_string(), key_upper: "key1".to_string() }));
+        assert_eq!(get_range_query, Ok(QueryRequest::GET_RANGE { key_lower: "key0".to_string(), key_upper: "key1".to_string(), predicates: vec![] }));
 
         let get_range_query = parse("GET RANGE key0", &DatabaseType::Int);
         assert_eq!(get_range_query, parser_error!(ParserErrorType::InvalidRange(1)));
@@ -650,6 +2224,36 @@ mod tests {
             KeyValuePair { key: "key1".to_owned(), value: ValueType::Int(-10) },
         ])));
 
+        let keys_query = parse("KEYS MATCH key? COUNT 2", &DatabaseType::Str);
+        assert_eq!(keys_query, Ok(QueryRequest::KEYS { pattern: "^key.$".to_string(), prefix: "key".to_string(), count: 2 }));
+
+        let first_query = parse("FIRST", &DatabaseType::Str);
+        assert_eq!(first_query, Ok(QueryRequest::FIRST));
+
+        let lpop_query = parse("LPOP queue", &DatabaseType::Str);
+        assert_eq!(lpop_query, Ok(QueryRequest::LPOP("queue".to_string())));
+
+        let sadd_query = parse("SADD tags 1", &DatabaseType::Int);
+        assert_eq!(sadd_query, Ok(QueryRequest::SADD { key: "tags".to_string(), members: vec![ValueType::Int(1)] }));
+
+        let last_query = parse("LAST now", &DatabaseType::Str);
+        assert_eq!(last_query, parser_error!(ParserErrorType::UnexpectedParameters("LAST".to_string())));
+
+        let prev_query = parse("PREV key", &DatabaseType::Str);
+        assert_eq!(prev_query, Ok(QueryRequest::PREV("key".to_string())));
+
+        let json_query = parse("JSON.GET key $", &DatabaseType::Json);
+        assert_eq!(json_query, Ok(QueryRequest::JSON_GET { key: "key".to_string(), path: JsonPath::parse("$").unwrap() }));
+
+        let json_query = parse("JSON.FIND key", &DatabaseType::Json);
+        assert_eq!(json_query, parser_error!(ParserErrorType::UnknownQueryOperation("JSON.FIND key".to_string())));
+
+        let strlen_query = parse("STRLEN key", &DatabaseType::Bytes);
+        assert_eq!(strlen_query, Ok(QueryRequest::STRLEN("key".to_string())));
+
+        let schema_query = parse("SCHEMA GET", &DatabaseType::Json);
+        assert_eq!(schema_query, Ok(QueryRequest::SCHEMA_GET));
+
         let set_query = parse("UNKNOWN key \"val\"", &DatabaseType::Str);
         assert_eq!(set_query, parser_error!(ParserErrorType::UnknownQueryOperation("UNKNOWN key \"val\"".to_string())));
     }
@@ -680,6 +2284,13 @@ mod tests {
         let split_string: Vec<String> = split_at_delimiter(&string, ' ').into_iter().map(|p| p.replace("\\\"", "\"")).collect();
         assert_eq!(split_string, vec!["CASP/OK/GET/INT/key", "key \"val ue\"", "\n"]);*/
 
+        // test tuples aren't split
+        let split_string: Vec<&str> = split_at_delimiter("(7, \"a) b\") 5 f(x y)", ' ');
+        assert_eq!(split_string, vec!["(7, \"a) b\")", "5", "f(x", "y)"]);
+
+        let split_string: Vec<&str> = split_at_delimiter("(7, 1) 5, (7, 2) 6", ',');
+        assert_eq!(split_string, vec!["(7, 1) 5", "(7, 2) 6"]);
+
         let split_string: Vec<&str> = split_at_delimiter("no dash", '-');
         assert_eq!(split_string, vec!["no dash"]);
     }
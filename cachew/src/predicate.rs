@@ -0,0 +1,171 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::schemas::ValueType;
+use crate::json::JsonPath;
+use crate::index::IndexValue;
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ComparisonOperator {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Contains,
+    After,
+    Before
+}
+
+impl ComparisonOperator {
+
+    /// Parses a comparison operator, e.g `>=` or `CONTAINS`.
+    pub fn parse(operator: &str) -> Option<Self> {
+        match operator {
+            "=" => Some(ComparisonOperator::Eq),
+            "!=" => Some(ComparisonOperator::Ne),
+            "<" => Some(ComparisonOperator::Lt),
+            "<=" => Some(ComparisonOperator::Le),
+            ">" => Some(ComparisonOperator::Gt),
+            ">=" => Some(ComparisonOperator::Ge),
+            "CONTAINS" => Some(ComparisonOperator::Contains),
+            "AFTER" => Some(ComparisonOperator::After),
+            "BEFORE" => Some(ComparisonOperator::Before),
+            _ => None
+        }
+    }
+
+    /// Checks if an ordering between a value and the predicate literal satisfies the operator.
+    fn accepts(&self, ordering: Ordering) -> bool {
+        match self {
+            ComparisonOperator::Eq => ordering == Ordering::Equal,
+            ComparisonOperator::Ne => ordering != Ordering::Equal,
+            ComparisonOperator::Lt | ComparisonOperator::Before => ordering == Ordering::Less,
+            ComparisonOperator::Le => ordering != Ordering::Greater,
+            ComparisonOperator::Gt | ComparisonOperator::After => ordering == Ordering::Greater,
+            ComparisonOperator::Ge => ordering != Ordering::Less,
+            ComparisonOperator::Contains => false,
+        }
+    }
+}
+
+impl fmt::Display for ComparisonOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ComparisonOperator::Eq => write!(f, "="),
+            ComparisonOperator::Ne => write!(f, "!="),
+            ComparisonOperator::Lt => write!(f, "<"),
+            ComparisonOperator::Le => write!(f, "<="),
+            ComparisonOperator::Gt => write!(f, ">"),
+            ComparisonOperator::Ge => write!(f, ">="),
+            ComparisonOperator::Contains => write!(f, "CONTAINS"),
+            ComparisonOperator::After => write!(f, "AFTER"),
+            ComparisonOperator::Before => write!(f, "BEFORE"),
+        }
+    }
+}
+
+
+/// A condition on the values of a range read, e.g `value > 10` or `$.status = "failed"`.
+/// Predicates are type-checked by the parser, so the literal always matches the database type.
+#[derive(Debug, PartialEq)]
+pub enum Predicate {
+    /// Compares the whole value of a scalar database.
+    Value { operator: ComparisonOperator, literal: ValueType },
+    /// Compares the scalar at a path inside the documents of a JSON database.
+    Path { path: JsonPath, operator: ComparisonOperator, literal: IndexValue }
+}
+
+impl Predicate {
+
+    /// Evaluates the predicate on a value.
+    ///
+    /// # Arguments:
+    /// * `value`: The value to check.
+    ///
+    /// # Returns:
+    /// True if the value satisfies the predicate. Values of another type never match.
+    pub fn matches(&self, value: &ValueType) -> bool {
+        match (self, value) {
+            (Predicate::Value { operator: ComparisonOperator::Contains, literal: ValueType::Str(literal) }, ValueType::Str(value)) => value.contains(literal.as_str()),
+            (Predicate::Value { operator, literal }, value) => {
+                let ordering: Option<Ordering> = match (value, literal) {
+                    (ValueType::Str(value), ValueType::Str(literal)) => Some(value.cmp(literal)),
+                    (ValueType::Int(value), ValueType::Int(literal)) => Some(value.cmp(literal)),
+                    (ValueType::Float(value), ValueType::Float(literal)) => value.partial_cmp(literal),
+                    (ValueType::Bool(value), ValueType::Bool(literal)) => Some(value.cmp(literal)),
+                    (ValueType::Int64(value), ValueType::Int64(literal)) => Some(value.cmp(literal)),
+                    (ValueType::Float64(value), ValueType::Float64(literal)) => value.partial_cmp(literal),
+                    (ValueType::Decimal(value), ValueType::Decimal(literal)) => Some(value.cmp(literal)),
+                    (ValueType::Bytes(value), ValueType::Bytes(literal)) => Some(value.cmp(literal)),
+                    (ValueType::Timestamp(value), ValueType::Timestamp(literal)) => Some(value.cmp(literal)),
+                    _ => None
+                };
+                ordering.is_some_and(|ordering| operator.accepts(ordering))
+            }
+            (Predicate::Path { path, operator, literal }, ValueType::Json(document)) => {
+                let document: serde_json::Value = match serde_json::from_str(document) {
+                    Ok(document) => document,
+                    Err(_) => return false
+                };
+
+                match (path.get(&document).and_then(IndexValue::from_json), operator, literal) {
+                    (Some(IndexValue::Str(value)), ComparisonOperator::Contains, IndexValue::Str(literal)) => value.contains(literal.as_str()),
+                    (Some(value), operator, literal) if Self::same_type(&value, literal) => operator.accepts(value.cmp(literal)),
+                    _ => false
+                }
+            }
+            _ => false
+        }
+    }
+
+    fn same_type(a: &IndexValue, b: &IndexValue) -> bool {
+        std::mem::discriminant(a) == std::mem::discriminant(b)
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timestamp::Timestamp;
+
+    #[test]
+    fn test_value_predicate() {
+        let predicate = Predicate::Value { operator: ComparisonOperator::Gt, literal: ValueType::Int(10) };
+        assert!(predicate.matches(&ValueType::Int(11)));
+        assert!(!predicate.matches(&ValueType::Int(10)));
+        assert!(!predicate.matches(&ValueType::Float(11.0)));
+
+        let predicate = Predicate::Value { operator: ComparisonOperator::Contains, literal: ValueType::Str("err".to_string()) };
+        assert!(predicate.matches(&ValueType::Str("an error".to_string())));
+        assert!(!predicate.matches(&ValueType::Str("fine".to_string())));
+
+        let predicate = Predicate::Value { operator: ComparisonOperator::Ne, literal: ValueType::Bool(true) };
+        assert!(predicate.matches(&ValueType::Bool(false)));
+
+        let predicate = Predicate::Value { operator: ComparisonOperator::After, literal: ValueType::Timestamp(Timestamp::from_millis(1000).unwrap()) };
+        assert!(predicate.matches(&ValueType::Timestamp(Timestamp::from_millis(1001).unwrap())));
+        assert!(!predicate.matches(&ValueType::Timestamp(Timestamp::from_millis(1000).unwrap())));
+    }
+
+    #[test]
+    fn test_path_predicate() {
+        let predicate = Predicate::Path { path: JsonPath::parse("$.field").unwrap(), operator: ComparisonOperator::Eq, literal: IndexValue::Number(3.0) };
+        assert!(predicate.matches(&ValueType::Json("{\"field\":3}".to_string())));
+        assert!(predicate.matches(&ValueType::Json("{\"field\":3.0}".to_string())));
+        assert!(!predicate.matches(&ValueType::Json("{\"field\":\"3\"}".to_string())));
+        assert!(!predicate.matches(&ValueType::Json("{\"other\":3}".to_string())));
+
+        let predicate = Predicate::Path { path: JsonPath::parse("$.level").unwrap(), operator: ComparisonOperator::Le, literal: IndexValue::Str("m".to_string()) };
+        assert!(predicate.matches(&ValueType::Json("{\"level\":\"error\"}".to_string())));
+        assert!(!predicate.matches(&ValueType::Json("{\"level\":\"warn\"}".to_string())));
+        assert!(!predicate.matches(&ValueType::Json("{\"level\":1}".to_string())));
+
+        let predicate = Predicate::Path { path: JsonPath::parse("$.msg").unwrap(), operator: ComparisonOperator::Contains, literal: IndexValue::Str("err".to_string()) };
+        assert!(predicate.matches(&ValueType::Json("{\"msg\":\"an error\"}".to_string())));
+    }
+}
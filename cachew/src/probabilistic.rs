@@ -0,0 +1,219 @@
+use serde::{Serialize, Deserialize};
+
+
+/// The precision of a HyperLogLog created without an error rate (2^14 registers, ie. a standard error of 0.81%).
+pub const DEFAULT_PRECISION: u8 = 14;
+const MIN_PRECISION: u8 = 4;
+const MAX_PRECISION: u8 = 16;
+
+/// The error rate of a Bloom filter created by BF.ADD (without BF.RESERVE).
+pub const DEFAULT_BLOOM_ERROR_RATE: f64 = 0.01;
+/// The capacity of a Bloom filter created by BF.ADD (without BF.RESERVE).
+pub const DEFAULT_BLOOM_CAPACITY: u64 = 100;
+
+
+/// Hashes bytes with 64 bit FNV-1a and mixes the result with the finalizer of MurmurHash3, so that every bit depends
+/// on every input bit. Unlike `DefaultHasher` the hash is stable across Rust versions, so persisted structures stay valid.
+fn hash(bytes: &[u8], seed: u64) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325 ^ seed;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51afd7ed558ccd);
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xc4ceb9fe1a85ec53);
+    hash ^ (hash >> 33)
+}
+
+
+/// Estimates the amount of distinct elements with 2^precision registers of 6 bits (stored as bytes), independent of the amount of elements.
+/// The standard error of the estimate is 1.04 / sqrt(2^precision).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HyperLogLog {
+    precision: u8,
+    registers: Vec<u8>
+}
+
+impl HyperLogLog {
+
+    pub fn new(precision: u8) -> Self {
+        Self { precision, registers: vec![0; 1 << precision] }
+    }
+
+    /// Gets the smallest precision with a standard error of at most the error rate, clamped to 2^4 to 2^16 registers.
+    pub fn precision_for(error_rate: f64) -> u8 {
+        let precision: f64 = (2.0 * (1.04 / error_rate).log2()).ceil();
+        precision.clamp(MIN_PRECISION as f64, MAX_PRECISION as f64) as u8
+    }
+
+    /// Adds an element.
+    ///
+    /// # Returns:
+    /// True if a register changed, ie. the estimate might have changed.
+    pub fn add(&mut self, element: &[u8]) -> bool {
+        let hash: u64 = hash(element, 0);
+        let index: usize = (hash >> (64 - self.precision)) as usize;
+        // the position of the first set bit after the index bits, the marker bit bounds it if they're all zero
+        let rank: u8 = ((hash << self.precision) | (1 << (self.precision - 1))).leading_zeros() as u8 + 1;
+
+        if rank > self.registers[index] {
+            self.registers[index] = rank;
+            return true;
+        }
+        false
+    }
+
+    /// Merges another HyperLogLog into this one, afterwards it estimates the union of both.
+    ///
+    /// # Returns:
+    /// False if the precisions differ (nothing is merged then).
+    pub fn merge(&mut self, other: &HyperLogLog) -> bool {
+        if self.precision != other.precision {
+            return false;
+        }
+
+        for (register, other_register) in self.registers.iter_mut().zip(&other.registers) {
+            *register = (*register).max(*other_register);
+        }
+        true
+    }
+
+    pub fn count(&self) -> u64 {
+        let registers: f64 = self.registers.len() as f64;
+        let alpha: f64 = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / registers)
+        };
+
+        let sum: f64 = self.registers.iter().map(|register| 2f64.powi(-(*register as i32))).sum();
+        let estimate: f64 = alpha * registers * registers / sum;
+
+        // small cardinalities are estimated more accurately by counting the empty registers (linear counting)
+        let empty_registers: usize = self.registers.iter().filter(|register| **register == 0).count();
+        if estimate <= 2.5 * registers && empty_registers > 0 {
+            return (registers * (registers / empty_registers as f64).ln()).round() as u64;
+        }
+        estimate.round() as u64
+    }
+}
+
+
+/// Tests if an element was added, without storing the elements. False positives occur at about the error rate
+/// (as long as no more elements than the capacity are added), false negatives never occur.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BloomFilter {
+    bits: Vec<u64>,
+    bit_count: u64,
+    hash_count: u32
+}
+
+impl BloomFilter {
+
+    /// Creates a Bloom filter with the optimal amount of bits and hash functions for a capacity and an error rate.
+    pub fn new(error_rate: f64, capacity: u64) -> Self {
+        let bit_count: u64 = Self::bit_count(error_rate, capacity);
+        let hash_count: u32 = ((bit_count as f64 / capacity as f64) * std::f64::consts::LN_2).round().max(1.0) as u32;
+        Self { bits: vec![0; bit_count.div_ceil(64) as usize], bit_count, hash_count }
+    }
+
+    /// Gets the amount of bits a Bloom filter for a capacity and an error rate needs (without creating it).
+    pub fn bit_count(error_rate: f64, capacity: u64) -> u64 {
+        let bit_count: f64 = -(capacity as f64) * error_rate.ln() / (std::f64::consts::LN_2 * std::f64::consts::LN_2);
+        (bit_count.ceil() as u64).max(64)
+    }
+
+    /// Adds an element.
+    ///
+    /// # Returns:
+    /// True if the element wasn't in the filter yet (false if it was, or is a false positive).
+    pub fn add(&mut self, element: &[u8]) -> bool {
+        let mut added: bool = false;
+        for bit in self.bit_positions(element) {
+            let (word, mask) = (bit as usize / 64, 1 << (bit % 64));
+            added |= self.bits[word] & mask == 0;
+            self.bits[word] |= mask;
+        }
+        added
+    }
+
+    pub fn contains(&self, element: &[u8]) -> bool {
+        self.bit_positions(element).all(|bit| self.bits[bit as usize / 64] & (1 << (bit % 64)) != 0)
+    }
+
+    /// Gets the bits of an element by double hashing, ie. the i-th bit is ``hash1 + i * hash2``.
+    fn bit_positions(&self, element: &[u8]) -> impl Iterator<Item = u64> {
+        let (first_hash, second_hash) = (hash(element, 0), hash(element, 1) | 1);
+        let bit_count: u64 = self.bit_count;
+        (0..self.hash_count as u64).map(move |i| first_hash.wrapping_add(i.wrapping_mul(second_hash)) % bit_count)
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hyperloglog() {
+        let mut hyperloglog: HyperLogLog = HyperLogLog::new(DEFAULT_PRECISION);
+        assert_eq!(hyperloglog.count(), 0);
+
+        for element in 0..100_000u32 {
+            hyperloglog.add(&element.to_le_bytes());
+        }
+        // adding an element again never changes the estimate
+        assert!(!hyperloglog.add(&7u32.to_le_bytes()));
+
+        let count: f64 = hyperloglog.count() as f64;
+        assert!((count - 100_000.0).abs() < 100_000.0 * 0.03, "estimate {} is off", count);
+
+        let mut small: HyperLogLog = HyperLogLog::new(DEFAULT_PRECISION);
+        for element in 0..10u32 {
+            small.add(&element.to_le_bytes());
+        }
+        assert_eq!(small.count(), 10);
+    }
+
+    #[test]
+    fn test_hyperloglog_merge() {
+        let (mut first, mut second) = (HyperLogLog::new(12), HyperLogLog::new(12));
+        for element in 0..2000u32 {
+            first.add(&element.to_le_bytes());
+            second.add(&(element + 1000).to_le_bytes());
+        }
+
+        assert!(first.merge(&second));
+        let count: f64 = first.count() as f64;
+        assert!((count - 3000.0).abs() < 3000.0 * 0.05, "estimate {} is off", count);
+
+        assert!(!first.merge(&HyperLogLog::new(10)));
+    }
+
+    #[test]
+    fn test_precision_for() {
+        assert_eq!(HyperLogLog::precision_for(0.0081), 15);
+        assert_eq!(HyperLogLog::precision_for(0.01), 14);
+        assert_eq!(HyperLogLog::precision_for(0.5), 4);
+        assert_eq!(HyperLogLog::precision_for(0.0001), 16);
+    }
+
+    #[test]
+    fn test_bloom_filter() {
+        let mut bloom_filter: BloomFilter = BloomFilter::new(0.01, 1000);
+        for element in 0..1000u32 {
+            bloom_filter.add(&element.to_le_bytes());
+        }
+        assert!(!bloom_filter.add(&7u32.to_le_bytes()));
+
+        // no false negatives, about 1% false positives
+        assert!((0..1000u32).all(|element| bloom_filter.contains(&element.to_le_bytes())));
+        let false_positives: usize = (1000..11_000u32).filter(|element| bloom_filter.contains(&element.to_le_bytes())).count();
+        assert!(false_positives < 200, "{} false positives", false_positives);
+    }
+}
@@ -2,7 +2,8 @@ use serde::{Serialize, Deserialize};
 use std::fmt::{self, Write};
 
 
-use crate::{schemas::{ValueType, QueryResponseType, DatabaseType}, database};
+use crate::base64;
+use crate::{schemas::{ValueType, QueryResponseType, DatabaseType, KeyValuePair, ScoredMember, Sample, ThrottleResult, LockInfo, GeoPoint, AggregationResult}, database};
 
 
 pub struct QueryResponse { }
@@ -30,7 +31,112 @@ impl QueryResponse {
             ValueType::Int(value) => format!("{}", value),
             ValueType::Float(value) => format!("{}", value),
             ValueType::Bool(value) => format!("{}", value),
-            ValueType::Json(value) => format!("\"{}\"", value),
+            ValueType::Json(value) => format!("\"{}\"", value.replace('"', "\\\"")),
+            ValueType::Int64(value) => format!("{}", value),
+            ValueType::Float64(value) => format!("{}", value),
+            ValueType::Decimal(value) => format!("{}", value),
+            ValueType::Bytes(value) => format!("\"{}\"", base64::encode(value)),
+            ValueType::Timestamp(value) => format!("{}", value),
+        }
+    }
+
+    fn handle_key_value_pair(pair: &Option<KeyValuePair>) -> String {
+        match pair {
+            Some(pair) => format!("\"{}\" {}", pair.key, Self::handle_value_types(&pair.value)),
+            None => "NONE".to_string(),
+        }
+    }
+
+    /// Joins values with commata, an empty list is sent as "NONE".
+    fn handle_value_list(values: &[ValueType]) -> String {
+        match values.is_empty() {
+            true => "NONE".to_string(),
+            false => values.iter().map(Self::handle_value_types).collect::<Vec<String>>().join(",")
+        }
+    }
+
+    fn handle_optional_value(value: &Option<ValueType>) -> String {
+        match value {
+            Some(value) => Self::handle_value_types(value),
+            None => "NONE".to_string(),
+        }
+    }
+
+    /// Sends the members of a sorted set like key value pairs, ie. ``"alice" 12.5``, an empty list as "NONE".
+    fn handle_scored_members(members: &[ScoredMember]) -> String {
+        match members.is_empty() {
+            true => "NONE".to_string(),
+            false => members
+                .iter()
+                .map(|member| format!("{} {}", Self::handle_value_types(&member.member), member.score))
+                .collect::<Vec<String>>()
+                .join(",")
+        }
+    }
+
+    /// Sends the result of a THROTTLE request as ``allowed|denied <remaining tokens> <retry after ms>``, a retry after of "NONE" never succeeds.
+    fn handle_throttle_result(result: &ThrottleResult) -> String {
+        let retry_after: String = result.retry_after.map_or("NONE".to_string(), |retry_after| retry_after.to_string());
+        format!("{} {} {}", if result.allowed { "allowed" } else { "denied" }, result.remaining, retry_after)
+    }
+
+    /// Sends members like key value pairs, ie. ``"car1" 0.3572`` (distances are rounded to 4 decimals), an empty list as "NONE".
+    fn handle_geo_distances(members: &[(ValueType, f64)]) -> String {
+        match members.is_empty() {
+            true => "NONE".to_string(),
+            false => members
+                .iter()
+                .map(|(member, distance)| format!("{} {}", Self::handle_value_types(member), Self::round_distance(*distance)))
+                .collect::<Vec<String>>()
+                .join(",")
+        }
+    }
+
+    /// Sends members and their longitude and latitude, ie. ``"car1" 13.41 52.521``, an empty list as "NONE".
+    fn handle_geo_positions(members: &[(ValueType, GeoPoint)]) -> String {
+        match members.is_empty() {
+            true => "NONE".to_string(),
+            false => members
+                .iter()
+                .map(|(member, position)| format!("{} {} {}", Self::handle_value_types(member), position.lon, position.lat))
+                .collect::<Vec<String>>()
+                .join(",")
+        }
+    }
+
+    fn round_distance(distance: f64) -> f64 {
+        (distance * 10_000.0).round() / 10_000.0
+    }
+
+    fn handle_samples(samples: &[Sample]) -> String {
+        match samples.is_empty() {
+            true => "NONE".to_string(),
+            false => samples
+                .iter()
+                .map(|sample| format!("{} {}", sample.timestamp, Self::handle_value_types(&sample.value)))
+                .collect::<Vec<String>>()
+                .join(",")
+        }
+    }
+
+    fn handle_buckets(buckets: &[(i64, AggregationResult)]) -> String {
+        match buckets.is_empty() {
+            true => "NONE".to_string(),
+            false => buckets
+                .iter()
+                .map(|(timestamp, result)| format!("{} {}", timestamp, Self::handle_aggregation_result(result)))
+                .collect::<Vec<String>>()
+                .join(",")
+        }
+    }
+
+    fn handle_aggregation_result(result: &AggregationResult) -> String {
+        match result {
+            AggregationResult::Int(value) => format!("{}", value),
+            AggregationResult::Float(value) => format!("{}", value),
+            AggregationResult::Decimal(value) => format!("{}", value),
+            AggregationResult::Timestamp(value) => format!("{}", value),
+            AggregationResult::None => "NONE".to_string(),
         }
     }
 
@@ -39,6 +145,9 @@ impl QueryResponse {
             QueryResponseType::GET_OK(value) => {
                 Self::build_ok_response("GET".to_string(), Some(Self::handle_value_types(&value)), Some(database_type))
             },
+            QueryResponseType::GET_WAIT_OK(value) => {
+                Self::build_ok_response("GET WAIT".to_string(), Some(Self::handle_optional_value(&value)), Some(database_type))
+            },
             QueryResponseType::GET_RANGE_OK(values) => {
                 let mut content: String = String::new();
                 for (idx, value) in values.iter().enumerate() {
@@ -68,11 +177,22 @@ impl QueryResponse {
             QueryResponseType::DEL_OK => {
                 Self::build_ok_response("DEL".to_string(), None, None)
             },
-            QueryResponseType::DEL_RANGE_OK => {
-                Self::build_ok_response("DEL RANGE".to_string(), None, None)
+            QueryResponseType::GET_MANY_NULLS_OK(values) => {
+                let content: String = values
+                    .iter()
+                    .map(|value| match value {
+                        Some(value) => Self::handle_value_types(value),
+                        None => "NULL".to_string(),
+                    })
+                    .collect::<Vec<String>>()
+                    .join(",");
+                Self::build_ok_response("GET MANY".to_string(), Some(content), Some(database_type))
+            },
+            QueryResponseType::DEL_RANGE_OK(deleted) => {
+                Self::build_ok_response("DEL RANGE".to_string(), Some(deleted.to_string()), None)
             },
-            QueryResponseType::DEL_MANY_OK => {
-                Self::build_ok_response("DEL MANY".to_string(), None, None)
+            QueryResponseType::DEL_MANY_OK(deleted) => {
+                Self::build_ok_response("DEL MANY".to_string(), Some(deleted.to_string()), None)
             },
             QueryResponseType::SET_OK => {
                 Self::build_ok_response("SET".to_string(), None, None)
@@ -95,6 +215,208 @@ impl QueryResponse {
             QueryResponseType::EXISTS_OK(exists) => {
                 Self::build_ok_response("EXISTS".to_string(), Some(exists.to_string()), None)
             }
+            QueryResponseType::KEYS_OK(keys) => {
+                let mut content: String = keys.iter().map(|key| format!("\"{}\"", key)).collect::<Vec<String>>().join(",");
+                if content.is_empty() {
+                    content = "NONE".to_string();
+                }
+                Self::build_ok_response("KEYS".to_string(), Some(content), None)
+            }
+            QueryResponseType::FIRST_OK(pair) => {
+                Self::build_ok_response("FIRST".to_string(), Some(Self::handle_key_value_pair(&pair)), Some(database_type))
+            }
+            QueryResponseType::LAST_OK(pair) => {
+                Self::build_ok_response("LAST".to_string(), Some(Self::handle_key_value_pair(&pair)), Some(database_type))
+            }
+            QueryResponseType::NEXT_OK(pair) => {
+                Self::build_ok_response("NEXT".to_string(), Some(Self::handle_key_value_pair(&pair)), Some(database_type))
+            }
+            QueryResponseType::PREV_OK(pair) => {
+                Self::build_ok_response("PREV".to_string(), Some(Self::handle_key_value_pair(&pair)), Some(database_type))
+            }
+            QueryResponseType::FLOOR_OK(pair) => {
+                Self::build_ok_response("FLOOR".to_string(), Some(Self::handle_key_value_pair(&pair)), Some(database_type))
+            }
+            QueryResponseType::CEIL_OK(pair) => {
+                Self::build_ok_response("CEIL".to_string(), Some(Self::handle_key_value_pair(&pair)), Some(database_type))
+            }
+            QueryResponseType::AGG_OK(aggregation, result) => {
+                Self::build_ok_response(format!("AGG {}", aggregation), Some(Self::handle_aggregation_result(&result)), None)
+            }
+            QueryResponseType::RENAME_OK => {
+                Self::build_ok_response("RENAME".to_string(), None, None)
+            }
+            QueryResponseType::RENAME_PREFIX_OK(renamed) => {
+                Self::build_ok_response("RENAME PREFIX".to_string(), Some(renamed.to_string()), None)
+            }
+            QueryResponseType::COPY_OK => {
+                Self::build_ok_response("COPY".to_string(), None, None)
+            }
+            QueryResponseType::JSON_GET_OK(value) => {
+                Self::build_ok_response("JSON.GET".to_string(), Some(Self::handle_value_types(&ValueType::Json(value))), Some(database_type))
+            }
+            QueryResponseType::JSON_SET_OK => {
+                Self::build_ok_response("JSON.SET".to_string(), None, None)
+            }
+            QueryResponseType::JSON_DEL_OK => {
+                Self::build_ok_response("JSON.DEL".to_string(), None, None)
+            }
+            QueryResponseType::JSON_MERGE_OK => {
+                Self::build_ok_response("JSON.MERGE".to_string(), None, None)
+            }
+            QueryResponseType::SCHEMA_GET_OK(schema) => {
+                let content: String = match schema {
+                    Some(schema) => Self::handle_value_types(&ValueType::Json(schema)),
+                    None => "NONE".to_string(),
+                };
+                Self::build_ok_response("SCHEMA GET".to_string(), Some(content), None)
+            }
+            QueryResponseType::INDEX_CREATE_OK => {
+                Self::build_ok_response("INDEX CREATE".to_string(), None, None)
+            }
+            QueryResponseType::INDEX_DROP_OK => {
+                Self::build_ok_response("INDEX DROP".to_string(), None, None)
+            }
+            QueryResponseType::FIND_OK(pairs) => {
+                let mut content: String = pairs.into_iter().map(|pair| Self::handle_key_value_pair(&Some(pair))).collect::<Vec<String>>().join(",");
+                if content.is_empty() {
+                    content = "NONE".to_string();
+                }
+                Self::build_ok_response("FIND".to_string(), Some(content), Some(database_type))
+            }
+            QueryResponseType::STRLEN_OK(length) => {
+                Self::build_ok_response("STRLEN".to_string(), Some(length.to_string()), None)
+            }
+            QueryResponseType::LPUSH_OK(length) => {
+                Self::build_ok_response("LPUSH".to_string(), Some(length.to_string()), None)
+            }
+            QueryResponseType::RPUSH_OK(length) => {
+                Self::build_ok_response("RPUSH".to_string(), Some(length.to_string()), None)
+            }
+            QueryResponseType::LPOP_OK(value) => {
+                Self::build_ok_response("LPOP".to_string(), Some(Self::handle_optional_value(&value)), Some(database_type))
+            }
+            QueryResponseType::RPOP_OK(value) => {
+                Self::build_ok_response("RPOP".to_string(), Some(Self::handle_optional_value(&value)), Some(database_type))
+            }
+            QueryResponseType::BLPOP_OK(value) => {
+                Self::build_ok_response("BLPOP".to_string(), Some(Self::handle_optional_value(&value)), Some(database_type))
+            }
+            QueryResponseType::LRANGE_OK(values) => {
+                Self::build_ok_response("LRANGE".to_string(), Some(Self::handle_value_list(&values)), Some(database_type))
+            }
+            QueryResponseType::SADD_OK(added) => {
+                Self::build_ok_response("SADD".to_string(), Some(added.to_string()), None)
+            }
+            QueryResponseType::SREM_OK(removed) => {
+                Self::build_ok_response("SREM".to_string(), Some(removed.to_string()), None)
+            }
+            QueryResponseType::SMEMBERS_OK(members) => {
+                Self::build_ok_response("SMEMBERS".to_string(), Some(Self::handle_value_list(&members)), Some(database_type))
+            }
+            QueryResponseType::SISMEMBER_OK(is_member) => {
+                Self::build_ok_response("SISMEMBER".to_string(), Some(is_member.to_string()), None)
+            }
+            QueryResponseType::HSET_OK(added) => {
+                Self::build_ok_response("HSET".to_string(), Some(added.to_string()), None)
+            }
+            QueryResponseType::HGET_OK(value) => {
+                Self::build_ok_response("HGET".to_string(), Some(Self::handle_value_types(&value)), Some(database_type))
+            }
+            QueryResponseType::HDEL_OK(deleted) => {
+                Self::build_ok_response("HDEL".to_string(), Some(deleted.to_string()), None)
+            }
+            QueryResponseType::HGETALL_OK(fields) => {
+                let mut content: String = fields.into_iter().map(|field| Self::handle_key_value_pair(&Some(field))).collect::<Vec<String>>().join(",");
+                if content.is_empty() {
+                    content = "NONE".to_string();
+                }
+                Self::build_ok_response("HGETALL".to_string(), Some(content), Some(database_type))
+            }
+            QueryResponseType::ZADD_OK(added) => {
+                Self::build_ok_response("ZADD".to_string(), Some(added.to_string()), None)
+            }
+            QueryResponseType::ZINCRBY_OK(score) => {
+                Self::build_ok_response("ZINCRBY".to_string(), Some(score.to_string()), None)
+            }
+            QueryResponseType::ZRANGE_OK(members) => {
+                Self::build_ok_response("ZRANGE".to_string(), Some(Self::handle_scored_members(&members)), Some(database_type))
+            }
+            QueryResponseType::ZRANK_OK(rank) => {
+                let content: String = rank.map(|rank| rank.to_string()).unwrap_or("NONE".to_string());
+                Self::build_ok_response("ZRANK".to_string(), Some(content), None)
+            }
+            QueryResponseType::ZREM_OK(removed) => {
+                Self::build_ok_response("ZREM".to_string(), Some(removed.to_string()), None)
+            }
+            QueryResponseType::ZCOUNT_OK(count) => {
+                Self::build_ok_response("ZCOUNT".to_string(), Some(count.to_string()), None)
+            }
+            QueryResponseType::TS_ADD_OK(timestamp) => {
+                Self::build_ok_response("TS.ADD".to_string(), Some(timestamp.to_string()), None)
+            }
+            QueryResponseType::TS_RANGE_OK(samples) => {
+                Self::build_ok_response("TS.RANGE".to_string(), Some(Self::handle_samples(&samples)), Some(database_type))
+            }
+            QueryResponseType::TS_RANGE_AGG_OK(aggregation, buckets) => {
+                Self::build_ok_response(format!("TS.RANGE {}", aggregation), Some(Self::handle_buckets(&buckets)), None)
+            }
+            QueryResponseType::PFADD_OK(changed) => {
+                Self::build_ok_response("PFADD".to_string(), Some(changed.to_string()), None)
+            }
+            QueryResponseType::PFCOUNT_OK(count) => {
+                Self::build_ok_response("PFCOUNT".to_string(), Some(count.to_string()), None)
+            }
+            QueryResponseType::PFMERGE_OK => {
+                Self::build_ok_response("PFMERGE".to_string(), None, None)
+            }
+            QueryResponseType::BF_RESERVE_OK => {
+                Self::build_ok_response("BF.RESERVE".to_string(), None, None)
+            }
+            QueryResponseType::BF_ADD_OK(added) => {
+                Self::build_ok_response("BF.ADD".to_string(), Some(added.to_string()), None)
+            }
+            QueryResponseType::BF_EXISTS_OK(exists) => {
+                Self::build_ok_response("BF.EXISTS".to_string(), Some(exists.to_string()), None)
+            }
+            QueryResponseType::THROTTLE_OK(result) => {
+                Self::build_ok_response("THROTTLE".to_string(), Some(Self::handle_throttle_result(&result)), None)
+            }
+            QueryResponseType::LOCK_OK(token) => {
+                Self::build_ok_response("LOCK".to_string(), Some(token.map_or("NONE".to_string(), |token| token.to_string())), None)
+            }
+            QueryResponseType::UNLOCK_OK(released) => {
+                Self::build_ok_response("UNLOCK".to_string(), Some(released.to_string()), None)
+            }
+            QueryResponseType::RENEW_OK(renewed) => {
+                Self::build_ok_response("RENEW".to_string(), Some(renewed.to_string()), None)
+            }
+            QueryResponseType::LOCKINFO_OK(info) => {
+                let content: String = info.map_or("NONE".to_string(), |info| format!("\"{}\" {} {}", info.owner, info.token, info.ttl));
+                Self::build_ok_response("LOCKINFO".to_string(), Some(content), None)
+            }
+            QueryResponseType::SEQ_NEXT_OK(first_id) => {
+                Self::build_ok_response("SEQ.NEXT".to_string(), Some(first_id.to_string()), None)
+            }
+            QueryResponseType::SEQ_SET_OK => {
+                Self::build_ok_response("SEQ.SET".to_string(), None, None)
+            }
+            QueryResponseType::SEQ_GET_OK(last_id) => {
+                Self::build_ok_response("SEQ.GET".to_string(), Some(last_id.to_string()), None)
+            }
+            QueryResponseType::GEO_ADD_OK(added) => {
+                Self::build_ok_response("GEO.ADD".to_string(), Some(added.to_string()), None)
+            }
+            QueryResponseType::GEO_RADIUS_OK(members) => {
+                Self::build_ok_response("GEO.RADIUS".to_string(), Some(Self::handle_geo_distances(&members)), Some(database_type))
+            }
+            QueryResponseType::GEO_BOX_OK(members) => {
+                Self::build_ok_response("GEO.BOX".to_string(), Some(Self::handle_geo_positions(&members)), Some(database_type))
+            }
+            QueryResponseType::GEO_DIST_OK(distance) => {
+                let content: String = distance.map_or("NONE".to_string(), |distance| Self::round_distance(distance).to_string());
+                Self::build_ok_response("GEO.DIST".to_string(), Some(content), None)
+            }
             QueryResponseType::SHUTDOWN_OK => {
                 Self::build_ok_response("SHUTDOWN".to_string(), None, None)
             }
@@ -114,6 +436,9 @@ impl QueryResponse {
 
 #[cfg(test)]
 mod tests {
+    use crate::schemas::AggregationType;
+    use crate::decimal::Decimal;
+    use crate::timestamp::Timestamp;
     use super::*;
 
     #[test]
@@ -143,6 +468,60 @@ mod tests {
         assert_eq!(response, "CASP/OK/GET/FLOAT/0.01/\n")
     }
 
+    #[test]
+    fn test_get_wide_numbers() {
+        let response = QueryResponse::ok(
+            QueryResponseType::GET_OK(ValueType::Int64(-1697000000123)),
+            &DatabaseType::Int64
+        );
+        assert_eq!(response, "CASP/OK/GET/INT64/-1697000000123/\n");
+
+        let response = QueryResponse::ok(
+            QueryResponseType::GET_MANY_OK(vec![ValueType::Float64(0.1234567890123), ValueType::Float64(2.0)]),
+            &DatabaseType::Float64
+        );
+        assert_eq!(response, "CASP/OK/GET MANY/FLOAT64/0.1234567890123,2/\n");
+
+        let response = QueryResponse::ok(
+            QueryResponseType::GET_OK(ValueType::Decimal(Decimal::parse("10.50").unwrap())),
+            &DatabaseType::Decimal
+        );
+        assert_eq!(response, "CASP/OK/GET/DECIMAL/10.50/\n");
+
+        let response = QueryResponse::ok(
+            QueryResponseType::AGG_OK(AggregationType::Sum, AggregationResult::Decimal(Decimal::parse("0.30").unwrap())),
+            &DatabaseType::Decimal
+        );
+        assert_eq!(response, "CASP/OK/AGG SUM/0.30/\n")
+    }
+
+    #[test]
+    fn test_get_bytes() {
+        let response = QueryResponse::ok(
+            QueryResponseType::GET_OK(ValueType::Bytes(vec![0, 255, 254, 62, 63])),
+            &DatabaseType::Bytes
+        );
+        assert_eq!(response, "CASP/OK/GET/BYTES/\"AP/+Pj8=\"/\n");
+
+        let response = QueryResponse::ok(QueryResponseType::STRLEN_OK(5), &DatabaseType::Bytes);
+        assert_eq!(response, "CASP/OK/STRLEN/5/\n")
+    }
+
+    #[test]
+    fn test_get_timestamps() {
+        let response = QueryResponse::ok(
+            QueryResponseType::GET_RANGE_OK(vec![ValueType::Timestamp(Timestamp::from_millis(1697000800123).unwrap()), ValueType::Timestamp(Timestamp::from_millis(0).unwrap())]),
+            &DatabaseType::Timestamp
+        );
+        assert_eq!(response, "CASP/OK/GET RANGE/TIMESTAMP/2023-10-11T05:06:40.123Z,1970-01-01T00:00:00.000Z/\n");
+
+        let response = QueryResponse::ok(
+            QueryResponseType::AGG_OK(AggregationType::Max, AggregationResult::Timestamp(Timestamp::from_millis(1697000800123).unwrap())),
+            &DatabaseType::Timestamp
+        );
+        assert_eq!(response, "CASP/OK/AGG MAX/2023-10-11T05:06:40.123Z/\n")
+    }
+
     #[test]
     fn test_get_range_string() {
         let response = QueryResponse::ok(
@@ -176,6 +555,15 @@ mod tests {
         assert_eq!(response, "CASP/OK/GET MANY/STR/\"value1\",\"value2\"/\n")
     }
 
+    #[test]
+    fn test_get_many_nulls() {
+        let response = QueryResponse::ok(
+            QueryResponseType::GET_MANY_NULLS_OK(vec![Some(ValueType::Int(1)), None, Some(ValueType::Int(3))]),
+            &DatabaseType::Int
+        );
+        assert_eq!(response, "CASP/OK/GET MANY/INT/1,NULL,3/\n")
+    }
+
     #[test]
     fn test_del() {
         let response = QueryResponse::ok(
@@ -188,19 +576,19 @@ mod tests {
     #[test]
     fn test_del_range() {
         let response = QueryResponse::ok(
-            QueryResponseType::DEL_RANGE_OK,
+            QueryResponseType::DEL_RANGE_OK(3),
             &DatabaseType::Str
         );
-        assert_eq!(response, "CASP/OK/DEL RANGE/\n")
+        assert_eq!(response, "CASP/OK/DEL RANGE/3/\n")
     }
 
     #[test]
     fn test_del_many() {
         let response = QueryResponse::ok(
-            QueryResponseType::DEL_MANY_OK,
+            QueryResponseType::DEL_MANY_OK(0),
             &DatabaseType::Str
         );
-        assert_eq!(response, "CASP/OK/DEL MANY/\n")
+        assert_eq!(response, "CASP/OK/DEL MANY/0/\n")
     }
 
     #[test]
@@ -272,6 +660,298 @@ mod tests {
         assert_eq!(response, "CASP/OK/EXISTS/false/\n")
     }
 
+    #[test]
+    fn test_keys() {
+        let response = QueryResponse::ok(
+            QueryResponseType::KEYS_OK(vec!["key1".to_string(), "key 2".to_string()]),
+            &DatabaseType::Str
+        );
+        assert_eq!(response, "CASP/OK/KEYS/\"key1\",\"key 2\"/\n");
+
+        let response = QueryResponse::ok(
+            QueryResponseType::KEYS_OK(vec![]),
+            &DatabaseType::Str
+        );
+        assert_eq!(response, "CASP/OK/KEYS/NONE/\n")
+    }
+
+    #[test]
+    fn test_navigation() {
+        let response = QueryResponse::ok(
+            QueryResponseType::NEXT_OK(Some(KeyValuePair { key: "key 1".to_string(), value: ValueType::Int(5) })),
+            &DatabaseType::Int
+        );
+        assert_eq!(response, "CASP/OK/NEXT/INT/\"key 1\" 5/\n");
+
+        let response = QueryResponse::ok(
+            QueryResponseType::FIRST_OK(Some(KeyValuePair { key: "key".to_string(), value: ValueType::Str("value".to_string()) })),
+            &DatabaseType::Str
+        );
+        assert_eq!(response, "CASP/OK/FIRST/STR/\"key\" \"value\"/\n");
+
+        let response = QueryResponse::ok(
+            QueryResponseType::CEIL_OK(None),
+            &DatabaseType::Float
+        );
+        assert_eq!(response, "CASP/OK/CEIL/FLOAT/NONE/\n")
+    }
+
+    #[test]
+    fn test_agg() {
+        let response = QueryResponse::ok(
+            QueryResponseType::AGG_OK(AggregationType::Sum, AggregationResult::Int(4294967294)),
+            &DatabaseType::Int
+        );
+        assert_eq!(response, "CASP/OK/AGG SUM/4294967294/\n");
+
+        let response = QueryResponse::ok(
+            QueryResponseType::AGG_OK(AggregationType::Avg, AggregationResult::Float(0.75)),
+            &DatabaseType::Float
+        );
+        assert_eq!(response, "CASP/OK/AGG AVG/0.75/\n");
+
+        let response = QueryResponse::ok(
+            QueryResponseType::AGG_OK(AggregationType::Max, AggregationResult::None),
+            &DatabaseType::Float
+        );
+        assert_eq!(response, "CASP/OK/AGG MAX/NONE/\n")
+    }
+
+    #[test]
+    fn test_rename() {
+        let response = QueryResponse::ok(
+            QueryResponseType::RENAME_OK,
+            &DatabaseType::Str
+        );
+        assert_eq!(response, "CASP/OK/RENAME/\n");
+
+        let response = QueryResponse::ok(
+            QueryResponseType::RENAME_PREFIX_OK(3),
+            &DatabaseType::Str
+        );
+        assert_eq!(response, "CASP/OK/RENAME PREFIX/3/\n")
+    }
+
+    #[test]
+    fn test_copy() {
+        let response = QueryResponse::ok(
+            QueryResponseType::COPY_OK,
+            &DatabaseType::Str
+        );
+        assert_eq!(response, "CASP/OK/COPY/\n")
+    }
+
+    #[test]
+    fn test_get_json() {
+        let response = QueryResponse::ok(
+            QueryResponseType::GET_OK(ValueType::Json("{\"key\":[1,2]}".to_string())),
+            &DatabaseType::Json
+        );
+        assert_eq!(response, "CASP/OK/GET/JSON/\"{\\\"key\\\":[1,2]}\"/\n");
+
+        // quotes are escaped once more, like the parser expects them
+        let response = QueryResponse::ok(
+            QueryResponseType::GET_OK(ValueType::Json("{\"msg\":\"say \\\"hi\\\"\"}".to_string())),
+            &DatabaseType::Json
+        );
+        assert_eq!(response, "CASP/OK/GET/JSON/\"{\\\"msg\\\":\\\"say \\\\\"hi\\\\\"\\\"}\"/\n");
+
+        let response = QueryResponse::ok(
+            QueryResponseType::JSON_GET_OK("\"value\"".to_string()),
+            &DatabaseType::Json
+        );
+        assert_eq!(response, "CASP/OK/JSON.GET/JSON/\"\\\"value\\\"\"/\n");
+
+        let response = QueryResponse::ok(
+            QueryResponseType::JSON_MERGE_OK,
+            &DatabaseType::Json
+        );
+        assert_eq!(response, "CASP/OK/JSON.MERGE/\n")
+    }
+
+    #[test]
+    fn test_schema_get() {
+        let response = QueryResponse::ok(
+            QueryResponseType::SCHEMA_GET_OK(Some("{\"type\":\"object\"}".to_string())),
+            &DatabaseType::Json
+        );
+        assert_eq!(response, "CASP/OK/SCHEMA GET/\"{\\\"type\\\":\\\"object\\\"}\"/\n");
+
+        let response = QueryResponse::ok(
+            QueryResponseType::SCHEMA_GET_OK(None),
+            &DatabaseType::Json
+        );
+        assert_eq!(response, "CASP/OK/SCHEMA GET/NONE/\n")
+    }
+
+    #[test]
+    fn test_find() {
+        let response = QueryResponse::ok(
+            QueryResponseType::FIND_OK(vec![
+                KeyValuePair { key: "job1".to_string(), value: ValueType::Json("{\"status\":\"failed\"}".to_string()) },
+                KeyValuePair { key: "job3".to_string(), value: ValueType::Json("{\"status\":\"failed\"}".to_string()) },
+            ]),
+            &DatabaseType::Json
+        );
+        assert_eq!(response, "CASP/OK/FIND/JSON/\"job1\" \"{\\\"status\\\":\\\"failed\\\"}\",\"job3\" \"{\\\"status\\\":\\\"failed\\\"}\"/\n");
+
+        let response = QueryResponse::ok(QueryResponseType::FIND_OK(vec![]), &DatabaseType::Json);
+        assert_eq!(response, "CASP/OK/FIND/JSON/NONE/\n");
+
+        let response = QueryResponse::ok(QueryResponseType::INDEX_CREATE_OK, &DatabaseType::Json);
+        assert_eq!(response, "CASP/OK/INDEX CREATE/\n")
+    }
+
+    #[test]
+    fn test_probabilistic() {
+        let response = QueryResponse::ok(QueryResponseType::PFCOUNT_OK(1498), &DatabaseType::Int);
+        assert_eq!(response, "CASP/OK/PFCOUNT/1498/\n");
+
+        let response = QueryResponse::ok(QueryResponseType::PFMERGE_OK, &DatabaseType::Int);
+        assert_eq!(response, "CASP/OK/PFMERGE/\n");
+
+        let response = QueryResponse::ok(QueryResponseType::BF_EXISTS_OK(false), &DatabaseType::Int);
+        assert_eq!(response, "CASP/OK/BF.EXISTS/false/\n");
+    }
+
+    #[test]
+    fn test_throttle() {
+        let result: ThrottleResult = ThrottleResult { allowed: true, remaining: 9, retry_after: Some(0) };
+        let response = QueryResponse::ok(QueryResponseType::THROTTLE_OK(result), &DatabaseType::Int);
+        assert_eq!(response, "CASP/OK/THROTTLE/allowed 9 0/\n");
+
+        let result: ThrottleResult = ThrottleResult { allowed: false, remaining: 0, retry_after: Some(600) };
+        let response = QueryResponse::ok(QueryResponseType::THROTTLE_OK(result), &DatabaseType::Int);
+        assert_eq!(response, "CASP/OK/THROTTLE/denied 0 600/\n");
+
+        let result: ThrottleResult = ThrottleResult { allowed: false, remaining: 10, retry_after: None };
+        let response = QueryResponse::ok(QueryResponseType::THROTTLE_OK(result), &DatabaseType::Int);
+        assert_eq!(response, "CASP/OK/THROTTLE/denied 10 NONE/\n");
+    }
+
+    #[test]
+    fn test_locks() {
+        let response = QueryResponse::ok(QueryResponseType::LOCK_OK(Some(17)), &DatabaseType::Int);
+        assert_eq!(response, "CASP/OK/LOCK/17/\n");
+
+        let response = QueryResponse::ok(QueryResponseType::LOCK_OK(None), &DatabaseType::Int);
+        assert_eq!(response, "CASP/OK/LOCK/NONE/\n");
+
+        let response = QueryResponse::ok(QueryResponseType::UNLOCK_OK(true), &DatabaseType::Int);
+        assert_eq!(response, "CASP/OK/UNLOCK/true/\n");
+
+        let info: LockInfo = LockInfo { owner: "worker-1".to_string(), token: 17, ttl: 29500 };
+        let response = QueryResponse::ok(QueryResponseType::LOCKINFO_OK(Some(info)), &DatabaseType::Int);
+        assert_eq!(response, "CASP/OK/LOCKINFO/\"worker-1\" 17 29500/\n");
+    }
+
+    #[test]
+    fn test_sequences() {
+        let response = QueryResponse::ok(QueryResponseType::SEQ_NEXT_OK(18446744073709551615), &DatabaseType::Str);
+        assert_eq!(response, "CASP/OK/SEQ.NEXT/18446744073709551615/\n");
+
+        let response = QueryResponse::ok(QueryResponseType::SEQ_SET_OK, &DatabaseType::Str);
+        assert_eq!(response, "CASP/OK/SEQ.SET/\n");
+    }
+
+    #[test]
+    fn test_geo_sets() {
+        let members: Vec<(ValueType, f64)> = vec![(ValueType::Str("car2".to_string()), 0.068434), (ValueType::Str("car1".to_string()), 0.357249)];
+        let response = QueryResponse::ok(QueryResponseType::GEO_RADIUS_OK(members), &DatabaseType::Str);
+        assert_eq!(response, "CASP/OK/GEO.RADIUS/STR/\"car2\" 0.0684,\"car1\" 0.3572/\n");
+
+        let response = QueryResponse::ok(QueryResponseType::GEO_RADIUS_OK(vec![]), &DatabaseType::Str);
+        assert_eq!(response, "CASP/OK/GEO.RADIUS/STR/NONE/\n");
+
+        let members: Vec<(ValueType, GeoPoint)> = vec![(ValueType::Int(7), GeoPoint { lon: 13.41, lat: 52.521 })];
+        let response = QueryResponse::ok(QueryResponseType::GEO_BOX_OK(members), &DatabaseType::Int);
+        assert_eq!(response, "CASP/OK/GEO.BOX/INT/7 13.41 52.521/\n");
+
+        let response = QueryResponse::ok(QueryResponseType::GEO_DIST_OK(Some(504.2561849)), &DatabaseType::Str);
+        assert_eq!(response, "CASP/OK/GEO.DIST/504.2562/\n");
+    }
+
+    #[test]
+    fn test_time_series() {
+        let response = QueryResponse::ok(QueryResponseType::TS_ADD_OK(1697000800123), &DatabaseType::Float);
+        assert_eq!(response, "CASP/OK/TS.ADD/1697000800123/\n");
+
+        let response = QueryResponse::ok(QueryResponseType::TS_RANGE_OK(vec![
+            Sample { timestamp: 1000, value: ValueType::Float(21.5) },
+            Sample { timestamp: 2000, value: ValueType::Float(22.0) }
+        ]), &DatabaseType::Float);
+        assert_eq!(response, "CASP/OK/TS.RANGE/FLOAT/1000 21.5,2000 22/\n");
+
+        let response = QueryResponse::ok(QueryResponseType::TS_RANGE_AGG_OK(AggregationType::Avg, vec![(0, AggregationResult::Float(21.75))]), &DatabaseType::Float);
+        assert_eq!(response, "CASP/OK/TS.RANGE AVG/0 21.75/\n");
+
+        let response = QueryResponse::ok(QueryResponseType::TS_RANGE_OK(vec![]), &DatabaseType::Float);
+        assert_eq!(response, "CASP/OK/TS.RANGE/FLOAT/NONE/\n");
+    }
+
+    #[test]
+    fn test_collections() {
+        let response = QueryResponse::ok(QueryResponseType::LPUSH_OK(3), &DatabaseType::Int);
+        assert_eq!(response, "CASP/OK/LPUSH/3/\n");
+
+        let response = QueryResponse::ok(QueryResponseType::LPOP_OK(Some(ValueType::Int(7))), &DatabaseType::Int);
+        assert_eq!(response, "CASP/OK/LPOP/INT/7/\n");
+
+        let response = QueryResponse::ok(QueryResponseType::RPOP_OK(None), &DatabaseType::Int);
+        assert_eq!(response, "CASP/OK/RPOP/INT/NONE/\n");
+
+        let response = QueryResponse::ok(QueryResponseType::BLPOP_OK(Some(ValueType::Int(7))), &DatabaseType::Int);
+        assert_eq!(response, "CASP/OK/BLPOP/INT/7/\n");
+
+        let response = QueryResponse::ok(QueryResponseType::GET_WAIT_OK(None), &DatabaseType::Int);
+        assert_eq!(response, "CASP/OK/GET WAIT/INT/NONE/\n");
+
+        let response = QueryResponse::ok(QueryResponseType::LRANGE_OK(vec![ValueType::Int(1), ValueType::Int(2)]), &DatabaseType::Int);
+        assert_eq!(response, "CASP/OK/LRANGE/INT/1,2/\n");
+
+        let response = QueryResponse::ok(QueryResponseType::SMEMBERS_OK(vec![]), &DatabaseType::Str);
+        assert_eq!(response, "CASP/OK/SMEMBERS/STR/NONE/\n");
+
+        let response = QueryResponse::ok(QueryResponseType::SISMEMBER_OK(true), &DatabaseType::Str);
+        assert_eq!(response, "CASP/OK/SISMEMBER/true/\n");
+
+        let response = QueryResponse::ok(QueryResponseType::HGET_OK(ValueType::Str("x".to_string())), &DatabaseType::Str);
+        assert_eq!(response, "CASP/OK/HGET/STR/\"x\"/\n");
+
+        let response = QueryResponse::ok(
+            QueryResponseType::HGETALL_OK(vec![
+                KeyValuePair { key: "name".to_string(), value: ValueType::Str("x".to_string()) },
+                KeyValuePair { key: "tag".to_string(), value: ValueType::Str("y".to_string()) }
+            ]),
+            &DatabaseType::Str
+        );
+        assert_eq!(response, "CASP/OK/HGETALL/STR/\"name\" \"x\",\"tag\" \"y\"/\n");
+    }
+
+    #[test]
+    fn test_sorted_sets() {
+        let response = QueryResponse::ok(QueryResponseType::ZINCRBY_OK(12.5), &DatabaseType::Str);
+        assert_eq!(response, "CASP/OK/ZINCRBY/12.5/\n");
+
+        let response = QueryResponse::ok(
+            QueryResponseType::ZRANGE_OK(vec![
+                ScoredMember { member: ValueType::Str("alice".to_string()), score: 12.5 },
+                ScoredMember { member: ValueType::Str("bob".to_string()), score: f64::INFINITY }
+            ]),
+            &DatabaseType::Str
+        );
+        assert_eq!(response, "CASP/OK/ZRANGE/STR/\"alice\" 12.5,\"bob\" inf/\n");
+
+        let response = QueryResponse::ok(QueryResponseType::ZRANGE_OK(vec![]), &DatabaseType::Int);
+        assert_eq!(response, "CASP/OK/ZRANGE/INT/NONE/\n");
+
+        let response = QueryResponse::ok(QueryResponseType::ZRANK_OK(Some(0)), &DatabaseType::Int);
+        assert_eq!(response, "CASP/OK/ZRANK/0/\n");
+
+        let response = QueryResponse::ok(QueryResponseType::ZRANK_OK(None), &DatabaseType::Int);
+        assert_eq!(response, "CASP/OK/ZRANK/NONE/\n");
+    }
+
     #[test]
     fn test_error() {
         let response = QueryResponse::error("This is an error message.");
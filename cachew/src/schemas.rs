@@ -1,7 +1,14 @@
 use std::{str::FromStr, fmt};
+use std::time::Duration;
 
 use serde::{Serialize, Deserialize};
 
+use crate::json::JsonPath;
+use crate::index::IndexValue;
+use crate::predicate::Predicate;
+use crate::decimal::Decimal;
+use crate::timestamp::Timestamp;
+
 
 
 #[derive(Debug, PartialEq)]
@@ -11,13 +18,69 @@ pub struct KeyValuePair {
 }
 
 
+/// A sample of a time series, the timestamp is in epoch milliseconds.
+#[derive(Debug, PartialEq)]
+pub struct Sample {
+    pub timestamp: i64,
+    pub value: ValueType
+}
+
+/// Aggregates the samples of a TS.RANGE query per bucket of milliseconds.
+#[derive(Debug, PartialEq)]
+pub struct Downsampling {
+    pub aggregation: AggregationType,
+    pub bucket: i64
+}
+
+/// The limits of a THROTTLE request: a token bucket holding up to max burst tokens, refilled with rate tokens per period (in milliseconds).
+#[derive(Debug, PartialEq)]
+pub struct ThrottleLimit {
+    pub max_burst: u64,
+    pub rate: u64,
+    pub period: i64
+}
+
+/// The result of a THROTTLE request, the retry after (in milliseconds) is 0 if it was allowed and `None` if it never will be.
+#[derive(Debug, PartialEq)]
+pub struct ThrottleResult {
+    pub allowed: bool,
+    pub remaining: u64,
+    pub retry_after: Option<u64>
+}
+
+/// The holder of a lock, its fencing token and the milliseconds until it expires.
+#[derive(Debug, PartialEq)]
+pub struct LockInfo {
+    pub owner: String,
+    pub token: u64,
+    pub ttl: i64
+}
+
+/// A position on earth in degrees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoPoint {
+    pub lon: f64,
+    pub lat: f64
+}
+
+/// A member of a sorted set and its score.
+#[derive(Debug, PartialEq)]
+pub struct ScoredMember {
+    pub member: ValueType,
+    pub score: f64
+}
+
+
 #[derive(Debug, PartialEq)]
 pub enum QueryRequest<'a> {
     GET(String),
+    GET_WAIT { key: String, timeout: Option<Duration> },
     SET(KeyValuePair),
+    SET_EPHEMERAL(KeyValuePair),
     SET_MANY(Vec<KeyValuePair>),
-    GET_RANGE { key_lower: String, key_upper: String},
+    GET_RANGE { key_lower: String, key_upper: String, predicates: Vec<Predicate> },
     GET_MANY(Vec<&'a str>),
+    GET_MANY_NULLS(Vec<&'a str>),
     DEL(String),
     DEL_RANGE { key_lower: String, key_upper: String},
     DEL_MANY(Vec<&'a str>),
@@ -26,6 +89,68 @@ pub enum QueryRequest<'a> {
     LEN,
     PING,
     EXISTS(String),
+    KEYS { pattern: String, prefix: String, count: usize },
+    FIRST,
+    LAST,
+    NEXT(String),
+    PREV(String),
+    FLOOR(String),
+    CEIL(String),
+    AGG { aggregation: AggregationType, key_lower: String, key_upper: String },
+    RENAME { key: String, new_key: String, nx: bool },
+    RENAME_PREFIX { prefix: String, new_prefix: String },
+    COPY { key: String, new_key: String, replace: bool },
+    JSON_GET { key: String, path: JsonPath },
+    JSON_SET { key: String, path: JsonPath, value: String },
+    JSON_DEL { key: String, path: JsonPath },
+    JSON_MERGE { key: String, patch: String },
+    SCHEMA_GET,
+    STRLEN(String),
+    INDEX_CREATE { name: String, path: JsonPath },
+    INDEX_DROP(String),
+    FIND { name: String, lower: IndexValue, upper: IndexValue },
+    FIND_VALUE { lower: ValueType, upper: Option<ValueType> },
+    LPUSH { key: String, values: Vec<ValueType> },
+    RPUSH { key: String, values: Vec<ValueType> },
+    LPOP(String),
+    RPOP(String),
+    BLPOP { key: String, timeout: Option<Duration> },
+    LRANGE { key: String, start: i64, stop: i64 },
+    SADD { key: String, members: Vec<ValueType> },
+    SREM { key: String, members: Vec<ValueType> },
+    SMEMBERS(String),
+    SISMEMBER { key: String, member: ValueType },
+    HSET { key: String, fields: Vec<KeyValuePair> },
+    HGET { key: String, field: String },
+    HDEL { key: String, fields: Vec<String> },
+    HGETALL(String),
+    ZADD { key: String, members: Vec<ScoredMember> },
+    ZINCRBY { key: String, increment: f64, member: ValueType },
+    ZRANGE { key: String, start: i64, stop: i64, reverse: bool },
+    ZRANGE_BYSCORE { key: String, min: f64, max: f64 },
+    ZRANK { key: String, member: ValueType, reverse: bool },
+    ZREM { key: String, members: Vec<ValueType> },
+    ZCOUNT { key: String, min: f64, max: f64 },
+    TS_ADD { key: String, timestamp: Option<i64>, value: ValueType, retention: Option<i64> },
+    TS_RANGE { key: String, from: i64, to: i64, downsampling: Option<Downsampling> },
+    PFADD { key: String, error_rate: Option<f64>, values: Vec<ValueType> },
+    PFCOUNT(Vec<&'a str>),
+    PFMERGE { destination: String, sources: Vec<&'a str> },
+    BF_RESERVE { key: String, error_rate: f64, capacity: u64 },
+    BF_ADD { key: String, value: ValueType },
+    BF_EXISTS { key: String, value: ValueType },
+    THROTTLE { key: String, limit: ThrottleLimit, cost: u64 },
+    LOCK { name: String, owner: String, ttl: i64 },
+    UNLOCK { name: String, owner: String },
+    RENEW { name: String, owner: String, ttl: i64 },
+    LOCKINFO(String),
+    SEQ_NEXT { name: String, count: u64 },
+    SEQ_SET { name: String, last_id: u64 },
+    SEQ_GET(String),
+    GEO_ADD { key: String, member: ValueType, position: GeoPoint },
+    GEO_RADIUS { key: String, center: GeoPoint, radius: f64, unit: GeoUnit, count: Option<usize> },
+    GEO_BOX { key: String, min: GeoPoint, max: GeoPoint, count: Option<usize> },
+    GEO_DIST { key: String, from: ValueType, to: ValueType, unit: GeoUnit },
     SHUTDOWN
 }
 
@@ -36,18 +161,25 @@ pub enum ValueType {
     Int(i32),
     Float(f32),
     Bool(bool),
-    Json(String)
+    Json(String),
+    Int64(i64),
+    Float64(f64),
+    Decimal(Decimal),
+    Bytes(Vec<u8>),
+    Timestamp(Timestamp)
 }
 
 
 #[derive(Debug, PartialEq)]
 pub enum QueryResponseType {
     GET_OK(ValueType),
+    GET_WAIT_OK(Option<ValueType>),
     GET_RANGE_OK(Vec<ValueType>),
     GET_MANY_OK(Vec<ValueType>),
+    GET_MANY_NULLS_OK(Vec<Option<ValueType>>),
     DEL_OK,
-    DEL_RANGE_OK,
-    DEL_MANY_OK,
+    DEL_RANGE_OK(usize),
+    DEL_MANY_OK(usize),
     SET_OK,
     SET_MANY_OK,
     AUTH_OK,
@@ -55,17 +187,158 @@ pub enum QueryResponseType {
     LEN_OK(usize),
     PING_OK,
     EXISTS_OK(bool),
+    KEYS_OK(Vec<String>),
+    FIRST_OK(Option<KeyValuePair>),
+    LAST_OK(Option<KeyValuePair>),
+    NEXT_OK(Option<KeyValuePair>),
+    PREV_OK(Option<KeyValuePair>),
+    FLOOR_OK(Option<KeyValuePair>),
+    CEIL_OK(Option<KeyValuePair>),
+    AGG_OK(AggregationType, AggregationResult),
+    RENAME_OK,
+    RENAME_PREFIX_OK(usize),
+    COPY_OK,
+    JSON_GET_OK(String),
+    JSON_SET_OK,
+    JSON_DEL_OK,
+    JSON_MERGE_OK,
+    SCHEMA_GET_OK(Option<String>),
+    STRLEN_OK(usize),
+    INDEX_CREATE_OK,
+    INDEX_DROP_OK,
+    FIND_OK(Vec<KeyValuePair>),
+    LPUSH_OK(usize),
+    RPUSH_OK(usize),
+    LPOP_OK(Option<ValueType>),
+    RPOP_OK(Option<ValueType>),
+    BLPOP_OK(Option<ValueType>),
+    LRANGE_OK(Vec<ValueType>),
+    SADD_OK(usize),
+    SREM_OK(usize),
+    SMEMBERS_OK(Vec<ValueType>),
+    SISMEMBER_OK(bool),
+    HSET_OK(usize),
+    HGET_OK(ValueType),
+    HDEL_OK(usize),
+    HGETALL_OK(Vec<KeyValuePair>),
+    ZADD_OK(usize),
+    ZINCRBY_OK(f64),
+    ZRANGE_OK(Vec<ScoredMember>),
+    ZRANK_OK(Option<usize>),
+    ZREM_OK(usize),
+    ZCOUNT_OK(usize),
+    TS_ADD_OK(i64),
+    TS_RANGE_OK(Vec<Sample>),
+    TS_RANGE_AGG_OK(AggregationType, Vec<(i64, AggregationResult)>),
+    PFADD_OK(bool),
+    PFCOUNT_OK(u64),
+    PFMERGE_OK,
+    BF_RESERVE_OK,
+    BF_ADD_OK(bool),
+    BF_EXISTS_OK(bool),
+    THROTTLE_OK(ThrottleResult),
+    LOCK_OK(Option<u64>),
+    UNLOCK_OK(bool),
+    RENEW_OK(bool),
+    LOCKINFO_OK(Option<LockInfo>),
+    SEQ_NEXT_OK(u64),
+    SEQ_SET_OK,
+    SEQ_GET_OK(u64),
+    GEO_ADD_OK(bool),
+    GEO_RADIUS_OK(Vec<(ValueType, f64)>),
+    GEO_BOX_OK(Vec<(ValueType, GeoPoint)>),
+    GEO_DIST_OK(Option<f64>),
     SHUTDOWN_OK
 }
 
 
+/// What a blocking request (BLPOP, GET WAIT) is attempted after.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum WaitEvent {
+    /// The request was just received.
+    Start,
+    /// The key of the request was written to or deleted.
+    KeyChanged,
+    /// The timeout of the request elapsed.
+    Timeout
+}
+
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum AggregationType {
+    Sum,
+    Avg,
+    Min,
+    Max,
+    Count
+}
+
+impl fmt::Display for AggregationType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AggregationType::Sum => write!(f, "SUM"),
+            AggregationType::Avg => write!(f, "AVG"),
+            AggregationType::Min => write!(f, "MIN"),
+            AggregationType::Max => write!(f, "MAX"),
+            AggregationType::Count => write!(f, "COUNT"),
+        }
+    }
+}
+
+
+/// The unit of the distances of GEO.RADIUS and GEO.DIST.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum GeoUnit {
+    Meters,
+    Kilometers,
+    Miles,
+    Feet
+}
+
+impl GeoUnit {
+
+    /// Gets the meters of one unit.
+    pub fn meters(&self) -> f64 {
+        match self {
+            GeoUnit::Meters => 1.0,
+            GeoUnit::Kilometers => 1000.0,
+            GeoUnit::Miles => 1609.344,
+            GeoUnit::Feet => 0.3048,
+        }
+    }
+}
+
+
+#[derive(Debug, PartialEq)]
+pub enum AggregationResult {
+    Int(i128),
+    Float(f64),
+    Decimal(Decimal),
+    Timestamp(Timestamp),
+    None
+}
+
+
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub enum DatabaseType {
     Str,
     Int,
     Float,
     Bool,
-    Json
+    Json,
+    Int64,
+    Float64,
+    Decimal,
+    Bytes,
+    Timestamp
+}
+
+impl DatabaseType {
+
+    /// Checks if the values of the database are numbers (ie. can be aggregated and compared as numbers).
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, DatabaseType::Int | DatabaseType::Float | DatabaseType::Int64 | DatabaseType::Float64 | DatabaseType::Decimal)
+    }
 }
 
 impl fmt::Display for DatabaseType {
@@ -76,6 +349,11 @@ impl fmt::Display for DatabaseType {
             DatabaseType::Float => write!(f, "FLOAT"),
             DatabaseType::Bool => write!(f, "BOOL"),
             DatabaseType::Json => write!(f, "JSON"),
+            DatabaseType::Int64 => write!(f, "INT64"),
+            DatabaseType::Float64 => write!(f, "FLOAT64"),
+            DatabaseType::Decimal => write!(f, "DECIMAL"),
+            DatabaseType::Bytes => write!(f, "BYTES"),
+            DatabaseType::Timestamp => write!(f, "TIMESTAMP"),
         }
     }
 }
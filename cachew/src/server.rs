@@ -1,12 +1,13 @@
 use std::{sync::{Arc}, net::SocketAddr};
-use tokio::{sync::{Mutex, broadcast}, signal::unix::{signal, SignalKind}};
+use tokio::{sync::{Mutex, broadcast}, signal::unix::{signal, SignalKind}, time::{Instant, sleep_until}};
 use tokio::{
     io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
-    net::{TcpListener, TcpStream, tcp::ReadHalf}
+    net::{TcpListener, TcpStream, tcp::{ReadHalf, WriteHalf}}
 };
 use log::{info, warn, error};
 
-use crate::{response::QueryResponse, state::State, schemas::QueryRequest};
+use crate::{response::QueryResponse, state::State, schemas::{QueryRequest, QueryResponseType, WaitEvent}};
+use crate::key::Key;
 use crate::{protocol_error};
 use crate::parser;
 use crate::errors::protocol_errors::{ProtocolErrorType};
@@ -35,28 +36,120 @@ fn check_protocol(request: &str) -> Result<(), String> {
 }
 
 
+/// What ended the wait of a blocking request.
+enum WaitOutcome {
+    Response(Result<QueryResponseType, String>),
+    Shutdown,
+    Closed
+}
+
+/// A blocking request (BLPOP, GET WAIT) waiting for a write to its key (or its deletion).
+struct BlockedRequest<'a> {
+    request: &'a QueryRequest<'a>,
+    key: Key,
+    deadline: Option<Instant>,
+    /// Subscribed before the first attempt, so no write after it is missed.
+    key_changes: broadcast::Receiver<Key>
+}
+
+/// Waits until a blocking request is answered. It is attempted again after every write to (or deletion of) its key
+/// and once its timeout elapsed, the state is only locked during the attempts so other clients can write meanwhile.
+async fn wait_for_request(
+    mut blocked: BlockedRequest<'_>,
+    address: &str,
+    state: &Arc<Mutex<State>>,
+    reader: &mut BufReader<ReadHalf<'_>>,
+    pending: &mut Vec<u8>,
+    shutdown_rx: &mut broadcast::Receiver<()>
+) -> WaitOutcome {
+    loop {
+        let event: WaitEvent = tokio::select! {
+            _ = shutdown_rx.recv() => return WaitOutcome::Shutdown,
+            _ = async {
+                match blocked.deadline {
+                    Some(deadline) => sleep_until(deadline).await,
+                    None => std::future::pending().await
+                }
+            } => WaitEvent::Timeout,
+            changed_key = blocked.key_changes.recv() => match changed_key {
+                Ok(changed_key) if changed_key != blocked.key => continue,
+                // the key was written or deleted, or changes were missed (lagged), the attempt re-checks the key either way
+                _ => WaitEvent::KeyChanged
+            },
+            // reading detects a closed connection, anything the client sends meanwhile is kept for after the wait
+            buffer = reader.fill_buf() => match buffer {
+                Ok(buffer) if !buffer.is_empty() => {
+                    let length: usize = buffer.len();
+                    pending.extend_from_slice(buffer);
+                    reader.consume(length);
+                    continue;
+                }
+                _ => return WaitOutcome::Closed
+            }
+        };
+
+        if let Some(response) = state.lock().await.execute_blocking_request(address, blocked.request, event) {
+            return WaitOutcome::Response(response);
+        }
+    }
+}
+
+
+
+/// Moves the bytes the client sent while a blocking request waited to the buffer, up to the first line break.
+///
+/// # Returns:
+/// True if a whole line was moved, it's handled without reading from the connection then.
+fn take_pending_line(pending: &mut Vec<u8>, buffer: &mut Vec<u8>) -> bool {
+    match pending.iter().position(|byte| *byte == b'\n') {
+        Some(end) => {
+            buffer.extend(pending.drain(..=end));
+            true
+        }
+        None => {
+            buffer.append(pending);
+            false
+        }
+    }
+}
+
+/// Sends a response to the client.
+///
+/// # Returns:
+/// False if the connection is gone (e.g it was reset).
+async fn respond(socket_writer: &mut WriteHalf<'_>, response: String) -> bool {
+    socket_writer.write_all(response.as_bytes()).await.is_ok()
+}
 
 async fn handle_client(mut socket: TcpStream, address: SocketAddr, state_clone: Arc<Mutex<State>>, mut shutdown_rx: broadcast::Receiver<()>) {
     let (socket_reader, mut socket_writer) = socket.split();
             
     let mut reader: BufReader<ReadHalf> = BufReader::new(socket_reader);
-    let mut line: String = String::new();
+    let mut buffer: Vec<u8> = Vec::new();
+    // bytes the client sent while a blocking request waited, they are handled before reading from the connection again
+    let mut pending: Vec<u8> = Vec::new();
 
     loop {
+        let pending_line: bool = take_pending_line(&mut pending, &mut buffer);
+
         tokio::select! {
             _ = shutdown_rx.recv() => {
-                let _ = socket_writer.write_all(QueryResponse::warn("SHUTDOWN").to_string().as_bytes()).await;
+                let _ = respond(&mut socket_writer, QueryResponse::warn("SHUTDOWN")).await;
                 break;
             }
-            byte_amount = reader.read_line(&mut line) => {
-                let mut state_lock = state_clone.lock().await;
-
-                if byte_amount.unwrap() == 0 {
+            byte_amount = async {
+                if pending_line { Ok(buffer.len()) } else { reader.read_until(b'\n', &mut buffer).await }
+            } => {
+                // a reset connection is handled like a closed one
+                if !matches!(byte_amount, Ok(byte_amount) if byte_amount > 0) {
                     warn!("Connection closed. ({})", address);
-                    state_lock.deauthenticate(&address.to_string());
-                    return;
+                    break;
                 }
 
+                let line: String = String::from_utf8_lossy(&buffer).into_owned();
+                buffer.clear();
+                let mut state_lock = state_clone.lock().await;
+
                 info!("Incoming request: {:?}.", &line);
 
                 // check if the incoming message followed the protocol specification
@@ -64,7 +157,7 @@ async fn handle_client(mut socket: TcpStream, address: SocketAddr, state_clone:
                     Ok(_) => { }
                     Err(error) => {
                         error!("Invalid request. Request didn't follow CASP specification.");
-                        socket_writer.write_all(QueryResponse::error(&error).to_string().as_bytes()).await.unwrap();
+                        let _ = respond(&mut socket_writer, QueryResponse::error(&error)).await;
                         break;
                     }
                 }
@@ -73,7 +166,7 @@ async fn handle_client(mut socket: TcpStream, address: SocketAddr, state_clone:
                 let request: &str = line.strip_prefix(REQUEST_START_MARKER).unwrap().strip_suffix(REQUEST_END_MARKER).unwrap().trim();
                 let query = parser::parse(request, &state_lock.database_type);
                 
-                match query {
+                let response: String = match query {
                     Ok(query) => {
                         // handle shutdown on request
                         if let QueryRequest::SHUTDOWN = query {
@@ -83,7 +176,7 @@ async fn handle_client(mut socket: TcpStream, address: SocketAddr, state_clone:
                             state_lock.signal_shutdown().await;
 
                             // send OK response to client who intiated shutdown
-                            socket_writer.write_all(QueryResponse::ok(crate::schemas::QueryResponseType::SHUTDOWN_OK, &state_lock.database_type).to_string().as_bytes()).await.unwrap();                            
+                            let _ = respond(&mut socket_writer, QueryResponse::ok(crate::schemas::QueryResponseType::SHUTDOWN_OK, &state_lock.database_type)).await;                            
 
                             // TODO: add persistance here if needed.
 
@@ -91,27 +184,74 @@ async fn handle_client(mut socket: TcpStream, address: SocketAddr, state_clone:
                             std::process::exit(0);
                         }
 
+                        // blocking requests wait without holding the state lock
+                        if let QueryRequest::BLPOP { key, timeout } | QueryRequest::GET_WAIT { key, timeout } = &query {
+                            let database_type = state_lock.database_type;
+                            let blocked: BlockedRequest = BlockedRequest {
+                                request: &query,
+                                key: state_lock.db.key(key),
+                                deadline: timeout.map(|timeout| Instant::now() + timeout),
+                                key_changes: state_lock.subscribe_key_changes()
+                            };
+                            let response = state_lock.execute_blocking_request(&address.to_string(), &query, WaitEvent::Start);
+                            drop(state_lock);
+
+                            let outcome: WaitOutcome = match response {
+                                Some(response) => WaitOutcome::Response(response),
+                                None => wait_for_request(blocked, &address.to_string(), &state_clone, &mut reader, &mut pending, &mut shutdown_rx).await
+                            };
+
+                            let response: String = match outcome {
+                                WaitOutcome::Response(Ok(result)) => {
+                                    info!("Successfully executed request.");
+                                    QueryResponse::ok(result, &database_type)
+                                }
+                                WaitOutcome::Response(Err(error)) => {
+                                    error!("Failed to execute request. Error: {:?}.", &error);
+                                    QueryResponse::error(&error)
+                                }
+                                WaitOutcome::Shutdown => {
+                                    let _ = respond(&mut socket_writer, QueryResponse::warn("SHUTDOWN")).await;
+                                    break;
+                                }
+                                WaitOutcome::Closed => {
+                                    warn!("Connection closed. ({})", address);
+                                    break;
+                                }
+                            };
+
+                            if !respond(&mut socket_writer, response).await {
+                                break;
+                            }
+                            continue;
+                        }
+
                         match state_lock.execute_request(&address.to_string(), query) {
                             Ok(result) => {
                                 info!("Successfully executed request.");
-                                socket_writer.write_all(QueryResponse::ok(result, &state_lock.database_type).to_string().as_bytes()).await.unwrap();                            
+                                QueryResponse::ok(result, &state_lock.database_type)
                             }
                             Err(error) => {
                                 error!("Failed to execute request. Error: {:?}.", &error);
-                                socket_writer.write_all(QueryResponse::error(&error).to_string().as_bytes()).await.unwrap();                            
+                                QueryResponse::error(&error)
                             }
                         }
                     }
                     Err(error) => {
                         error!("Failed to parse request. Error: {:?}.", &error);
-                        socket_writer.write_all(QueryResponse::error(&error).to_string().as_bytes()).await.unwrap();                            
+                        QueryResponse::error(&error)
                     }
-                }
+                };
 
-                line.clear();
+                if !respond(&mut socket_writer, response).await {
+                    break;
+                }
             }
         }
     }
+
+    // every way out of the loop ends the connection, so the keys the client set with EPHEMERAL never outlive it
+    state_clone.lock().await.disconnect(&address.to_string());
 }
 
 
@@ -160,6 +300,7 @@ pub async fn serve(state: State, host: &str, port: &str) {
 
 #[cfg(test)]
 mod tests {
+    use crate::schemas::DatabaseType;
     use super::*;
 
     #[test]
@@ -177,4 +318,55 @@ mod tests {
         assert_eq!(protocol_validity.unwrap_err(), format!("ProtocolError 'endMarkerNotFound': Expected request to end with '{}'.", REQUEST_END_MARKER.replace('\n', "\\n")));
     }
 
+    /// Connects a client that authenticates and sets an ephemeral key.
+    async fn connect_ephemeral_client(port: u16, key: &str) -> BufReader<TcpStream> {
+        let mut client: BufReader<TcpStream> = BufReader::new(TcpStream::connect(("127.0.0.1", port)).await.unwrap());
+        let mut response: String = String::new();
+
+        for request in ["CASP/AUTH pwd123/\n".to_string(), format!("CASP/SET {} 1 EPHEMERAL/\n", key)] {
+            client.get_mut().write_all(request.as_bytes()).await.unwrap();
+            client.read_line(&mut response).await.unwrap();
+        }
+        assert_eq!(response, "CASP/OK/AUTH/\nCASP/OK/SET/\n");
+        client
+    }
+
+    #[tokio::test]
+    async fn test_ephemeral_keys_of_broken_connections() {
+        let state: Arc<Mutex<State>> = Arc::new(Mutex::new(State::new(DatabaseType::Int, "pwd123".to_string())));
+        let listener: TcpListener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port: u16 = listener.local_addr().unwrap().port();
+
+        let server_state = Arc::clone(&state);
+        tokio::spawn(async move {
+            loop {
+                let (socket, address) = listener.accept().await.unwrap();
+                let shutdown_rx = server_state.lock().await.subscribe_shutdown_channel();
+                tokio::spawn(handle_client(socket, address, Arc::clone(&server_state), shutdown_rx));
+            }
+        });
+
+        // a client that crashes resets its connection instead of closing it
+        let reset_client = connect_ephemeral_client(port, "worker:1").await;
+        reset_client.get_ref().set_linger(Some(std::time::Duration::ZERO)).unwrap();
+        drop(reset_client);
+
+        // a client that sends something while blocked and then goes away is still noticed
+        let mut blocked_client = connect_ephemeral_client(port, "worker:2").await;
+        blocked_client.get_mut().write_all(b"CASP/GET worker:2 WAIT 0/\nCASP/GET").await.unwrap();
+        drop(blocked_client);
+
+        for key in ["worker:1", "worker:2"] {
+            let mut exists: bool = true;
+            for _ in 0..100 {
+                exists = state.lock().await.db.exists(key) == Ok(QueryResponseType::EXISTS_OK(true));
+                if !exists {
+                    break;
+                }
+                sleep_until(Instant::now() + std::time::Duration::from_millis(10)).await;
+            }
+            assert!(!exists, "the ephemeral key '{}' outlived its connection", key);
+        }
+    }
+
 }
\ No newline at end of file
@@ -0,0 +1,162 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap};
+use std::ops::Bound::{Included, Unbounded};
+use std::ops::RangeInclusive;
+
+
+/// A score of a sorted set, totally ordered (scores are never NaN).
+#[derive(Debug, Clone, Copy)]
+struct Score(f64);
+
+impl Ord for Score {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl PartialOrd for Score {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Score {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Score {}
+
+
+/// A set of distinct members ordered by their score, members with the same score are ordered by their serialized form.
+/// Two indexes are kept in sync: member to score (for lookups and updates) and score to member (for ordered ranges).
+/// Ranges by score take O(log n + k), ranks take O(rank).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SortedSet {
+    scores: HashMap<Vec<u8>, f64>,
+    ordered: BTreeSet<(Score, Vec<u8>)>
+}
+
+impl SortedSet {
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.scores.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scores.is_empty()
+    }
+
+    /// Inserts a member or updates its score.
+    ///
+    /// # Arguments:
+    /// * `member`: The serialized member.
+    /// * `score`: The score (must not be NaN).
+    ///
+    /// # Returns:
+    /// True if the member is new.
+    pub fn insert(&mut self, member: Vec<u8>, score: f64) -> bool {
+        // -0.0 and 0.0 are the same score
+        let score: f64 = score + 0.0;
+        let previous_score: Option<f64> = self.scores.insert(member.clone(), score);
+        if let Some(previous_score) = previous_score {
+            self.ordered.remove(&(Score(previous_score), member.clone()));
+        }
+        self.ordered.insert((Score(score), member));
+        previous_score.is_none()
+    }
+
+    /// Removes a member.
+    ///
+    /// # Returns:
+    /// True if the member existed.
+    pub fn remove(&mut self, member: &[u8]) -> bool {
+        match self.scores.remove(member) {
+            Some(score) => self.ordered.remove(&(Score(score), member.to_vec())),
+            None => false
+        }
+    }
+
+    pub fn score(&self, member: &[u8]) -> Option<f64> {
+        self.scores.get(member).copied()
+    }
+
+    /// Gets the position of a member in ascending score order (the member with the lowest score has rank 0).
+    pub fn rank(&self, member: &[u8]) -> Option<usize> {
+        let score: f64 = self.score(member)?;
+        Some(self.ordered.range(..(Score(score), member.to_vec())).count())
+    }
+
+    /// Gets the members with their scores between two ranks (both inclusive).
+    ///
+    /// # Arguments:
+    /// * `ranks`: The ranks.
+    /// * `reverse`: If true, ranks count from the highest score.
+    pub fn range_by_rank(&self, ranks: RangeInclusive<usize>, reverse: bool) -> Vec<(&[u8], f64)> {
+        let (start, stop) = ranks.into_inner();
+        let members = self.ordered.iter().map(|(score, member)| (member.as_slice(), score.0));
+        match reverse {
+            true => members.rev().skip(start).take(stop + 1 - start).collect(),
+            false => members.skip(start).take(stop + 1 - start).collect()
+        }
+    }
+
+    /// Gets the members with a score between a minimum and a maximum (both inclusive), in ascending score order.
+    pub fn range_by_score(&self, min: f64, max: f64) -> impl Iterator<Item = (&[u8], f64)> {
+        self.ordered
+            .range((Included((Score(min), vec![])), Unbounded))
+            .take_while(move |(score, _)| score.0 <= max)
+            .map(|(score, member)| (member.as_slice(), score.0))
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn members(sorted_set: &SortedSet) -> Vec<(u8, f64)> {
+        sorted_set.range_by_rank(0..=sorted_set.len().saturating_sub(1), false).into_iter().map(|(member, score)| (member[0], score)).collect()
+    }
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut sorted_set: SortedSet = SortedSet::new();
+        assert!(sorted_set.insert(vec![1], 10.0));
+        assert!(sorted_set.insert(vec![2], -5.0));
+        assert!(sorted_set.insert(vec![3], 10.0));
+        assert!(!sorted_set.insert(vec![2], 20.0));
+        assert_eq!(members(&sorted_set), vec![(1, 10.0), (3, 10.0), (2, 20.0)]);
+
+        assert!(sorted_set.remove(&[1]));
+        assert!(!sorted_set.remove(&[1]));
+        assert_eq!(members(&sorted_set), vec![(3, 10.0), (2, 20.0)]);
+        assert_eq!(sorted_set.len(), 2);
+    }
+
+    #[test]
+    fn test_ranks_and_ranges() {
+        let mut sorted_set: SortedSet = SortedSet::new();
+        for (member, score) in [(1, 3.0), (2, 1.0), (3, 2.0), (4, f64::INFINITY), (5, -0.0)] {
+            sorted_set.insert(vec![member], score);
+        }
+
+        assert_eq!(sorted_set.rank(&[5]), Some(0));
+        assert_eq!(sorted_set.rank(&[4]), Some(4));
+        assert_eq!(sorted_set.rank(&[9]), None);
+
+        let top: Vec<u8> = sorted_set.range_by_rank(0..=1, true).into_iter().map(|(member, _)| member[0]).collect();
+        assert_eq!(top, vec![4, 1]);
+
+        let by_score: Vec<u8> = sorted_set.range_by_score(0.0, 2.0).map(|(member, _)| member[0]).collect();
+        assert_eq!(by_score, vec![5, 2, 3]);
+        assert_eq!(sorted_set.range_by_score(f64::NEG_INFINITY, f64::INFINITY).count(), 5);
+        assert_eq!(sorted_set.range_by_score(5.0, 1.0).count(), 0);
+    }
+}
@@ -3,8 +3,9 @@ use std::time::{Duration};
 use tokio::sync::broadcast;
 
 use crate::auth_error;
-use crate::schemas::{DatabaseType, QueryRequest, QueryResponseType};
+use crate::schemas::{DatabaseType, QueryRequest, QueryResponseType, WaitEvent};
 use crate::database::Database;
+use crate::key::Key;
 use crate::errors::authentication_errors::{AuthenticationErrorType};
 
 
@@ -43,6 +44,12 @@ impl State {
         self.auth_table.remove(address);
     }
 
+    /// Forgets a client whose connection closed, ie. deauthenticates it and deletes the keys it set with EPHEMERAL.
+    pub fn disconnect(&mut self, address: &str) {
+        self.deauthenticate(address);
+        self.db.del_ephemeral(address);
+    }
+
     pub fn authenticate(&mut self, address: &str, given_password: &str) -> Result<QueryResponseType, String> {
         // if the passwords match, add the client to the auth table
         if given_password == self.password {
@@ -69,22 +76,126 @@ impl State {
 
         match request {
             QueryRequest::GET(key) => self.db.get(&key),
-            QueryRequest::GET_RANGE { key_lower, key_upper } => self.db.get_range(key_lower, key_upper),
+            QueryRequest::GET_RANGE { key_lower, key_upper, predicates } => self.db.get_range(key_lower, key_upper, &predicates),
             QueryRequest::GET_MANY(keys) => self.db.get_many(keys),
+            QueryRequest::GET_MANY_NULLS(keys) => self.db.get_many_nulls(keys),
             QueryRequest::DEL(key) => self.db.del(&key),
             QueryRequest::DEL_RANGE { key_lower, key_upper } => self.db.del_range(key_lower, key_upper),
             QueryRequest::DEL_MANY(keys) => self.db.del_many(keys),
             QueryRequest::SET(key_value_pair) => self.db.set(&key_value_pair.key, key_value_pair.value),
+            QueryRequest::SET_EPHEMERAL(key_value_pair) => self.db.set_ephemeral(&key_value_pair.key, key_value_pair.value, address),
             QueryRequest::SET_MANY(key_value_pairs) => self.db.set_many(key_value_pairs),
             QueryRequest::AUTH(password) => self.authenticate(address, &password),
             QueryRequest::CLEAR => self.db.clear(),
             QueryRequest::LEN => self.db.len(),
             QueryRequest::PING => Ok(QueryResponseType::PING_OK),
             QueryRequest::EXISTS(key) => self.db.exists(&key),
+            QueryRequest::KEYS { pattern, prefix, count } => self.db.keys(&pattern, &prefix, count),
+            QueryRequest::FIRST => self.db.first(),
+            QueryRequest::LAST => self.db.last(),
+            QueryRequest::NEXT(key) => self.db.next(&key),
+            QueryRequest::PREV(key) => self.db.prev(&key),
+            QueryRequest::FLOOR(key) => self.db.floor(&key),
+            QueryRequest::CEIL(key) => self.db.ceil(&key),
+            QueryRequest::AGG { aggregation, key_lower, key_upper } => self.db.aggregate(aggregation, key_lower, key_upper),
+            QueryRequest::RENAME { key, new_key, nx } => self.db.rename(&key, &new_key, nx),
+            QueryRequest::RENAME_PREFIX { prefix, new_prefix } => self.db.rename_prefix(&prefix, &new_prefix),
+            QueryRequest::COPY { key, new_key, replace } => self.db.copy(&key, &new_key, replace),
+            QueryRequest::JSON_GET { key, path } => self.db.json_get(&key, &path),
+            QueryRequest::JSON_SET { key, path, value } => self.db.json_set(&key, &path, &value),
+            QueryRequest::JSON_DEL { key, path } => self.db.json_del(&key, &path),
+            QueryRequest::JSON_MERGE { key, patch } => self.db.json_merge(&key, &patch),
+            QueryRequest::SCHEMA_GET => self.db.schema_get(),
+            QueryRequest::STRLEN(key) => self.db.strlen(&key),
+            QueryRequest::INDEX_CREATE { name, path } => self.db.index_create(&name, path),
+            QueryRequest::INDEX_DROP(name) => self.db.index_drop(&name),
+            QueryRequest::FIND { name, lower, upper } => self.db.find(&name, &lower, &upper),
+            QueryRequest::FIND_VALUE { lower, upper } => self.db.find_value(&lower, upper.as_ref()),
+            QueryRequest::LPUSH { key, values } => self.db.lpush(&key, values),
+            QueryRequest::RPUSH { key, values } => self.db.rpush(&key, values),
+            QueryRequest::LPOP(key) => self.db.lpop(&key),
+            QueryRequest::RPOP(key) => self.db.rpop(&key),
+            QueryRequest::LRANGE { key, start, stop } => self.db.lrange(&key, start, stop),
+            QueryRequest::SADD { key, members } => self.db.sadd(&key, members),
+            QueryRequest::SREM { key, members } => self.db.srem(&key, members),
+            QueryRequest::SMEMBERS(key) => self.db.smembers(&key),
+            QueryRequest::SISMEMBER { key, member } => self.db.sismember(&key, &member),
+            QueryRequest::HSET { key, fields } => self.db.hset(&key, fields),
+            QueryRequest::HGET { key, field } => self.db.hget(&key, &field),
+            QueryRequest::HDEL { key, fields } => self.db.hdel(&key, fields),
+            QueryRequest::HGETALL(key) => self.db.hgetall(&key),
+            QueryRequest::ZADD { key, members } => self.db.zadd(&key, members),
+            QueryRequest::ZINCRBY { key, increment, member } => self.db.zincrby(&key, increment, member),
+            QueryRequest::ZRANGE { key, start, stop, reverse } => self.db.zrange(&key, start, stop, reverse),
+            QueryRequest::ZRANGE_BYSCORE { key, min, max } => self.db.zrange_by_score(&key, min, max),
+            QueryRequest::ZRANK { key, member, reverse } => self.db.zrank(&key, &member, reverse),
+            QueryRequest::ZREM { key, members } => self.db.zrem(&key, members),
+            QueryRequest::ZCOUNT { key, min, max } => self.db.zcount(&key, min, max),
+            QueryRequest::TS_ADD { key, timestamp, value, retention } => self.db.ts_add(&key, timestamp, value, retention),
+            QueryRequest::TS_RANGE { key, from, to, downsampling } => self.db.ts_range(&key, from, to, downsampling),
+            QueryRequest::PFADD { key, error_rate, values } => self.db.pfadd(&key, error_rate, values),
+            QueryRequest::PFCOUNT(keys) => self.db.pfcount(keys),
+            QueryRequest::PFMERGE { destination, sources } => self.db.pfmerge(&destination, sources),
+            QueryRequest::BF_RESERVE { key, error_rate, capacity } => self.db.bf_reserve(&key, error_rate, capacity),
+            QueryRequest::BF_ADD { key, value } => self.db.bf_add(&key, value),
+            QueryRequest::BF_EXISTS { key, value } => self.db.bf_exists(&key, &value),
+            QueryRequest::THROTTLE { key, limit, cost } => self.db.throttle(&key, &limit, cost),
+            QueryRequest::LOCK { name, owner, ttl } => self.db.lock(&name, &owner, ttl),
+            QueryRequest::UNLOCK { name, owner } => self.db.unlock(&name, &owner),
+            QueryRequest::RENEW { name, owner, ttl } => self.db.renew(&name, &owner, ttl),
+            QueryRequest::LOCKINFO(name) => self.db.lockinfo(&name),
+            QueryRequest::SEQ_NEXT { name, count } => self.db.seq_next(&name, count),
+            QueryRequest::SEQ_SET { name, last_id } => self.db.seq_set(&name, last_id),
+            QueryRequest::SEQ_GET(name) => self.db.seq_get(&name),
+            QueryRequest::GEO_ADD { key, member, position } => self.db.geo_add(&key, member, position),
+            QueryRequest::GEO_RADIUS { key, center, radius, unit, count } => self.db.geo_radius(&key, &center, radius, unit, count),
+            QueryRequest::GEO_BOX { key, min, max, count } => self.db.geo_box(&key, &min, &max, count),
+            QueryRequest::GEO_DIST { key, from, to, unit } => self.db.geo_dist(&key, &from, &to, unit),
+            // without waiting, ie. as if the timeout elapsed right away (the server waits for them, see `execute_blocking_request`)
+            QueryRequest::BLPOP { .. } | QueryRequest::GET_WAIT { .. } => self.execute_blocking_request(address, &request, WaitEvent::Timeout).unwrap(),
             QueryRequest::SHUTDOWN => Ok(QueryResponseType::SHUTDOWN_OK)
         }
     }
 
+    /// Attempts a blocking request (BLPOP, GET WAIT). The server attempts it when it arrives, after every write to (or deletion of) its key
+    /// and once its timeout elapsed, in between the state isn't locked by the waiting client.
+    /// 
+    /// # Arguments:
+    /// * `address`: The address of the client.
+    /// * `request`: The blocking request.
+    /// * `event`: What the request is attempted after.
+    /// 
+    /// # Returns:
+    /// The response, or `None` if the request has to keep waiting.
+    pub fn execute_blocking_request(&mut self, address: &str, request: &QueryRequest, event: WaitEvent) -> Option<Result<QueryResponseType, String>> {
+        if !self.is_authenticated(address.to_owned()) {
+            let not_authenticated = || -> Result<QueryResponseType, String> { auth_error!(AuthenticationErrorType::NotAuthenticated) };
+            return Some(not_authenticated());
+        }
+
+        match request {
+            QueryRequest::BLPOP { key, .. } => match self.db.lpop(key) {
+                Ok(QueryResponseType::LPOP_OK(None)) if event != WaitEvent::Timeout => None,
+                Ok(QueryResponseType::LPOP_OK(value)) => Some(Ok(QueryResponseType::BLPOP_OK(value))),
+                response => Some(response)
+            },
+            // GET WAIT is answered right away if the key exists, else once it's written (or NONE after the timeout),
+            // so an attempt after a missed or unrelated change (e.g a deletion) only re-checks the key
+            QueryRequest::GET_WAIT { key, .. } => match (event, self.db.get(key)) {
+                (_, Ok(QueryResponseType::GET_OK(value))) => Some(Ok(QueryResponseType::GET_WAIT_OK(Some(value)))),
+                (WaitEvent::Timeout, _) => Some(Ok(QueryResponseType::GET_WAIT_OK(None))),
+                (_, Err(_)) if self.db.exists(key) == Ok(QueryResponseType::EXISTS_OK(false)) => None,
+                (_, response) => Some(response)
+            },
+            _ => unreachable!()
+        }
+    }
+
+    /// Subscribes to the keys written to or deleted, see `Database::subscribe_key_changes`.
+    pub fn subscribe_key_changes(&self) -> broadcast::Receiver<Key> {
+        self.db.subscribe_key_changes()
+    }
+
     pub async fn signal_shutdown(&self) {
         let _ = self.shutdown_tx.send(());
         tokio::time::sleep(Duration::from_secs(1)).await;
@@ -98,7 +209,8 @@ impl State {
 
 #[cfg(test)]
 mod tests {
-    use crate::schemas::{KeyValuePair, ValueType};
+    use crate::schemas::{KeyValuePair, ValueType, AggregationType};
+    use crate::index::IndexValue;
     use super::*;
 
     #[test]
@@ -156,22 +268,40 @@ mod tests {
         let response_get_many = state.execute_request(client_address, QueryRequest::GET_MANY(vec!["key3", "key2"]));
         assert_eq!(response_get_many, Ok(QueryResponseType::GET_MANY_OK(vec![ValueType::Str("value3".to_string()), ValueType::Str("value2".to_string())])));
 
-        let response_get_range = state.execute_request(client_address, QueryRequest::GET_RANGE { key_lower: "key2".to_string(), key_upper: "key4".to_string() });
+        let response_get_many_nulls = state.execute_request(client_address, QueryRequest::GET_MANY_NULLS(vec!["key3", "key9"]));
+        assert_eq!(response_get_many_nulls, Ok(QueryResponseType::GET_MANY_NULLS_OK(vec![Some(ValueType::Str("value3".to_string())), None])));
+
+        let response_get_range = state.execute_request(client_address, QueryRequest::GET_RANGE { key_lower: "key2".to_string(), key_upper: "key4".to_string(), predicates: vec![] });
         assert_eq!(response_get_range, Ok(QueryResponseType::GET_RANGE_OK(vec![
             ValueType::Str("value2".to_string()), ValueType::Str("value3".to_string()), ValueType::Str("value4".to_string())
         ])));
 
         let response_ping = state.execute_request(client_address,QueryRequest::EXISTS("key2".to_owned()));
         assert_eq!(response_ping, Ok(QueryResponseType::EXISTS_OK(true)));
+
+        let response_keys = state.execute_request(client_address, QueryRequest::KEYS { pattern: "^key[1-2]$".to_string(), prefix: "key".to_string(), count: 10 });
+        assert_eq!(response_keys, Ok(QueryResponseType::KEYS_OK(vec!["key1".to_string(), "key2".to_string()])));
         
+        let response_next = state.execute_request(client_address, QueryRequest::NEXT("key4".to_string()));
+        assert_eq!(response_next, Ok(QueryResponseType::NEXT_OK(Some(KeyValuePair { key: "key5".to_string(), value: ValueType::Str("value5".to_string()) }))));
+
+        let response_copy = state.execute_request(client_address, QueryRequest::COPY { key: "key".to_string(), new_key: "copy".to_string(), replace: false });
+        assert_eq!(response_copy, Ok(QueryResponseType::COPY_OK));
+
+        let response_rename = state.execute_request(client_address, QueryRequest::RENAME { key: "copy".to_string(), new_key: "renamed".to_string(), nx: true });
+        assert_eq!(response_rename, Ok(QueryResponseType::RENAME_OK));
+
+        let response_rename_prefix = state.execute_request(client_address, QueryRequest::RENAME_PREFIX { prefix: "renamed".to_string(), new_prefix: "moved".to_string() });
+        assert_eq!(response_rename_prefix, Ok(QueryResponseType::RENAME_PREFIX_OK(1)));
+
         let response_del = state.execute_request(client_address, QueryRequest::DEL("key1".to_string()));
         assert_eq!(response_del, Ok(QueryResponseType::DEL_OK));
 
         let response_del_many = state.execute_request(client_address, QueryRequest::DEL_MANY(vec!["key4", "key3"]));
-        assert_eq!(response_del_many, Ok(QueryResponseType::DEL_MANY_OK));
+        assert_eq!(response_del_many, Ok(QueryResponseType::DEL_MANY_OK(2)));
 
         let response_del_range = state.execute_request(client_address, QueryRequest::DEL_RANGE { key_lower: "key2".to_string(), key_upper: "key5".to_string() });
-        assert_eq!(response_del_range, Ok(QueryResponseType::DEL_RANGE_OK));    
+        assert_eq!(response_del_range, Ok(QueryResponseType::DEL_RANGE_OK(2)));    
         
         let response_clear = state.execute_request(client_address, QueryRequest::CLEAR);
         assert_eq!(response_clear, Ok(QueryResponseType::CLEAR_OK));
@@ -181,7 +311,65 @@ mod tests {
 
         let response_ping = state.execute_request(client_address,QueryRequest::PING);
         assert_eq!(response_ping, Ok(QueryResponseType::PING_OK));
+
+        let response_agg = state.execute_request(client_address, QueryRequest::AGG { aggregation: AggregationType::Count, key_lower: "a".to_string(), key_upper: "z".to_string() });
+        assert_eq!(response_agg.unwrap_err(), "DatabaseError 'unsupportedAggregation': Aggregations are not supported on 'STR' databases.");
+
+        let response_find = state.execute_request(client_address, QueryRequest::FIND { name: "by_age".to_string(), lower: IndexValue::Number(18.0), upper: IndexValue::Number(30.0) });
+        assert_eq!(response_find.unwrap_err(), "DatabaseError 'indexNotFound': The index 'by_age' doesn't exist.");
+
+        let response_find_value = state.execute_request(client_address, QueryRequest::FIND_VALUE { lower: ValueType::Str("value".to_string()), upper: None });
+        assert_eq!(response_find_value.unwrap_err(), "DatabaseError 'valueIndexDisabled': The value index is not enabled (start the server with '--value-index').");
     }
 
-}
+    #[test]
+    fn test_execute_blocking_request() {
+        let mut state: State = State::new(DatabaseType::Int, "pwd123".to_string());
+        let client_address: &str = "0.0.0.0:0000";
+        let blpop = QueryRequest::BLPOP { key: "jobs".to_string(), timeout: None };
+        let get_wait = QueryRequest::GET_WAIT { key: "counter".to_string(), timeout: None };
+
+        let not_authenticated = state.execute_blocking_request(client_address, &blpop, WaitEvent::Start);
+        assert_eq!(not_authenticated.unwrap().unwrap_err(), "AuthenticationError 'notAuthenticated': Please authenticate before executing queries.");
+        let _ = state.authenticate(client_address, "pwd123");
+
+        // an empty list keeps BLPOP waiting until it times out
+        assert_eq!(state.execute_blocking_request(client_address, &blpop, WaitEvent::Start), None);
+        assert_eq!(state.execute_blocking_request(client_address, &blpop, WaitEvent::Timeout), Some(Ok(QueryResponseType::BLPOP_OK(None))));
+
+        let _ = state.execute_request(client_address, QueryRequest::RPUSH { key: "jobs".to_string(), values: vec![ValueType::Int(7)] });
+        assert_eq!(state.execute_blocking_request(client_address, &blpop, WaitEvent::KeyChanged), Some(Ok(QueryResponseType::BLPOP_OK(Some(ValueType::Int(7))))));
+
+        // GET WAIT on a missing key waits until the key is written
+        assert_eq!(state.execute_blocking_request(client_address, &get_wait, WaitEvent::Start), None);
+        let _ = state.execute_request(client_address, QueryRequest::SET(KeyValuePair { key: "counter".to_string(), value: ValueType::Int(1) }));
+        assert_eq!(state.execute_blocking_request(client_address, &get_wait, WaitEvent::KeyChanged), Some(Ok(QueryResponseType::GET_WAIT_OK(Some(ValueType::Int(1))))));
+
+        // GET WAIT on an existing key is answered right away
+        assert_eq!(state.execute_blocking_request(client_address, &get_wait, WaitEvent::Start), Some(Ok(QueryResponseType::GET_WAIT_OK(Some(ValueType::Int(1))))));
+
+        // a deletion wakes up the waiting requests, but they keep waiting
+        let _ = state.execute_request(client_address, QueryRequest::DEL("counter".to_string()));
+        assert_eq!(state.execute_blocking_request(client_address, &get_wait, WaitEvent::KeyChanged), None);
+        assert_eq!(state.execute_blocking_request(client_address, &get_wait, WaitEvent::Timeout), Some(Ok(QueryResponseType::GET_WAIT_OK(None))));
+        let _ = state.execute_request(client_address, QueryRequest::DEL("jobs".to_string()));
+        assert_eq!(state.execute_blocking_request(client_address, &blpop, WaitEvent::KeyChanged), None);
+    }
 
+    #[test]
+    fn test_disconnect() {
+        let mut state: State = State::new(DatabaseType::Int, "pwd123".to_string());
+        let (client_address, other_address): (&str, &str) = ("0.0.0.0:0001", "0.0.0.0:0002");
+        let _ = state.authenticate(client_address, "pwd123");
+        let _ = state.authenticate(other_address, "pwd123");
+
+        let _ = state.execute_request(client_address, QueryRequest::SET_EPHEMERAL(KeyValuePair { key: "worker:1".to_string(), value: ValueType::Int(1) }));
+        let _ = state.execute_request(other_address, QueryRequest::SET_EPHEMERAL(KeyValuePair { key: "worker:2".to_string(), value: ValueType::Int(2) }));
+
+        state.disconnect(client_address);
+        assert!(!state.is_authenticated(client_address.to_owned()));
+        assert_eq!(state.execute_request(other_address, QueryRequest::EXISTS("worker:1".to_string())), Ok(QueryResponseType::EXISTS_OK(false)));
+        assert_eq!(state.execute_request(other_address, QueryRequest::EXISTS("worker:2".to_string())), Ok(QueryResponseType::EXISTS_OK(true)));
+    }
+
+}
@@ -0,0 +1,126 @@
+use std::collections::VecDeque;
+
+
+/// A series of samples (a timestamp in epoch milliseconds and a serialized value), ordered by their timestamp.
+/// Samples can only be appended, so ranges are found with a binary search and expired samples are dropped from the front.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TimeSeries {
+    samples: VecDeque<(i64, Vec<u8>)>,
+    /// Samples older than the newest sample minus the retention (in milliseconds) are dropped, 0 keeps every sample.
+    retention: i64
+}
+
+impl TimeSeries {
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn last_timestamp(&self) -> Option<i64> {
+        self.samples.back().map(|(timestamp, _)| *timestamp)
+    }
+
+    /// Sets the retention and drops the samples that are expired by it.
+    pub fn set_retention(&mut self, retention: i64) {
+        self.retention = retention;
+        self.drop_expired();
+    }
+
+    /// Appends a sample and drops the samples that expired by it.
+    ///
+    /// # Arguments:
+    /// * `timestamp`: The timestamp, it has to be after the timestamp of the last sample.
+    /// * `value`: The serialized value.
+    ///
+    /// # Returns:
+    /// The timestamp of the last sample as error if the timestamp isn't after it (nothing is appended then).
+    pub fn append(&mut self, timestamp: i64, value: Vec<u8>) -> Result<(), i64> {
+        if let Some(last_timestamp) = self.last_timestamp().filter(|last_timestamp| timestamp <= *last_timestamp) {
+            return Err(last_timestamp);
+        }
+
+        self.samples.push_back((timestamp, value));
+        self.drop_expired();
+        Ok(())
+    }
+
+    /// Gets the samples between two timestamps (both inclusive).
+    pub fn range(&self, from: i64, to: i64) -> impl Iterator<Item = (i64, &[u8])> {
+        let start: usize = self.samples.partition_point(|(timestamp, _)| *timestamp < from);
+        self.samples
+            .range(start..)
+            .take_while(move |(timestamp, _)| *timestamp <= to)
+            .map(|(timestamp, value)| (*timestamp, value.as_slice()))
+    }
+
+    fn drop_expired(&mut self) {
+        let Some(last_timestamp) = self.last_timestamp() else { return };
+        if self.retention == 0 {
+            return;
+        }
+
+        let oldest_timestamp: i64 = last_timestamp.saturating_sub(self.retention);
+        while self.samples.front().is_some_and(|(timestamp, _)| *timestamp < oldest_timestamp) {
+            self.samples.pop_front();
+        }
+    }
+}
+
+
+/// Gets the start of the bucket a timestamp falls into, buckets are aligned to the epoch (e.g with 60000 ms buckets they start at full minutes).
+pub fn bucket_start(timestamp: i64, bucket: i64) -> i64 {
+    timestamp - timestamp.rem_euclid(bucket)
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timestamps(time_series: &TimeSeries) -> Vec<i64> {
+        time_series.range(i64::MIN, i64::MAX).map(|(timestamp, _)| timestamp).collect()
+    }
+
+    #[test]
+    fn test_append_and_range() {
+        let mut time_series: TimeSeries = TimeSeries::new();
+        assert_eq!(time_series.append(1000, vec![1]), Ok(()));
+        assert_eq!(time_series.append(2000, vec![2]), Ok(()));
+        assert_eq!(time_series.append(3500, vec![3]), Ok(()));
+
+        // samples are append-only
+        assert_eq!(time_series.append(3500, vec![4]), Err(3500));
+        assert_eq!(time_series.append(0, vec![4]), Err(3500));
+
+        let values: Vec<u8> = time_series.range(1500, 3500).map(|(_, value)| value[0]).collect();
+        assert_eq!(values, vec![2, 3]);
+        assert_eq!(time_series.range(4000, 5000).count(), 0);
+        assert_eq!(time_series.range(3000, 1000).count(), 0);
+    }
+
+    #[test]
+    fn test_retention() {
+        let mut time_series: TimeSeries = TimeSeries::new();
+        for timestamp in [1000, 2000, 3000, 4000] {
+            let _ = time_series.append(timestamp, vec![0]);
+        }
+
+        time_series.set_retention(2000);
+        assert_eq!(timestamps(&time_series), vec![2000, 3000, 4000]);
+
+        let _ = time_series.append(5500, vec![0]);
+        assert_eq!(timestamps(&time_series), vec![4000, 5500]);
+    }
+
+    #[test]
+    fn test_bucket_start() {
+        assert_eq!(bucket_start(61_500, 60_000), 60_000);
+        assert_eq!(bucket_start(60_000, 60_000), 60_000);
+        assert_eq!(bucket_start(-1, 60_000), -60_000);
+    }
+}
@@ -0,0 +1,211 @@
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Serialize, Deserialize};
+
+
+const MILLIS_PER_SECOND: i64 = 1000;
+const MILLIS_PER_DAY: i64 = 86_400 * MILLIS_PER_SECOND;
+
+/// The earliest instant that can be stored, ``0000-01-01T00:00:00.000Z``.
+const MIN_MILLIS: i64 = -62_167_219_200_000;
+/// The latest instant that can be stored, ``9999-12-31T23:59:59.999Z``.
+const MAX_MILLIS: i64 = 253_402_300_799_999;
+
+
+/// An instant in time, stored as milliseconds since the Unix epoch (UTC). Timestamps can be parsed from
+/// RFC 3339 (any offset, sub-millisecond digits are truncated) or from epoch milliseconds and are always
+/// displayed as RFC 3339 in UTC with millisecond precision, e.g ``2023-10-11T05:06:40.123Z``.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Timestamp {
+    millis: i64
+}
+
+impl Timestamp {
+
+    /// Creates a timestamp from epoch milliseconds.
+    ///
+    /// # Returns:
+    /// The timestamp or `None` if it's outside the years 0000 to 9999.
+    pub fn from_millis(millis: i64) -> Option<Self> {
+        (MIN_MILLIS..=MAX_MILLIS).contains(&millis).then_some(Self { millis })
+    }
+
+    /// The current time.
+    pub fn now() -> Self {
+        Self { millis: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as i64 }
+    }
+
+    pub fn millis(&self) -> i64 {
+        self.millis
+    }
+
+    /// Parses a timestamp, either RFC 3339 (e.g ``2023-10-11T07:06:40.5+02:00``) or epoch milliseconds (e.g ``1697000800500``).
+    ///
+    /// # Arguments:
+    /// * `timestamp`: The timestamp string.
+    ///
+    /// # Returns:
+    /// The timestamp or `None` if the string is neither valid RFC 3339 nor an integer, or the instant is outside the years 0000 to 9999.
+    pub fn parse(timestamp: &str) -> Option<Self> {
+        if let Ok(millis) = timestamp.parse::<i64>() {
+            return Self::from_millis(millis);
+        }
+        Self::parse_rfc3339(timestamp)
+    }
+
+    fn parse_rfc3339(timestamp: &str) -> Option<Self> {
+        let (date, time) = timestamp.split_once(['T', 't'])?;
+
+        let date: Vec<&str> = date.split('-').collect();
+        let [year, month, day] = date[..] else {
+            return None;
+        };
+        let year: i64 = parse_digits(year, 4)?;
+        let month: i64 = parse_digits(month, 2)?;
+        let day: i64 = parse_digits(day, 2)?;
+        if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+            return None;
+        }
+
+        // the offset is either 'Z' or '+HH:MM' / '-HH:MM'
+        let (time, offset_millis) = match time.strip_suffix(['Z', 'z']) {
+            Some(time) => (time, 0),
+            None => {
+                let sign_position: usize = time.rfind(['+', '-'])?;
+                let (time, offset) = time.split_at(sign_position);
+                let (offset_hours, offset_minutes) = offset[1..].split_once(':')?;
+                let offset_hours: i64 = parse_digits(offset_hours, 2)?;
+                let offset_minutes: i64 = parse_digits(offset_minutes, 2)?;
+                if offset_hours > 23 || offset_minutes > 59 {
+                    return None;
+                }
+                let offset_millis: i64 = (offset_hours * 60 + offset_minutes) * 60 * MILLIS_PER_SECOND;
+                (time, if offset.starts_with('-') { -offset_millis } else { offset_millis })
+            }
+        };
+
+        let (time, fraction) = time.split_once('.').unwrap_or((time, ""));
+        let time: Vec<&str> = time.split(':').collect();
+        let [hour, minute, second] = time[..] else {
+            return None;
+        };
+        let hour: i64 = parse_digits(hour, 2)?;
+        let minute: i64 = parse_digits(minute, 2)?;
+        let second: i64 = parse_digits(second, 2)?;
+        if hour > 23 || minute > 59 || second > 59 {
+            return None;
+        }
+
+        if time_has_empty_fraction(timestamp) || !fraction.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let fraction_millis: i64 = format!("{:0<3}", &fraction[..fraction.len().min(3)]).parse::<i64>().ok()?;
+
+        let local_millis: i64 = days_from_civil(year, month, day) * MILLIS_PER_DAY
+            + ((hour * 60 + minute) * 60 + second) * MILLIS_PER_SECOND
+            + fraction_millis;
+        Self::from_millis(local_millis - offset_millis)
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (year, month, day) = civil_from_days(self.millis.div_euclid(MILLIS_PER_DAY));
+        let millis_of_day: i64 = self.millis.rem_euclid(MILLIS_PER_DAY);
+        let seconds_of_day: i64 = millis_of_day / MILLIS_PER_SECOND;
+
+        write!(
+            f, "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+            year, month, day,
+            seconds_of_day / 3600, seconds_of_day / 60 % 60, seconds_of_day % 60,
+            millis_of_day % MILLIS_PER_SECOND
+        )
+    }
+}
+
+
+/// Parses a number with exactly `length` ASCII digits (no sign).
+fn parse_digits(digits: &str, length: usize) -> Option<i64> {
+    if digits.len() != length || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse::<i64>().ok()
+}
+
+/// Checks for a decimal point without fractional digits, e.g ``12:00:00.Z``.
+fn time_has_empty_fraction(timestamp: &str) -> bool {
+    timestamp.find('.').is_some_and(|position| !timestamp[position + 1..].starts_with(|c: char| c.is_ascii_digit()))
+}
+
+fn is_leap_year(year: i64) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31
+    }
+}
+
+/// Converts a date of the proleptic Gregorian calendar into days since 1970-01-01.
+/// See: http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year: i64 = if month <= 2 { year - 1 } else { year };
+    let era: i64 = year.div_euclid(400);
+    let year_of_era: i64 = year - era * 400;
+    let day_of_year: i64 = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era: i64 = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Converts days since 1970-01-01 into a date (year, month, day) of the proleptic Gregorian calendar.
+/// See: http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days: i64 = days + 719_468;
+    let era: i64 = days.div_euclid(146_097);
+    let day_of_era: i64 = days - era * 146_097;
+    let year_of_era: i64 = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year: i64 = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index: i64 = (5 * day_of_year + 2) / 153;
+    let day: i64 = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month: i64 = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    (year_of_era + era * 400 + i64::from(month <= 2), month, day)
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_display() {
+        assert_eq!(Timestamp::parse("2023-10-11T05:06:40.123Z").unwrap().to_string(), "2023-10-11T05:06:40.123Z");
+        assert_eq!(Timestamp::parse("2023-10-11T05:06:40Z").unwrap().millis(), 1697000800000);
+        assert_eq!(Timestamp::parse("2023-10-11T07:06:40.5+02:00").unwrap().to_string(), "2023-10-11T05:06:40.500Z");
+        assert_eq!(Timestamp::parse("2023-10-10T23:36:40.123456-05:30").unwrap().to_string(), "2023-10-11T05:06:40.123Z");
+        assert_eq!(Timestamp::parse("1697000800123").unwrap().to_string(), "2023-10-11T05:06:40.123Z");
+        assert_eq!(Timestamp::parse("-1").unwrap().to_string(), "1969-12-31T23:59:59.999Z");
+        assert_eq!(Timestamp::parse("2024-02-29T00:00:00Z").unwrap().to_string(), "2024-02-29T00:00:00.000Z");
+        assert_eq!(Timestamp::parse("0000-01-01T00:00:00Z").unwrap().millis(), MIN_MILLIS);
+        assert_eq!(Timestamp::parse("9999-12-31T23:59:59.999Z").unwrap().millis(), MAX_MILLIS);
+
+        for timestamp in [
+            "", "now", "2023-10-11", "2023-10-11T05:06:40", "2023-13-01T00:00:00Z", "2023-02-29T00:00:00Z",
+            "2023-10-11T24:00:00Z", "2023-10-11T05:06:60Z", "2023-10-11T05:06:40.Z", "2023-10-11T05:06:40+2:00",
+            "23-10-11T05:06:40Z", "0000-01-01T00:00:00+01:00", "253402300800000"
+        ] {
+            assert_eq!(Timestamp::parse(timestamp), None, "{}", timestamp);
+        }
+    }
+
+    #[test]
+    fn test_ordering() {
+        assert!(Timestamp::parse("2023-10-11T05:06:40Z").unwrap() > Timestamp::parse("2023-10-11T06:06:39+02:00").unwrap());
+        assert_eq!(Timestamp::parse("2023-10-11T05:06:40Z").unwrap(), Timestamp::parse("2023-10-11T07:06:40+02:00").unwrap());
+    }
+}
@@ -0,0 +1,74 @@
+use crate::schemas::{ThrottleLimit, ThrottleResult};
+
+
+/// The state of a token bucket of a rate limiter, ie. the tokens at the last update (they're refilled continuously since then).
+/// The limits aren't stored, they're passed with every request.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TokenBucket {
+    tokens: f64,
+    /// The time of the last update in epoch milliseconds, a bucket that was never updated is full.
+    updated_at: Option<i64>
+}
+
+impl TokenBucket {
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Refills the bucket and takes tokens from it if there are enough.
+    ///
+    /// # Arguments:
+    /// * `limit`: The capacity (max burst) and the refill rate of the bucket.
+    /// * `cost`: The amount of tokens to take.
+    /// * `now`: The current time in epoch milliseconds.
+    ///
+    /// # Returns:
+    /// If the tokens were taken, the remaining tokens and when to retry.
+    pub fn take(&mut self, limit: &ThrottleLimit, cost: u64, now: i64) -> ThrottleResult {
+        let capacity: f64 = limit.max_burst as f64;
+        let tokens_per_milli: f64 = limit.rate as f64 / limit.period as f64;
+
+        self.tokens = match self.updated_at {
+            // the clock can go backwards, the bucket is never drained by it
+            Some(updated_at) => (self.tokens + now.saturating_sub(updated_at).max(0) as f64 * tokens_per_milli).min(capacity),
+            None => capacity
+        };
+        self.updated_at = Some(self.updated_at.map_or(now, |updated_at| updated_at.max(now)));
+
+        let cost: f64 = cost as f64;
+        if cost <= self.tokens {
+            self.tokens -= cost;
+            return ThrottleResult { allowed: true, remaining: self.tokens as u64, retry_after: Some(0) };
+        }
+
+        // a cost above the capacity is never allowed
+        let retry_after: Option<u64> = (cost <= capacity).then(|| ((cost - self.tokens) * limit.period as f64 / limit.rate as f64).ceil() as u64);
+        ThrottleResult { allowed: false, remaining: self.tokens as u64, retry_after }
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take() {
+        // 5 tokens, refilled with 1 token per second
+        let limit: ThrottleLimit = ThrottleLimit { max_burst: 5, rate: 1, period: 1000 };
+        let mut bucket: TokenBucket = TokenBucket::new();
+
+        assert_eq!(bucket.take(&limit, 3, 0), ThrottleResult { allowed: true, remaining: 2, retry_after: Some(0) });
+        assert_eq!(bucket.take(&limit, 2, 0), ThrottleResult { allowed: true, remaining: 0, retry_after: Some(0) });
+        assert_eq!(bucket.take(&limit, 1, 0), ThrottleResult { allowed: false, remaining: 0, retry_after: Some(1000) });
+        assert_eq!(bucket.take(&limit, 2, 500), ThrottleResult { allowed: false, remaining: 0, retry_after: Some(1500) });
+
+        // the bucket is refilled continuously but never above the max burst
+        assert_eq!(bucket.take(&limit, 1, 1000), ThrottleResult { allowed: true, remaining: 0, retry_after: Some(0) });
+        assert_eq!(bucket.take(&limit, 0, 60_000), ThrottleResult { allowed: true, remaining: 5, retry_after: Some(0) });
+
+        assert_eq!(bucket.take(&limit, 6, 60_000), ThrottleResult { allowed: false, remaining: 5, retry_after: None });
+    }
+}
@@ -0,0 +1,257 @@
+use std::fmt;
+
+
+/// The first character of every encoded tuple key. Plain keys can't start with it.
+pub const TUPLE_MARKER: char = '\u{1}';
+
+const INT_TAG: char = '\u{2}';
+const STR_TAG: char = '\u{3}';
+const STR_TERMINATOR: char = '\u{0}';
+const ESCAPE: char = '\u{1}';
+
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TupleSegment {
+    Int(i64),
+    Str(String)
+}
+
+
+/// A composite key like ``(7, 1697000000, "x")``, ordered segment by segment.
+/// Integers are ordered before strings and a tuple is ordered before the tuples it is a prefix of.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tuple {
+    segments: Vec<TupleSegment>
+}
+
+impl Tuple {
+
+    /// Parses a tuple, e.g ``(7, 1697000000, "x")``. Segments are 64 bit integers or strings in quotes
+    /// (which can't contain quotes themselves).
+    ///
+    /// # Arguments:
+    /// * `tuple`: The tuple string.
+    ///
+    /// # Returns:
+    /// The tuple or `None` if the string isn't a valid tuple with at least one segment.
+    pub fn parse(tuple: &str) -> Option<Self> {
+        let mut rest: &str = tuple.strip_prefix('(')?.strip_suffix(')')?;
+        let mut segments: Vec<TupleSegment> = Vec::new();
+
+        loop {
+            rest = rest.trim_start_matches(' ');
+            let segment: &str = if rest.starts_with('"') {
+                let end: usize = rest[1..].find('"')? + 2;
+                let (segment, remaining) = rest.split_at(end);
+                rest = remaining;
+                segment
+            }
+            else {
+                let end: usize = rest.find(',').unwrap_or(rest.len());
+                let (segment, remaining) = rest.split_at(end);
+                rest = remaining;
+                segment.trim_end_matches(' ')
+            };
+
+            segments.push(match segment.strip_prefix('"').and_then(|segment| segment.strip_suffix('"')) {
+                Some(string) => TupleSegment::Str(string.to_owned()),
+                None => TupleSegment::Int(segment.parse::<i64>().ok()?)
+            });
+
+            rest = rest.trim_start_matches(' ');
+            if rest.is_empty() {
+                return Some(Self { segments });
+            }
+            rest = rest.strip_prefix(',')?;
+        }
+    }
+
+    pub fn segments(&self) -> &[TupleSegment] {
+        &self.segments
+    }
+
+    /// Encodes the tuple into a string whose bytewise order is the order of the tuples.
+    /// Integers are stored as 16 hex digits with the sign bit flipped, strings are terminated
+    /// by a null character (null characters and the escape character inside are escaped).
+    pub fn encode(&self) -> String {
+        let mut encoded: String = String::from(TUPLE_MARKER);
+        for segment in &self.segments {
+            match segment {
+                TupleSegment::Int(value) => encode_int(&mut encoded, *value),
+                TupleSegment::Str(value) => {
+                    encode_str(&mut encoded, value);
+                    encoded.push(STR_TERMINATOR);
+                }
+            }
+        }
+        encoded
+    }
+
+    /// Encodes the beginning of a tuple, e.g ``(7, 1697000000, "x`` of ``(7, 1697000000, "xy")``, so that the encodings
+    /// of all tuples starting with it start with the encoded prefix. Only complete segments and the beginning of a string
+    /// are encoded, the rest is left out (e.g ``(7, 16`` is encoded like ``(7,``).
+    ///
+    /// # Arguments:
+    /// * `prefix`: The beginning of a tuple.
+    ///
+    /// # Returns:
+    /// The encoded prefix or `None` if the string doesn't start like a tuple.
+    pub fn encode_prefix(prefix: &str) -> Option<String> {
+        let mut rest: &str = prefix.strip_prefix('(')?;
+        let mut encoded: String = String::from(TUPLE_MARKER);
+
+        loop {
+            rest = rest.trim_start_matches(' ');
+            if let Some(string) = rest.strip_prefix('"') {
+                let Some(end) = string.find('"') else {
+                    encode_str(&mut encoded, string);
+                    return Some(encoded);
+                };
+                encode_str(&mut encoded, &string[..end]);
+                encoded.push(STR_TERMINATOR);
+                rest = string[end + 1..].trim_start_matches(' ');
+            }
+            // an integer is only complete if a comma follows it, e.g 16 could be the beginning of 1697000000
+            else {
+                let Some((integer, remaining)) = rest.split_once(',') else {
+                    return Some(encoded);
+                };
+                let Ok(value) = integer.trim_end_matches(' ').parse::<i64>() else {
+                    return Some(encoded);
+                };
+                encode_int(&mut encoded, value);
+                rest = remaining;
+                continue;
+            }
+
+            match rest.strip_prefix(',') {
+                Some(remaining) => rest = remaining,
+                None => return Some(encoded)
+            }
+        }
+    }
+
+    /// Decodes a tuple encoded with `encode`.
+    ///
+    /// # Returns:
+    /// The tuple or `None` if the string isn't an encoded tuple.
+    pub fn decode(encoded: &str) -> Option<Self> {
+        let mut characters = encoded.strip_prefix(TUPLE_MARKER)?.chars();
+        let mut segments: Vec<TupleSegment> = Vec::new();
+
+        while let Some(tag) = characters.next() {
+            match tag {
+                INT_TAG => {
+                    let digits: String = characters.by_ref().take(16).collect();
+                    let value: u64 = u64::from_str_radix(&digits, 16).ok()?;
+                    segments.push(TupleSegment::Int((value ^ (1 << 63)) as i64));
+                }
+                STR_TAG => {
+                    let mut value: String = String::new();
+                    loop {
+                        match characters.next()? {
+                            STR_TERMINATOR => break,
+                            ESCAPE => value.push(if characters.next()? == '\u{1}' { STR_TERMINATOR } else { ESCAPE }),
+                            character => value.push(character)
+                        }
+                    }
+                    segments.push(TupleSegment::Str(value));
+                }
+                _ => return None
+            }
+        }
+
+        Some(Self { segments })
+    }
+
+    /// Checks if a key is an encoded tuple.
+    pub fn is_encoded(key: &str) -> bool {
+        key.starts_with(TUPLE_MARKER)
+    }
+
+    /// Turns an encoded tuple into the upper bound of a range, so that the range includes every
+    /// tuple the bound is a prefix of, e.g ``(7)`` includes ``(7, 1697000000, "x")``.
+    pub fn encoded_upper_bound(encoded: &str) -> String {
+        format!("{}{}", encoded, char::MAX)
+    }
+}
+
+fn encode_int(encoded: &mut String, value: i64) {
+    encoded.push(INT_TAG);
+    encoded.push_str(&format!("{:016X}", (value as u64) ^ (1 << 63)));
+}
+
+/// Encodes a string segment without its terminator, null characters and the escape character are escaped.
+fn encode_str(encoded: &mut String, value: &str) {
+    encoded.push(STR_TAG);
+    for character in value.chars() {
+        match character {
+            STR_TERMINATOR => encoded.push_str("\u{1}\u{1}"),
+            ESCAPE => encoded.push_str("\u{1}\u{2}"),
+            character => encoded.push(character)
+        }
+    }
+}
+
+impl fmt::Display for Tuple {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let segments: Vec<String> = self.segments
+            .iter()
+            .map(|segment| match segment {
+                TupleSegment::Int(value) => value.to_string(),
+                TupleSegment::Str(value) => format!("\"{}\"", value),
+            })
+            .collect();
+        write!(f, "({})", segments.join(", "))
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let tuple = Tuple::parse("(7,  -1697000000 , \"a, b\")").unwrap();
+        assert_eq!(tuple.segments, vec![TupleSegment::Int(7), TupleSegment::Int(-1697000000), TupleSegment::Str("a, b".to_string())]);
+        assert_eq!(tuple.to_string(), "(7, -1697000000, \"a, b\")");
+
+        for tuple in ["()", "(7", "7)", "(7,)", "(, 7)", "(7 8)", "(\"x)", "(x)", "(\"x\" 7)", "(1.5)"] {
+            assert_eq!(Tuple::parse(tuple), None, "{}", tuple);
+        }
+    }
+
+    #[test]
+    fn test_encoding() {
+        let tuple = Tuple::parse("(7, -1, \"x\u{0}\u{1}y\")").unwrap();
+        assert_eq!(Tuple::decode(&tuple.encode()), Some(tuple));
+
+        // the bytewise order of the encodings is the order of the tuples
+        let ordered: Vec<&str> = vec![
+            "(-5)", "(7)", "(7, 1)", "(7, 1, \"\")", "(7, 1, \"a\")", "(7, 1, \"a\u{0}\")", "(7, 1, \"ab\")", "(7, 2)", "(7, \"a\")", "(10)", "(\"\")"
+        ];
+        let encoded: Vec<String> = ordered.iter().map(|tuple| Tuple::parse(tuple).unwrap().encode()).collect();
+        assert!(encoded.windows(2).all(|pair| pair[0] < pair[1]));
+
+        // a prefix used as upper bound includes the tuples it is a prefix of
+        let upper_bound: String = Tuple::encoded_upper_bound(&Tuple::parse("(7, 1)").unwrap().encode());
+        assert!(Tuple::parse("(7, 1, \"zzz\")").unwrap().encode() < upper_bound);
+        assert!(Tuple::parse("(7, 2)").unwrap().encode() > upper_bound);
+    }
+
+    #[test]
+    fn test_encode_prefix() {
+        let encoded: String = Tuple::parse("(7, 1697000000, \"xy\", 3)").unwrap().encode();
+        for prefix in ["(", "(7", "(7, ", "(7, 16", "(7, 1697000000,", "(7, 1697000000, \"", "(7, 1697000000, \"x", "(7, 1697000000, \"xy\"", "(7, 1697000000, \"xy\", 3"] {
+            assert!(encoded.starts_with(&Tuple::encode_prefix(prefix).unwrap()), "{}", prefix);
+        }
+
+        // only complete segments and the beginning of a string narrow the prefix
+        assert_eq!(Tuple::encode_prefix("(7, 16"), Tuple::encode_prefix("(7,"));
+        assert_eq!(Tuple::encode_prefix("(7, 1697000000, \"x"), Some(format!("{}\u{3}x", Tuple::parse("(7, 1697000000)").unwrap().encode())));
+        assert!(!Tuple::parse("(7, 1697000000, \"a\")").unwrap().encode().starts_with(&Tuple::encode_prefix("(7, 1697000000, \"x").unwrap()));
+        assert_eq!(Tuple::encode_prefix("key"), None);
+    }
+}
@@ -118,14 +118,78 @@ pub fn print_help() {
         ("AUTH", "Authenticating on the server."),
         ("PING", "Checks if server is running (responses with 'PONG' if so)."),
         ("SET <key> <value>", "Inserts a new key value pair."),
-        ("SET MANY <key1> <value1>, ...", "Inserts multiple key value pairs."),
+        ("SET <key> <value> EPHEMERAL", "Inserts a key value pair that is deleted when this connection closes."),
+        ("SET MANY <key1> <value1>, ...", "Inserts multiple key value pairs (all or nothing)."),
         ("GET <key>", "Gets a value by key."),
-        ("GET MANY <key1>, ... <keyN>", "Gets multiple values by their key."),
-        ("GET RANGE <lower key> <upper key>", "Gets values in a range of keys."),
+        ("GET <key> WAIT <timeout>", "Waits until a key is written and gets its new value (timeout in seconds, 0 waits forever)."),
+        ("GET MANY <key1>, ... <keyN> [NULLS]", "Gets multiple values by their key (NULLS returns NULL for missing keys)."),
+        ("GET RANGE <lower key> <upper key>", "Gets values in a range of keys (partial tuple keys like '(7)' include all tuples they are a prefix of)."),
+        ("GET RANGE <lower key> <upper key> WHERE <predicate>", "Gets the values in a range that match, e.g 'value > 10', 'value AFTER <timestamp>' or '$.field = 3'."),
+        ("FIRST", "Gets the entry with the smallest key."),
+        ("LAST", "Gets the entry with the biggest key."),
+        ("NEXT <key>", "Gets the entry with the next bigger key."),
+        ("PREV <key>", "Gets the entry with the next smaller key."),
+        ("FLOOR <key>", "Gets the entry with the biggest key smaller than or equal to the given one."),
+        ("CEIL <key>", "Gets the entry with the smallest key bigger than or equal to the given one."),
+        ("AGG SUM|AVG|MIN|MAX|COUNT <lower key> <upper key>", "Aggregates the values in a range of keys (numeric types only, MIN|MAX|COUNT on TIMESTAMP)."),
         ("DEL <key>", "Deletes a value by key."),
-        ("DEL MANY <key1>, ... <keyN>", "Deletes multiple values by their key."),
-        ("DEL RANGE <lower key> <upper key>", "Deletes values in a range of keys."),
+        ("DEL MANY <key1>, ... <keyN>", "Deletes multiple values by their key and returns the amount of deleted keys."),
+        ("DEL RANGE <lower key> <upper key>", "Deletes values in a range of keys and returns the amount of deleted keys."),
+        ("RENAME <key> <new key> [NX]", "Renames a key (NX: fails if the new key exists)."),
+        ("RENAME PREFIX <prefix> <new prefix>", "Moves every key under a prefix to a new prefix."),
+        ("COPY <key> <new key> [REPLACE]", "Copies a value to a new key (REPLACE: overwrites an existing key)."),
+        ("JSON.GET <key> <path>", "Gets the value at a JSON path, e.g '$.user.tags[0]' (JSON only)."),
+        ("JSON.SET <key> <path> <value>", "Sets the value at a JSON path (JSON only)."),
+        ("JSON.DEL <key> <path>", "Deletes the value at a JSON path (JSON only)."),
+        ("JSON.MERGE <key> <patch>", "Applies a JSON merge patch (RFC 7396) to a document (JSON only)."),
+        ("STRLEN <key>", "Returns the length of a value in bytes (STR and BYTES only)."),
+        ("SCHEMA GET", "Shows the active JSON schema (JSON only)."),
+        ("INDEX CREATE <name> ON <path>", "Creates a secondary index over the values at a JSON path (JSON only)."),
+        ("INDEX DROP <name>", "Drops a secondary index."),
+        ("FIND <index> = <value>", "Gets the entries whose indexed value equals a JSON scalar."),
+        ("FIND <index> BETWEEN <lower> <upper>", "Gets the entries whose indexed value lies in a range."),
+        ("FIND VALUE <value>", "Gets the entries holding a value (needs the server's value index)."),
+        ("FIND VALUE BETWEEN <lower> <upper>", "Gets the entries whose value lies in a range (numeric types only)."),
+        ("LPUSH|RPUSH <key> <value1> ... <valueN>", "Pushes values to the front or back of a list and returns its length."),
+        ("LPOP|RPOP <key>", "Pops the first or last value of a list."),
+        ("BLPOP <key> <timeout>", "Pops the first value of a list, waiting until there is one (timeout in seconds, 0 waits forever)."),
+        ("LRANGE <key> <start> <stop>", "Gets the values of a list between two indexes (inclusive, -1 is the last value)."),
+        ("SADD|SREM <key> <value1> ... <valueN>", "Adds values to or removes values from a set."),
+        ("SMEMBERS <key>", "Gets the members of a set."),
+        ("SISMEMBER <key> <value>", "Checks if a value is a member of a set."),
+        ("HSET <key> <field1> <value1> ... <fieldN> <valueN>", "Sets fields of a hash."),
+        ("HGET <key> <field>", "Gets the value of a field of a hash."),
+        ("HDEL <key> <field1> ... <fieldN>", "Deletes fields of a hash."),
+        ("HGETALL <key>", "Gets all fields of a hash."),
+        ("ZADD <key> <score1> <value1> ... <scoreN> <valueN>", "Adds values to a sorted set or updates their scores."),
+        ("ZINCRBY <key> <increment> <value>", "Increments the score of a value in a sorted set."),
+        ("ZRANGE <key> <start> <stop> [REV]", "Gets the values of a sorted set between two ranks (REV: ranks count from the highest score)."),
+        ("ZRANGE <key> <min> <max> BYSCORE", "Gets the values of a sorted set with a score between min and max."),
+        ("ZRANK <key> <value> [REV]", "Gets the rank of a value in a sorted set."),
+        ("ZREM <key> <value1> ... <valueN>", "Removes values from a sorted set."),
+        ("ZCOUNT <key> <min> <max>", "Counts the values of a sorted set with a score between min and max."),
+        ("TS.ADD <key> <timestamp>|* <value> [RETENTION <ms>]", "Appends a sample to a time series (RETENTION drops samples older than the newest one minus ms)."),
+        ("TS.RANGE <key> <from>|- <to>|+ [AGG <aggregation> BUCKET <ms>]", "Gets the samples of a time series, optionally aggregated per bucket of ms."),
+        ("PFADD <key> [ERROR <rate>] <value1> ... <valueN>", "Adds values to a HyperLogLog (ERROR sets its error rate when it's created)."),
+        ("PFCOUNT <key1> ... <keyN>", "Estimates the amount of distinct values added to HyperLogLogs."),
+        ("PFMERGE <destination> <source1> ... <sourceN>", "Merges HyperLogLogs into the destination."),
+        ("BF.RESERVE <key> <error rate> <capacity>", "Creates a Bloom filter sized for a capacity and an error rate."),
+        ("BF.ADD <key> <value>", "Adds a value to a Bloom filter."),
+        ("BF.EXISTS <key> <value>", "Checks if a value was added to a Bloom filter (may be a false positive)."),
+        ("GEO.ADD <key> <value> <lon> <lat>", "Adds a value at a position to a geospatial set (or moves it)."),
+        ("GEO.RADIUS <key> <lon> <lat> <radius> m|km|mi|ft [COUNT <n>]", "Gets the values within a radius of a position and their distances, the nearest first."),
+        ("GEO.BOX <key> <min lon> <min lat> <max lon> <max lat> [COUNT <n>]", "Gets the values inside a box and their positions."),
+        ("GEO.DIST <key> <value1> <value2> [m|km|mi|ft]", "Gets the distance between two values of a geospatial set."),
+        ("THROTTLE <key> <max burst> <rate> <period ms> [cost]", "Takes tokens (default 1) from a rate limiter, returns allowed|denied, the remaining tokens and the retry after in ms."),
+        ("LOCK <name> <owner> <ttl ms>", "Acquires a lock that expires after ttl ms, returns a fencing token (NONE if the lock is held)."),
+        ("UNLOCK <name> <owner>", "Releases a lock held by the owner."),
+        ("RENEW <name> <owner> <ttl ms>", "Extends a lock held by the owner to expire ttl ms from now."),
+        ("LOCKINFO <name>", "Gets the owner, the fencing token and the remaining ms of a lock."),
+        ("SEQ.NEXT <name> [count]", "Reserves count (default 1) IDs from a sequence and returns the first one."),
+        ("SEQ.SET <name> <last id>", "Sets the last ID handed out by a sequence (it can only be increased)."),
+        ("SEQ.GET <name>", "Gets the last ID handed out by a sequence."),
         ("EXISTS <key>", "Checks if a key exists or not."),
+        ("KEYS MATCH <pattern> [REGEX] [COUNT <n>]", "Lists keys matching a glob (or regex) pattern."),
         ("LEN", "Returns the amount of entries in the database."),
         ("CLEAR", "Deletes all entries in the database."),
         ("SHUTDOWN", "Shuts down the server."),
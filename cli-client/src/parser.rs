@@ -85,7 +85,7 @@ pub fn parse_response(response: &str) -> Result<ParsedResponse, String> {
     // check if the response is OK, ERROR or something invalid
     match response_parts[1] {
         OK_IDENTIFIER => {
-            if response_parts[2].starts_with("GET") {
+            if ["GET", "FIRST", "LAST", "NEXT", "PREV", "FLOOR", "CEIL", "JSON.GET", "FIND", "LPOP", "RPOP", "BLPOP", "LRANGE", "SMEMBERS", "HGET", "ZRANGE", "GEO.RADIUS", "GEO.BOX"].iter().any(|command| response_parts[2].starts_with(command)) || response_parts[2] == "TS.RANGE" {
                 if response_parts.len() != 6 {
                     return Err(r#"Failed to parse response: Expected GET OK responses to consist of six parts (CASP + OK + <command> + <type> + <value> + \n)."#.to_string());
                 }
@@ -101,9 +101,18 @@ pub fn parse_response(response: &str) -> Result<ParsedResponse, String> {
             }
             else if response_parts[2].starts_with("EXISTS") || 
                     response_parts[2].starts_with("PING") || 
-                    response_parts[2].starts_with("LEN") {
+                    response_parts[2].starts_with("LEN") ||
+                    response_parts[2].starts_with("KEYS") ||
+                    response_parts[2].starts_with("AGG") ||
+                    response_parts[2] == "DEL MANY" ||
+                    response_parts[2] == "DEL RANGE" ||
+                    response_parts[2] == "RENAME PREFIX" ||
+                    response_parts[2] == "SCHEMA GET" ||
+                    response_parts[2] == "STRLEN" ||
+                    response_parts[2].starts_with("TS.RANGE ") ||
+                    ["LPUSH", "RPUSH", "SADD", "SREM", "SISMEMBER", "HSET", "HDEL", "ZADD", "ZINCRBY", "ZRANK", "ZREM", "ZCOUNT", "TS.ADD", "PFADD", "PFCOUNT", "BF.ADD", "BF.EXISTS", "THROTTLE", "LOCK", "UNLOCK", "RENEW", "LOCKINFO", "SEQ.NEXT", "SEQ.GET", "GEO.ADD", "GEO.DIST"].contains(&response_parts[2]) {
                 if response_parts.len() != 5 {
-                    return Err(r#"Failed to parse response: Expected EXISTS, PING, LEN OK responses to consist of five parts (CASP + OK + <command> + <message> + \n)."#.to_string());
+                    return Err(r#"Failed to parse response: Expected EXISTS, PING, LEN, KEYS, AGG, DEL MANY, DEL RANGE, RENAME PREFIX, SCHEMA GET, STRLEN, LPUSH, RPUSH, SADD, SREM, SISMEMBER, HSET, HDEL, ZADD, ZINCRBY, ZRANK, ZREM, ZCOUNT, TS.ADD, TS.RANGE <aggregation>, PFADD, PFCOUNT, BF.ADD, BF.EXISTS, THROTTLE, LOCK, UNLOCK, RENEW, LOCKINFO, SEQ.NEXT, SEQ.GET, GEO.ADD, GEO.DIST OK responses to consist of five parts (CASP + OK + <command> + <message> + \n)."#.to_string());
                 }
 
                 Ok(ParsedResponse {
@@ -192,9 +201,48 @@ mod tests {
             status: ResponseStatus::OK, command: Some("GET MANY".to_string()), value: Some("10,20,30".to_string())
         }));
 
+        let parsed_response = parse_response("CASP/OK/NEXT/INT/\"key2\" 5/\n");
+        assert_eq!(parsed_response, Ok(ParsedResponse { status: ResponseStatus::OK, command: Some("NEXT".to_string()), value: Some("\"key2\" 5".to_string()) }));
+
+        let parsed_response = parse_response("CASP/OK/AGG SUM/42/\n");
+        assert_eq!(parsed_response, Ok(ParsedResponse { status: ResponseStatus::OK, command: Some("AGG SUM".to_string()), value: Some("42".to_string()) }));
+
+        let parsed_response = parse_response("CASP/OK/DEL RANGE/3/\n");
+        assert_eq!(parsed_response, Ok(ParsedResponse { status: ResponseStatus::OK, command: Some("DEL RANGE".to_string()), value: Some("3".to_string()) }));
+
+        let parsed_response = parse_response("CASP/OK/GET MANY/INT/1,NULL,3/\n");
+        assert_eq!(parsed_response, Ok(ParsedResponse { status: ResponseStatus::OK, command: Some("GET MANY".to_string()), value: Some("1,NULL,3".to_string()) }));
+
+        let parsed_response = parse_response("CASP/OK/FIND/JSON/\"job1\" \"{\\\"a\\\":1}\"/\n");
+        assert_eq!(parsed_response, Ok(ParsedResponse { status: ResponseStatus::OK, command: Some("FIND".to_string()), value: Some("\"job1\" \"{\\\"a\\\":1}\"".to_string()) }));
+
         let parsed_response = parse_response("CASP/OK/LEN/10/\n");
         assert_eq!(parsed_response, Ok(ParsedResponse { status: ResponseStatus::OK, command: Some("LEN".to_string()), value: Some("10".to_string()) }));
 
+        let parsed_response = parse_response("CASP/OK/STRLEN/5/\n");
+        assert_eq!(parsed_response, Ok(ParsedResponse { status: ResponseStatus::OK, command: Some("STRLEN".to_string()), value: Some("5".to_string()) }));
+
+        let parsed_response = parse_response("CASP/OK/LPUSH/3/\n");
+        assert_eq!(parsed_response, Ok(ParsedResponse { status: ResponseStatus::OK, command: Some("LPUSH".to_string()), value: Some("3".to_string()) }));
+
+        let parsed_response = parse_response("CASP/OK/BLPOP/INT/NONE/\n");
+        assert_eq!(parsed_response, Ok(ParsedResponse { status: ResponseStatus::OK, command: Some("BLPOP".to_string()), value: Some("NONE".to_string()) }));
+
+        let parsed_response = parse_response("CASP/OK/HGETALL/INT/\"age\" 30,\"height\" 180/\n");
+        assert_eq!(parsed_response, Ok(ParsedResponse { status: ResponseStatus::OK, command: Some("HGETALL".to_string()), value: Some("\"age\" 30,\"height\" 180".to_string()) }));
+
+        let parsed_response = parse_response("CASP/OK/TS.RANGE/FLOAT/1000 21.5,2000 22/\n");
+        assert_eq!(parsed_response, Ok(ParsedResponse { status: ResponseStatus::OK, command: Some("TS.RANGE".to_string()), value: Some("1000 21.5,2000 22".to_string()) }));
+
+        let parsed_response = parse_response("CASP/OK/TS.RANGE AVG/0 21.75/\n");
+        assert_eq!(parsed_response, Ok(ParsedResponse { status: ResponseStatus::OK, command: Some("TS.RANGE AVG".to_string()), value: Some("0 21.75".to_string()) }));
+
+        let parsed_response = parse_response("CASP/OK/PFCOUNT/1498/\n");
+        assert_eq!(parsed_response, Ok(ParsedResponse { status: ResponseStatus::OK, command: Some("PFCOUNT".to_string()), value: Some("1498".to_string()) }));
+
+        let parsed_response = parse_response("CASP/OK/KEYS/\"key1\",\"key2\"/\n");
+        assert_eq!(parsed_response, Ok(ParsedResponse { status: ResponseStatus::OK, command: Some("KEYS".to_string()), value: Some("\"key1\",\"key2\"".to_string()) }));
+
         let parsed_response = parse_response("CASP/ERROR/An error appeared./\n");
         assert_eq!(parsed_response, Ok(ParsedResponse { status: ResponseStatus::ERROR, command: None, value: Some("An error appeared.".to_string()) }));
 
@@ -224,7 +272,7 @@ mod tests {
         assert_eq!(parsed_response.unwrap_err(), r#"Failed to parse response: Expected GET OK responses to consist of six parts (CASP + OK + <command> + <type> + <value> + \n)."#);
 
         let parsed_response = parse_response("CASP/OK/EXISTS/\n");
-        assert_eq!(parsed_response.unwrap_err(), r#"Failed to parse response: Expected EXISTS, PING, LEN OK responses to consist of five parts (CASP + OK + <command> + <message> + \n)."#);
+        assert_eq!(parsed_response.unwrap_err(), r#"Failed to parse response: Expected EXISTS, PING, LEN, KEYS, AGG, DEL MANY, DEL RANGE, RENAME PREFIX, SCHEMA GET, STRLEN, LPUSH, RPUSH, SADD, SREM, SISMEMBER, HSET, HDEL, ZADD, ZINCRBY, ZRANK, ZREM, ZCOUNT, TS.ADD, TS.RANGE <aggregation>, PFADD, PFCOUNT, BF.ADD, BF.EXISTS, THROTTLE, LOCK, UNLOCK, RENEW, LOCKINFO, SEQ.NEXT, SEQ.GET, GEO.ADD, GEO.DIST OK responses to consist of five parts (CASP + OK + <command> + <message> + \n)."#);
 
         let parsed_response = parse_response("CASP/ERROR/\n");
         assert_eq!(parsed_response.unwrap_err(), r#"Failed to parse response: Expected ERROR responses to consist of four parts (CASP + ERROR + message + \n)."#);